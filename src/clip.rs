@@ -0,0 +1,1066 @@
+use std::{
+    fs::File,
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{DecoderOptions, CODEC_TYPE_NULL},
+    formats::FormatOptions,
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+};
+
+use crate::{
+    raw, remote, riff,
+    strings::{self, Language, Text},
+};
+
+const LOUDNESS_GATE_DB: f32 = -60.0;
+
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "aac", "aif", "aiff", "flac", "m4a", "mp3", "mp4", "oga", "ogg", "wav",
+];
+
+#[derive(Clone)]
+pub struct SampleClip {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub samples: Arc<Vec<f32>>,
+    pub root_midi: Option<i32>,
+    pub loop_frames: Option<(usize, usize)>,
+    pub loudness_db: Option<f32>,
+    pub decoded_frames: usize,
+    // The source has more audio past the requested length.
+    pub truncated: bool,
+    pub skipped_packets: usize,
+    pub track_id: Option<u32>,
+    pub tracks: Vec<TrackInfo>,
+    pub cue_frames: Vec<usize>,
+}
+
+#[derive(Clone, Debug)]
+pub struct TrackInfo {
+    pub id: u32,
+    pub codec: String,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<usize>,
+}
+
+impl TrackInfo {
+    pub fn label(&self, language: Language) -> String {
+        let mut label = strings::fill_all(
+            strings::get(language, Text::TrackLabel),
+            &[&self.id, &self.codec],
+        );
+        if let Some(rate) = self.sample_rate {
+            label.push_str(&format!(", {rate} Hz"));
+        }
+        if let Some(channels) = self.channels {
+            label.push_str(&format!(", {channels} ch"));
+        }
+        label
+    }
+}
+
+impl SampleClip {
+    pub fn from_file(
+        path: &Path,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        track_id: Option<u32>,
+        rate_override: Option<u32>,
+    ) -> Result<Self> {
+        let file = File::open(path)
+            .with_context(|| format!("failed to open selected file: {}", path.display()))?;
+        let sampler_info = riff::read_sampler_info(path)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        let extension = path.extension().and_then(|x| x.to_str());
+        Self::decode(
+            Box::new(file),
+            extension,
+            sampler_info,
+            duration_ms,
+            preserve_stereo,
+            track_id,
+            rate_override,
+        )
+    }
+
+    pub fn from_memory(
+        source: &MemoryClip,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        track_id: Option<u32>,
+        rate_override: Option<u32>,
+    ) -> Result<Self> {
+        let sampler_info = riff::sampler_info_from_bytes(&source.bytes)
+            .ok()
+            .flatten()
+            .unwrap_or_default();
+        Self::decode(
+            Box::new(Cursor::new(source.bytes.to_vec())),
+            source.extension.as_deref(),
+            sampler_info,
+            duration_ms,
+            preserve_stereo,
+            track_id,
+            rate_override,
+        )
+    }
+
+    pub fn from_raw_pcm(
+        path: &Path,
+        format: &raw::RawPcmFormat,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        rate_override: Option<u32>,
+    ) -> Result<Self> {
+        let interleaved = raw::read(path, format)?;
+        let sample_rate = rate_override.unwrap_or(format.sample_rate);
+        let channels = usize::from(format.channels);
+        let out_channels = if preserve_stereo && channels >= 2 {
+            2
+        } else {
+            1
+        };
+        let target_frames = frames_for_ms(sample_rate, duration_ms);
+
+        let mut out_samples: Vec<f32> = Vec::with_capacity(target_frames * out_channels);
+        for frame in interleaved.chunks_exact(channels).take(target_frames) {
+            if out_channels == 2 {
+                out_samples.extend_from_slice(&frame[..2]);
+            } else {
+                out_samples.push(frame.iter().sum::<f32>() / channels as f32);
+            }
+        }
+        let decoded_frames = out_samples.len() / out_channels;
+        let truncated = interleaved.len() / channels > target_frames;
+        out_samples.resize(target_frames * out_channels, 0.0);
+
+        Ok(Self {
+            sample_rate,
+            channels: out_channels as u16,
+            loudness_db: measure_loudness(&out_samples, out_channels, sample_rate),
+            samples: Arc::new(out_samples),
+            root_midi: None,
+            loop_frames: None,
+            decoded_frames,
+            truncated,
+            skipped_packets: 0,
+            track_id: None,
+            tracks: Vec::new(),
+            cue_frames: Vec::new(),
+        })
+    }
+
+    fn decode(
+        source: Box<dyn MediaSource>,
+        extension: Option<&str>,
+        sampler_info: riff::SamplerInfo,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        track_id: Option<u32>,
+        rate_override: Option<u32>,
+    ) -> Result<Self> {
+        let mss = MediaSourceStream::new(source, Default::default());
+
+        let mut hint = Hint::new();
+        if let Some(ext) = extension {
+            hint.with_extension(ext);
+        }
+
+        let probed = symphonia::default::get_probe().format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?;
+
+        let mut format = probed.format;
+        let codecs = symphonia::default::get_codecs();
+        let tracks: Vec<TrackInfo> = format
+            .tracks()
+            .iter()
+            .filter(|track| track.codec_params.codec != CODEC_TYPE_NULL)
+            .map(|track| TrackInfo {
+                id: track.id,
+                codec: codecs
+                    .get_codec(track.codec_params.codec)
+                    .map_or("unknown", |codec| codec.short_name)
+                    .to_string(),
+                sample_rate: track.codec_params.sample_rate,
+                channels: track.codec_params.channels.map(|c| c.count()),
+            })
+            .collect();
+        let track = match track_id {
+            Some(id) => format
+                .tracks()
+                .iter()
+                .find(|track| track.id == id)
+                .ok_or_else(|| anyhow!("audio track {id} not found"))?,
+            None => format
+                .default_track()
+                .ok_or_else(|| anyhow!("no playable audio track found"))?,
+        };
+        let selected_track = track.id;
+
+        let codec_params = &track.codec_params;
+        let mut decoder = codecs.make(codec_params, &DecoderOptions::default())?;
+
+        let mut sample_rate = codec_params
+            .sample_rate
+            .or(sampler_info.sample_rate)
+            .ok_or_else(|| anyhow!("audio file missing sample rate"))?;
+
+        let out_channels = match codec_params.channels.map(|c| c.count()) {
+            Some(count) if preserve_stereo && count >= 2 => 2,
+            _ => 1,
+        };
+        // An override re-slices the bite as if the file really ran at that rate.
+        let target_frames = frames_for_ms(rate_override.unwrap_or(sample_rate), duration_ms);
+        let target_len = target_frames * out_channels;
+        let mut out_samples: Vec<f32> = Vec::with_capacity(target_len);
+        let mut skipped_packets = 0;
+        let mut truncated = false;
+
+        while out_samples.len() < target_len {
+            let packet = match format.next_packet() {
+                Ok(packet) => packet,
+                Err(symphonia::core::errors::Error::IoError(_)) => break,
+                Err(err) => return Err(err.into()),
+            };
+            if packet.track_id() != selected_track {
+                continue;
+            }
+
+            let decoded = match decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                Err(symphonia::core::errors::Error::DecodeError(_)) => {
+                    skipped_packets += 1;
+                    continue;
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            sample_rate = decoded.spec().rate;
+            let channels = decoded.spec().channels.count().max(1);
+
+            let mut sample_buffer =
+                SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
+            sample_buffer.copy_interleaved_ref(decoded);
+            let decoded_samples = sample_buffer.samples();
+
+            let packet_frames = decoded_samples.len() / channels;
+            for (index, frame) in decoded_samples.chunks(channels).enumerate() {
+                if out_channels == 2 {
+                    out_samples.push(frame[0]);
+                    out_samples.push(frame.get(1).copied().unwrap_or(frame[0]));
+                } else {
+                    let mixed = frame.iter().copied().sum::<f32>() / channels as f32;
+                    out_samples.push(mixed);
+                }
+                if out_samples.len() >= target_len {
+                    truncated = index + 1 < packet_frames;
+                    break;
+                }
+            }
+        }
+        // A bite that ends exactly on a packet boundary is cut short if any packet follows.
+        while out_samples.len() >= target_len && !truncated {
+            match format.next_packet() {
+                Ok(packet) => truncated = packet.track_id() == selected_track,
+                Err(_) => break,
+            }
+        }
+
+        if out_samples.is_empty() {
+            return Err(anyhow!("failed to decode audio samples from selected clip"));
+        }
+
+        let decoded_frames = (out_samples.len() / out_channels).min(target_frames);
+        if out_samples.len() < target_len {
+            out_samples.resize(target_len, 0.0);
+        } else {
+            out_samples.truncate(target_len);
+        }
+
+        let loop_frames = sampler_info
+            .loop_frames
+            .filter(|&(start, end)| start < end && end <= target_frames);
+        let sample_rate = rate_override.unwrap_or(sample_rate);
+
+        Ok(Self {
+            sample_rate,
+            channels: out_channels as u16,
+            loudness_db: measure_loudness(&out_samples, out_channels, sample_rate),
+            samples: Arc::new(out_samples),
+            root_midi: sampler_info.unity_note,
+            loop_frames,
+            decoded_frames,
+            truncated,
+            skipped_packets,
+            track_id: Some(selected_track),
+            tracks,
+            cue_frames: sampler_info.cue_frames,
+        })
+    }
+
+    pub fn generated_test_tone(duration_ms: u32, tone: &ToneSettings) -> Self {
+        let sample_rate = 44_100;
+        let target_frames = frames_for_ms(sample_rate, duration_ms);
+        let mut out_mono = Vec::with_capacity(target_frames);
+        let max_harmonic = ((sample_rate as f32 * 0.5 / tone.frequency) as u32)
+            .min(tone.harmonics)
+            .max(1);
+
+        for i in 0..target_frames {
+            let t = i as f32 / sample_rate as f32;
+            let envelope = (1.0 - t).max(0.0).powf(2.0);
+            let phase = 2.0 * std::f32::consts::PI * tone.frequency * t;
+            let partial = |k: u32| (phase * k as f32).sin();
+            let raw = match tone.waveform {
+                ToneWaveform::Classic => {
+                    let sub = (phase * 0.5).sin() * 0.15;
+                    partial(1) + partial(2) * 0.35 + sub
+                }
+                ToneWaveform::Sine => partial(1),
+                ToneWaveform::Square => {
+                    (1..=max_harmonic)
+                        .step_by(2)
+                        .map(|k| partial(k) / k as f32)
+                        .sum::<f32>()
+                        * 4.0
+                        / std::f32::consts::PI
+                }
+                ToneWaveform::Saw => {
+                    (1..=max_harmonic)
+                        .map(|k| {
+                            let sign = if k % 2 == 0 { -1.0 } else { 1.0 };
+                            sign * partial(k) / k as f32
+                        })
+                        .sum::<f32>()
+                        * 2.0
+                        / std::f32::consts::PI
+                }
+                ToneWaveform::Triangle => {
+                    (1..=max_harmonic)
+                        .step_by(2)
+                        .map(|k| {
+                            let sign = if (k / 2) % 2 == 0 { 1.0 } else { -1.0 };
+                            sign * partial(k) / (k * k) as f32
+                        })
+                        .sum::<f32>()
+                        * 8.0
+                        / (std::f32::consts::PI * std::f32::consts::PI)
+                }
+            };
+            let sample = raw * envelope * 0.6;
+            out_mono.push(sample.clamp(-1.0, 1.0));
+        }
+
+        Self {
+            sample_rate,
+            channels: 1,
+            loudness_db: measure_loudness(&out_mono, 1, sample_rate),
+            samples: Arc::new(out_mono),
+            root_midi: None,
+            loop_frames: None,
+            decoded_frames: target_frames,
+            truncated: false,
+            skipped_packets: 0,
+            track_id: None,
+            tracks: Vec::new(),
+            cue_frames: Vec::new(),
+        }
+    }
+
+    pub fn nearest_zero_crossing(&self, frame: usize, radius: usize, rising: bool) -> usize {
+        let channels = usize::from(self.channels.max(1));
+        let frames = self.frames();
+        let value = |f: usize| {
+            self.samples[f * channels..(f + 1) * channels]
+                .iter()
+                .sum::<f32>()
+        };
+        let crosses = |f: usize| {
+            if f + 1 >= frames {
+                return false;
+            }
+            let (before, after) = (value(f) <= 0.0, value(f + 1) <= 0.0);
+            before != after && (!rising || before)
+        };
+        (0..=radius)
+            .flat_map(|offset| [frame.checked_sub(offset), Some(frame + offset)])
+            .flatten()
+            .find(|&f| crosses(f))
+            .map_or(frame, |f| f + 1)
+            .min(frames)
+    }
+
+    // Drops the frames before the first rising zero crossing within `radius` so the bite
+    // starts on silence. Returns how many frames were trimmed.
+    pub fn snap_start_to_zero_crossing(&mut self, radius: usize) -> usize {
+        let start = self.nearest_zero_crossing(0, radius, true);
+        if start == 0 || start >= self.frames() {
+            return 0;
+        }
+        let channels = usize::from(self.channels.max(1));
+        self.samples = Arc::new(self.samples[start * channels..].to_vec());
+        self.decoded_frames = self.decoded_frames.saturating_sub(start);
+        self.loop_frames = self
+            .loop_frames
+            .filter(|&(loop_start, _)| loop_start >= start)
+            .map(|(loop_start, end)| (loop_start - start, end - start));
+        start
+    }
+
+    // A copy that starts at `frame` and runs to the end of the decoded audio.
+    pub fn slice_from(&self, frame: usize) -> Self {
+        self.slice(frame, usize::MAX)
+    }
+
+    pub fn slice(&self, frame: usize, len: usize) -> Self {
+        let channels = usize::from(self.channels.max(1));
+        let decoded = self.decoded_frames.min(self.frames());
+        let start = frame.min(decoded);
+        let end = start + len.min(decoded - start);
+        Self {
+            sample_rate: self.sample_rate,
+            channels: self.channels,
+            samples: Arc::new(self.samples[start * channels..end * channels].to_vec()),
+            root_midi: self.root_midi,
+            loop_frames: None,
+            loudness_db: self.loudness_db,
+            decoded_frames: end - start,
+            truncated: false,
+            skipped_packets: self.skipped_packets,
+            track_id: self.track_id,
+            tracks: Vec::new(),
+            cue_frames: Vec::new(),
+        }
+    }
+
+    pub fn frames(&self) -> usize {
+        self.samples.len() / usize::from(self.channels.max(1))
+    }
+
+    // Energy-based onset detection: a hop whose level jumps above the recent average by
+    // more than the threshold starts a new hit. `sensitivity` runs 0–1; higher finds more.
+    pub fn detect_onsets(&self, sensitivity: f32) -> Vec<usize> {
+        let channels = usize::from(self.channels.max(1));
+        let hop = (self.sample_rate as usize / 100).max(1);
+        let min_gap = frames_for_ms(self.sample_rate, ONSET_MIN_GAP_MS).div_ceil(hop);
+        let threshold_db = 14.0 - 11.0 * sensitivity.clamp(0.0, 1.0);
+        let decoded = self.decoded_frames.min(self.frames());
+        let levels: Vec<f32> = self.samples[..decoded * channels]
+            .chunks(hop * channels)
+            .map(|block| {
+                let power = block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32;
+                10.0 * power.max(1e-10).log10()
+            })
+            .collect();
+
+        let mut onsets = Vec::new();
+        let mut last_hop: Option<usize> = None;
+        for (index, &level) in levels.iter().enumerate() {
+            if level < ONSET_GATE_DB {
+                continue;
+            }
+            let history = &levels[index.saturating_sub(ONSET_HISTORY_HOPS)..index];
+            let background = if history.is_empty() {
+                f32::NEG_INFINITY
+            } else {
+                history.iter().sum::<f32>() / history.len() as f32
+            };
+            let rested = last_hop.is_none_or(|last| index - last >= min_gap);
+            if level - background >= threshold_db && rested {
+                onsets.push(index * hop);
+                last_hop = Some(index);
+            }
+        }
+        onsets
+    }
+
+    // Subtracts the mean of the mono mix from every channel and re-measures loudness so
+    // auto-level sees the corrected signal. Returns the removed offset.
+    pub fn remove_dc_offset(&mut self) -> f32 {
+        let mono = self.mono_samples();
+        if mono.is_empty() {
+            return 0.0;
+        }
+        let offset = mono.iter().sum::<f32>() / mono.len() as f32;
+        let corrected: Vec<f32> = self.samples.iter().map(|s| s - offset).collect();
+        let channels = usize::from(self.channels.max(1));
+        self.loudness_db = measure_loudness(&corrected, channels, self.sample_rate);
+        self.samples = Arc::new(corrected);
+        offset
+    }
+
+    // Replaces the silent padding after a short decode with crossfaded repeats of the
+    // decoded audio, so a short loop sustains for the whole bite.
+    pub fn fill_by_looping(&mut self, crossfade_ms: u32) {
+        let channels = usize::from(self.channels.max(1));
+        let frames = self.frames();
+        let decoded = self.decoded_frames.min(frames);
+        if decoded < 2 || decoded == frames {
+            return;
+        }
+
+        let crossfade = frames_for_ms(self.sample_rate, crossfade_ms).min(decoded / 2);
+        let source = &self.samples[..decoded * channels];
+        let mut filled = source.to_vec();
+        while filled.len() < frames * channels {
+            let overlap_start = filled.len() - crossfade * channels;
+            for (index, &sample) in source[..crossfade * channels].iter().enumerate() {
+                let t = (index / channels) as f32 / crossfade as f32;
+                let out = &mut filled[overlap_start + index];
+                *out = *out * (1.0 - t) + sample * t;
+            }
+            filled.extend_from_slice(&source[crossfade * channels..]);
+        }
+        filled.truncate(frames * channels);
+
+        self.loudness_db = measure_loudness(&filled, channels, self.sample_rate);
+        self.samples = Arc::new(filled);
+        self.decoded_frames = frames;
+        self.loop_frames = None;
+    }
+
+    pub fn reverse_attack(&mut self, duration_ms: u32, crossfade_ms: u32) {
+        let channels = usize::from(self.channels.max(1));
+        let frames = self.frames();
+        let requested = frames_for_ms(self.sample_rate, duration_ms).min(frames);
+        let radius = frames_for_ms(self.sample_rate, crossfade_ms);
+        let region = self
+            .nearest_zero_crossing(requested, radius, false)
+            .min(frames);
+        if region < 2 {
+            return;
+        }
+
+        let original = &self.samples;
+        let mut shaped = original.to_vec();
+        let crossfade = frames_for_ms(self.sample_rate, crossfade_ms).min(region / 2);
+        for frame in 0..region {
+            let mirrored = region - 1 - frame;
+            let fade_start = region - crossfade;
+            let forward = if frame >= fade_start && crossfade > 0 {
+                (frame - fade_start + 1) as f32 / crossfade as f32
+            } else {
+                0.0
+            };
+            for channel in 0..channels {
+                let reversed = original[mirrored * channels + channel];
+                let straight = original[frame * channels + channel];
+                shaped[frame * channels + channel] = reversed + (straight - reversed) * forward;
+            }
+        }
+        self.samples = Arc::new(shaped);
+    }
+
+    pub fn from_layers(layers: &[ClipLayer], mode: LayerMode, duration_ms: u32) -> Result<Self> {
+        if layers.is_empty() {
+            return Err(anyhow!("add at least one layer"));
+        }
+        // Any soloed layer silences every layer that isn't soloed.
+        let any_solo = layers.iter().any(|layer| layer.solo);
+        let layers: Vec<&ClipLayer> = layers
+            .iter()
+            .filter(|layer| !layer.muted && (layer.solo || !any_solo))
+            .collect();
+        let decoded = layers
+            .iter()
+            .map(|layer| Self::from_file(&layer.path, duration_ms, false, None, None))
+            .collect::<Result<Vec<_>>>()?;
+        let sample_rate = decoded
+            .iter()
+            .map(|clip| clip.sample_rate)
+            .max()
+            .unwrap_or(44_100);
+        let target_frames = frames_for_ms(sample_rate, duration_ms);
+
+        let mut out_mono = vec![0.0f32; target_frames];
+        let mut cursor = 0;
+        for (layer, clip) in layers.iter().zip(&decoded) {
+            let mono = clip.mono_samples();
+            let body = resample_linear(&mono[..clip.decoded_frames], clip.sample_rate, sample_rate);
+            let offset = frames_for_ms(sample_rate, layer.offset_ms);
+            let start = match mode {
+                LayerMode::Sum => offset,
+                LayerMode::Concatenate => cursor + offset,
+            };
+            cursor = start + body.len();
+            let gain = 10f32.powf(layer.gain_db / 20.0);
+            for (out, sample) in out_mono.iter_mut().skip(start).zip(body) {
+                *out += sample * gain;
+            }
+        }
+
+        Ok(Self {
+            sample_rate,
+            channels: 1,
+            loudness_db: measure_loudness(&out_mono, 1, sample_rate),
+            samples: Arc::new(out_mono),
+            root_midi: decoded.first().and_then(|clip| clip.root_midi),
+            loop_frames: None,
+            decoded_frames: cursor.min(target_frames),
+            truncated: false,
+            skipped_packets: decoded.iter().map(|clip| clip.skipped_packets).sum(),
+            track_id: None,
+            tracks: Vec::new(),
+            cue_frames: Vec::new(),
+        })
+    }
+
+    pub fn mono_samples(&self) -> Vec<f32> {
+        let channels = usize::from(self.channels.max(1));
+        self.samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
+            .collect()
+    }
+}
+
+pub fn list_folder(dir: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(dir)
+        .with_context(|| format!("failed to read folder: {}", dir.display()))?;
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.is_file() && is_supported_audio(path))
+        .collect();
+    files.sort_by_key(|path| path.file_name().map(|name| name.to_ascii_lowercase()));
+    Ok(files)
+}
+
+pub fn is_supported_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SUPPORTED_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+fn resample_linear(samples: &[f32], from_rate: u32, to_rate: u32) -> Vec<f32> {
+    if from_rate == to_rate || samples.is_empty() {
+        return samples.to_vec();
+    }
+    let step = f64::from(from_rate) / f64::from(to_rate);
+    let frames = (samples.len() as f64 / step) as usize;
+    (0..frames)
+        .map(|frame| {
+            let position = frame as f64 * step;
+            let index = position as usize;
+            let fraction = (position - index as f64) as f32;
+            let current = samples[index];
+            let next = samples.get(index + 1).copied().unwrap_or(current);
+            current + (next - current) * fraction
+        })
+        .collect()
+}
+
+pub fn frames_for_ms(sample_rate: u32, duration_ms: u32) -> usize {
+    (u64::from(sample_rate) * u64::from(duration_ms) / 1_000) as usize
+}
+
+const ONSET_GATE_DB: f32 = -50.0;
+const ONSET_HISTORY_HOPS: usize = 4;
+const ONSET_MIN_GAP_MS: u32 = 60;
+
+pub fn measure_loudness(samples: &[f32], channels: usize, sample_rate: u32) -> Option<f32> {
+    let block_len = (sample_rate as usize / 20).max(1) * channels.max(1);
+    let gate = 10f32.powf(LOUDNESS_GATE_DB / 10.0);
+    let (sum, count) = samples
+        .chunks(block_len)
+        .map(|block| block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32)
+        .filter(|&power| power > gate)
+        .fold((0.0f32, 0usize), |(sum, count), power| {
+            (sum + power, count + 1)
+        });
+    (count > 0).then(|| 10.0 * (sum / count as f32).log10())
+}
+
+#[derive(Clone)]
+pub struct MemoryClip {
+    pub name: String,
+    pub bytes: Arc<Vec<u8>>,
+    pub extension: Option<String>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum LayerMode {
+    #[default]
+    Sum,
+    Concatenate,
+}
+
+impl LayerMode {
+    pub fn label(self) -> Text {
+        match self {
+            Self::Sum => Text::LayerSum,
+            Self::Concatenate => Text::LayerConcatenate,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipLayer {
+    pub path: PathBuf,
+    pub gain_db: f32,
+    pub offset_ms: u32,
+    pub muted: bool,
+    pub solo: bool,
+}
+
+#[derive(Clone)]
+pub enum ClipSource {
+    File(PathBuf),
+    RawPcm(PathBuf, raw::RawPcmFormat),
+    Url(String),
+    Memory(MemoryClip),
+    Layers(Vec<ClipLayer>, LayerMode),
+}
+
+impl ClipSource {
+    pub fn name(&self) -> String {
+        match self {
+            Self::File(path) | Self::RawPcm(path, _) => path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("clip")
+                .to_string(),
+            Self::Url(url) => remote::file_name_from_url(url).to_string(),
+            Self::Memory(source) => source.name.clone(),
+            Self::Layers(layers, _) => format!("{} layers", layers.len()),
+        }
+    }
+
+    fn resolve(self) -> Result<Self> {
+        match self {
+            Self::Url(url) => {
+                let download = remote::download(&url)?;
+                Ok(Self::Memory(MemoryClip {
+                    name: remote::file_name_from_url(&url).to_string(),
+                    bytes: Arc::new(download.bytes),
+                    extension: download.extension,
+                }))
+            }
+            other => Ok(other),
+        }
+    }
+
+    fn decode(
+        &self,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        track_id: Option<u32>,
+        rate_override: Option<u32>,
+    ) -> Result<SampleClip> {
+        match self {
+            Self::File(path) => {
+                SampleClip::from_file(path, duration_ms, preserve_stereo, track_id, rate_override)
+            }
+            Self::Memory(source) => SampleClip::from_memory(
+                source,
+                duration_ms,
+                preserve_stereo,
+                track_id,
+                rate_override,
+            ),
+            Self::RawPcm(path, format) => {
+                SampleClip::from_raw_pcm(path, format, duration_ms, preserve_stereo, rate_override)
+            }
+            Self::Layers(layers, mode) => SampleClip::from_layers(layers, *mode, duration_ms),
+            Self::Url(_) => Err(anyhow!("URL sources must be downloaded before decoding")),
+        }
+    }
+}
+
+pub struct PendingLoad {
+    pub name: String,
+    pub new_source: bool,
+    receiver: mpsc::Receiver<Result<(ClipSource, SampleClip)>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PendingLoad {
+    pub fn spawn(
+        source: ClipSource,
+        duration_ms: u32,
+        preserve_stereo: bool,
+        track_id: Option<u32>,
+        rate_override: Option<u32>,
+        new_source: bool,
+    ) -> Self {
+        let name = source.name();
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let worker_cancelled = Arc::clone(&cancelled);
+
+        thread::spawn(move || {
+            let result = source.resolve().and_then(|resolved| {
+                if worker_cancelled.load(Ordering::Relaxed) {
+                    return Err(anyhow!("load superseded"));
+                }
+                let sample =
+                    resolved.decode(duration_ms, preserve_stereo, track_id, rate_override)?;
+                Ok((resolved, sample))
+            });
+            if !worker_cancelled.load(Ordering::Relaxed) {
+                let _ = sender.send(result);
+            }
+        });
+
+        Self {
+            name,
+            new_source,
+            receiver,
+            cancelled,
+        }
+    }
+
+    pub fn poll(&self) -> Option<Result<(ClipSource, SampleClip)>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Some(Err(anyhow!("background loader stopped unexpectedly")))
+            }
+        }
+    }
+}
+
+impl Drop for PendingLoad {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ToneWaveform {
+    Classic,
+    Sine,
+    Square,
+    Saw,
+    Triangle,
+}
+
+impl ToneWaveform {
+    pub const ALL: [Self; 5] = [
+        Self::Classic,
+        Self::Sine,
+        Self::Square,
+        Self::Saw,
+        Self::Triangle,
+    ];
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::Classic => Text::WaveformClassic,
+            Self::Sine => Text::WaveformSine,
+            Self::Square => Text::WaveformSquare,
+            Self::Saw => Text::WaveformSaw,
+            Self::Triangle => Text::WaveformTriangle,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ToneSettings {
+    pub waveform: ToneWaveform,
+    pub frequency: f32,
+    pub harmonics: u32,
+}
+
+impl Default for ToneSettings {
+    fn default() -> Self {
+        Self {
+            waveform: ToneWaveform::Classic,
+            frequency: 261.63,
+            harmonics: 16,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wav_clip(sample_rate: u32, channels: u16, frames: usize) -> MemoryClip {
+        let spec = hound::WavSpec {
+            channels,
+            sample_rate,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut bytes = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut bytes, spec).unwrap();
+        for i in 0..frames * usize::from(channels) {
+            writer.write_sample((i % 100) as i16 * 100).unwrap();
+        }
+        writer.finalize().unwrap();
+        MemoryClip {
+            name: "test.wav".to_string(),
+            bytes: Arc::new(bytes.into_inner()),
+            extension: Some("wav".to_string()),
+        }
+    }
+
+    // A ramp that reaches ±0.9, so any clipping or scaling error shows up.
+    fn ramp(index: usize) -> f32 {
+        ((index % 200) as f32 / 100.0 - 1.0) * 0.9
+    }
+
+    fn high_depth_wav(bits_per_sample: u16, sample_format: hound::SampleFormat) -> MemoryClip {
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 44_100,
+            bits_per_sample,
+            sample_format,
+        };
+        let mut bytes = Cursor::new(Vec::new());
+        let mut writer = hound::WavWriter::new(&mut bytes, spec).unwrap();
+        for i in 0..4_410 {
+            match sample_format {
+                hound::SampleFormat::Float => writer.write_sample(ramp(i)).unwrap(),
+                hound::SampleFormat::Int => {
+                    let full_scale = (1i64 << (bits_per_sample - 1)) as f32;
+                    writer
+                        .write_sample((ramp(i) * full_scale).round() as i32)
+                        .unwrap()
+                }
+            }
+        }
+        writer.finalize().unwrap();
+        MemoryClip {
+            name: "test.wav".to_string(),
+            bytes: Arc::new(bytes.into_inner()),
+            extension: Some("wav".to_string()),
+        }
+    }
+
+    fn assert_matches_ramp(clip: &SampleClip, tolerance: f32) {
+        assert_eq!(clip.decoded_frames, 4_410);
+        for (index, &sample) in clip.samples[..4_410].iter().enumerate() {
+            assert!(
+                (sample - ramp(index)).abs() <= tolerance,
+                "frame {index}: {sample} vs {}",
+                ramp(index)
+            );
+        }
+    }
+
+    #[test]
+    fn high_bit_depth_wavs_keep_their_amplitude() {
+        for (bits, format, tolerance) in [
+            (24, hound::SampleFormat::Int, 1e-6),
+            (32, hound::SampleFormat::Int, 1e-6),
+            (32, hound::SampleFormat::Float, 0.0),
+        ] {
+            let memory = high_depth_wav(bits, format);
+            let clip = SampleClip::from_memory(&memory, 100, false, None, None).unwrap();
+            assert_matches_ramp(&clip, tolerance);
+        }
+    }
+
+    #[test]
+    fn reports_audio_left_past_the_decode_length() {
+        let memory = high_depth_wav(24, hound::SampleFormat::Int);
+        for (duration_ms, truncated) in [(50, true), (100, false), (200, false)] {
+            let clip = SampleClip::from_memory(&memory, duration_ms, false, None, None).unwrap();
+            assert_eq!(clip.truncated, truncated, "{duration_ms} ms");
+        }
+    }
+
+    #[test]
+    fn reads_24_bit_aiff_with_loop_markers() {
+        let chunk =
+            |id: &[u8], body: Vec<u8>| [id, &(body.len() as u32).to_be_bytes(), &body].concat();
+        let mut comm = Vec::new();
+        comm.extend_from_slice(&1u16.to_be_bytes());
+        comm.extend_from_slice(&4_410u32.to_be_bytes());
+        comm.extend_from_slice(&24u16.to_be_bytes());
+        // 44100 as an 80-bit IEEE extended float.
+        comm.extend_from_slice(&[0x40, 0x0e, 0xac, 0x44, 0, 0, 0, 0, 0, 0]);
+        let mut ssnd = vec![0u8; 8];
+        for i in 0..4_410 {
+            let value = (ramp(i) * 8_388_608.0).round() as i32;
+            ssnd.extend_from_slice(&value.to_be_bytes()[1..]);
+        }
+        let mut mark = 2u16.to_be_bytes().to_vec();
+        for (id, position) in [(1u16, 100u32), (2, 300)] {
+            mark.extend_from_slice(&id.to_be_bytes());
+            mark.extend_from_slice(&position.to_be_bytes());
+            mark.extend_from_slice(&[0, 0]);
+        }
+        let mut inst = vec![48, 0, 0, 127, 1, 127, 0, 0];
+        for value in [1u16, 1, 2, 0, 0, 0] {
+            inst.extend_from_slice(&value.to_be_bytes());
+        }
+        let chunks = [
+            chunk(b"COMM", comm),
+            chunk(b"MARK", mark),
+            chunk(b"INST", inst),
+            chunk(b"SSND", ssnd),
+        ]
+        .concat();
+        let bytes = [
+            b"FORM".as_slice(),
+            &(chunks.len() as u32 + 4).to_be_bytes(),
+            b"AIFF",
+            &chunks,
+        ]
+        .concat();
+        let memory = MemoryClip {
+            name: "test.aiff".to_string(),
+            bytes: Arc::new(bytes),
+            extension: Some("aiff".to_string()),
+        };
+
+        let clip = SampleClip::from_memory(&memory, 100, false, None, None).unwrap();
+        assert_matches_ramp(&clip, 1e-6);
+        assert_eq!(clip.root_midi, Some(48));
+        assert_eq!(clip.loop_frames, Some((100, 300)));
+        assert!(clip.cue_frames.is_empty());
+    }
+
+    #[test]
+    fn frame_counts_are_sample_accurate() {
+        assert_eq!(frames_for_ms(44_100, 500), 22_050);
+        assert_eq!(frames_for_ms(44_100, 1), 44);
+        assert_eq!(frames_for_ms(44_100, 333), 14_685);
+        assert_eq!(frames_for_ms(48_000, 501), 24_048);
+        assert_eq!(frames_for_ms(22_050, 5_000), 110_250);
+        assert_eq!(frames_for_ms(96_000, 4_999), 479_904);
+        assert_eq!(frames_for_ms(192_000, 5_000), 960_000);
+    }
+
+    #[test]
+    fn decode_hits_exact_target_length() {
+        for (sample_rate, duration_ms) in [(44_100, 500), (48_000, 733), (22_050, 1_001)] {
+            let target = frames_for_ms(sample_rate, duration_ms);
+            for source_frames in [target / 2, target * 2] {
+                for (channels, preserve_stereo) in [(1, false), (2, false), (2, true)] {
+                    let memory = wav_clip(sample_rate, channels, source_frames);
+                    let clip =
+                        SampleClip::from_memory(&memory, duration_ms, preserve_stereo, None, None)
+                            .unwrap();
+                    assert_eq!(
+                        clip.samples.len(),
+                        target * usize::from(clip.channels),
+                        "{sample_rate} Hz, {duration_ms} ms, {source_frames} source frames"
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,1975 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use rodio::Source;
+use serde::{Deserialize, Serialize};
+
+use crate::strings::Text;
+
+pub struct Panned<S> {
+    source: S,
+    left_gain: f32,
+    right_gain: f32,
+    pending_right: Option<f32>,
+    next_is_left: bool,
+}
+
+impl<S> Panned<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, pan: f32) -> Self {
+        let angle = (pan.clamp(-1.0, 1.0) + 1.0) * std::f32::consts::FRAC_PI_4;
+        let (mut left_gain, mut right_gain) = (angle.cos(), angle.sin());
+        if source.channels() == 2 {
+            left_gain = (left_gain * std::f32::consts::SQRT_2).min(1.0);
+            right_gain = (right_gain * std::f32::consts::SQRT_2).min(1.0);
+        }
+        Self {
+            source,
+            left_gain,
+            right_gain,
+            pending_right: None,
+            next_is_left: true,
+        }
+    }
+}
+
+impl<S> Iterator for Panned<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.source.channels() == 2 {
+            let sample = self.source.next()?;
+            let gain = if self.next_is_left {
+                self.left_gain
+            } else {
+                self.right_gain
+            };
+            self.next_is_left = !self.next_is_left;
+            return Some(sample * gain);
+        }
+
+        if let Some(right) = self.pending_right.take() {
+            return Some(right);
+        }
+        let sample = self.source.next()?;
+        self.pending_right = Some(sample * self.right_gain);
+        Some(sample * self.left_gain)
+    }
+}
+
+pub const MAX_STEREO_WIDTH: f32 = 2.0;
+
+// Mid/side width for stereo sources: 0 collapses to mono, 1 leaves the clip alone and
+// larger values boost the side signal. Mono sources pass through untouched.
+pub struct StereoWidth<S> {
+    source: S,
+    width: f32,
+    pending_right: Option<f32>,
+}
+
+impl<S> StereoWidth<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, width: f32) -> Self {
+        Self {
+            source,
+            width: width.clamp(0.0, MAX_STEREO_WIDTH),
+            pending_right: None,
+        }
+    }
+}
+
+impl<S> Iterator for StereoWidth<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.source.channels() != 2 {
+            return self.source.next();
+        }
+        if let Some(right) = self.pending_right.take() {
+            return Some(right);
+        }
+        let left = self.source.next()?;
+        let right = self.source.next().unwrap_or(left);
+        let mid = (left + right) * 0.5;
+        let side = (left - right) * 0.5 * self.width;
+        self.pending_right = Some(mid - side);
+        Some(mid + side)
+    }
+}
+
+impl<S> Source for StereoWidth<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+impl<S> Source for Panned<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        if self.source.channels() == 2 {
+            return self.source.current_frame_len();
+        }
+        let pending = usize::from(self.pending_right.is_some());
+        self.source.current_frame_len().map(|len| len * 2 + pending)
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[derive(Clone)]
+pub struct ReleaseHandle(Arc<AtomicU32>);
+
+impl Default for ReleaseHandle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ReleaseHandle {
+    const HELD: u32 = u32::MAX;
+    // The top bit carries the curve; the rest is the fade length.
+    const EXPONENTIAL: u32 = 1 << 31;
+
+    pub fn new() -> Self {
+        Self(Arc::new(AtomicU32::new(Self::HELD)))
+    }
+
+    pub fn release(&self, fade_ms: u32, curve: ReleaseCurve) {
+        let fade_ms = fade_ms.min(Self::EXPONENTIAL - 1);
+        let value = match curve {
+            ReleaseCurve::Linear => fade_ms,
+            ReleaseCurve::Exponential => fade_ms | Self::EXPONENTIAL,
+        };
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    fn requested_fade(&self) -> Option<(u32, ReleaseCurve)> {
+        let value = self.0.load(Ordering::Relaxed);
+        if value == Self::HELD {
+            return None;
+        }
+        let curve = if value & Self::EXPONENTIAL != 0 {
+            ReleaseCurve::Exponential
+        } else {
+            ReleaseCurve::Linear
+        };
+        Some((value & !Self::EXPONENTIAL, curve))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum ReleaseCurve {
+    #[default]
+    Linear,
+    Exponential,
+}
+
+impl ReleaseCurve {
+    pub const ALL: [Self; 2] = [Self::Linear, Self::Exponential];
+    const EXPONENTIAL_RATE: f32 = 5.0;
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::Linear => Text::CurveLinear,
+            Self::Exponential => Text::CurveExponential,
+        }
+    }
+
+    // Gain for the fraction of the fade still to go: 1.0 at the start, exactly 0.0 at the end.
+    fn gain(self, remaining: f32) -> f32 {
+        match self {
+            Self::Linear => remaining,
+            Self::Exponential => {
+                let floor = (-Self::EXPONENTIAL_RATE).exp();
+                ((-Self::EXPONENTIAL_RATE * (1.0 - remaining)).exp() - floor) / (1.0 - floor)
+            }
+        }
+    }
+}
+
+pub struct Releasable<S> {
+    source: S,
+    handle: ReleaseHandle,
+    fade: Option<(u32, u32, ReleaseCurve)>,
+}
+
+impl<S> Releasable<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, handle: ReleaseHandle) -> Self {
+        Self {
+            source,
+            handle,
+            fade: None,
+        }
+    }
+}
+
+impl<S> Iterator for Releasable<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.fade.is_none() {
+            if let Some((fade_ms, curve)) = self.handle.requested_fade() {
+                let samples = u64::from(self.source.sample_rate())
+                    * u64::from(self.source.channels())
+                    * u64::from(fade_ms)
+                    / 1_000;
+                let samples = samples.clamp(1, u64::from(u32::MAX)) as u32;
+                self.fade = Some((samples, samples, curve));
+            }
+        }
+
+        let sample = self.source.next()?;
+        match &mut self.fade {
+            None => Some(sample),
+            Some((_, 0, _)) => None,
+            Some((total, remaining, curve)) => {
+                *remaining -= 1;
+                Some(sample * curve.gain(*remaining as f32 / *total as f32))
+            }
+        }
+    }
+}
+
+impl<S> Source for Releasable<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct VoiceState {
+    stop: ReleaseHandle,
+    finished: Arc<AtomicBool>,
+}
+
+impl VoiceState {
+    // Fades the whole voice, effect tails included, instead of cutting it mid-cycle.
+    pub fn stop(&self, fade_ms: u32, curve: ReleaseCurve) {
+        self.stop.release(fade_ms, curve);
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished.load(Ordering::Relaxed)
+    }
+}
+
+pub struct Tracked<S> {
+    source: Releasable<S>,
+    state: VoiceState,
+}
+
+impl<S> Tracked<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, state: VoiceState) -> Self {
+        Self {
+            source: Releasable::new(source, state.stop.clone()),
+            state,
+        }
+    }
+}
+
+impl<S> Iterator for Tracked<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.source.next();
+        if sample.is_none() {
+            self.state.finished.store(true, Ordering::Relaxed);
+        }
+        sample
+    }
+}
+
+impl<S> Source for Tracked<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+impl<S> Drop for Tracked<S> {
+    fn drop(&mut self) {
+        self.state.finished.store(true, Ordering::Relaxed);
+    }
+}
+
+pub const DEFAULT_LIMITER_THRESHOLD_DB: f32 = -1.0;
+
+#[derive(Clone)]
+pub struct LimiterControl {
+    threshold: Arc<AtomicU32>,
+    makeup: Arc<AtomicU32>,
+}
+
+impl Default for LimiterControl {
+    fn default() -> Self {
+        Self::new(DEFAULT_LIMITER_THRESHOLD_DB, 0.0)
+    }
+}
+
+impl LimiterControl {
+    pub fn new(threshold_db: f32, makeup_db: f32) -> Self {
+        let control = Self {
+            threshold: Arc::new(AtomicU32::new(0)),
+            makeup: Arc::new(AtomicU32::new(0)),
+        };
+        control.set(threshold_db, makeup_db);
+        control
+    }
+
+    pub fn set(&self, threshold_db: f32, makeup_db: f32) {
+        let threshold = db_to_gain(threshold_db.min(0.0));
+        let makeup = db_to_gain(makeup_db);
+        self.threshold.store(threshold.to_bits(), Ordering::Relaxed);
+        self.makeup.store(makeup.to_bits(), Ordering::Relaxed);
+    }
+
+    fn threshold(&self) -> f32 {
+        f32::from_bits(self.threshold.load(Ordering::Relaxed))
+    }
+
+    fn makeup(&self) -> f32 {
+        f32::from_bits(self.makeup.load(Ordering::Relaxed))
+    }
+
+    pub fn process(&self, sample: f32) -> f32 {
+        soft_clip(sample * self.makeup(), self.threshold())
+    }
+}
+
+fn db_to_gain(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+// Linear below half the threshold, then a tanh knee that never exceeds it.
+pub fn soft_clip(sample: f32, threshold: f32) -> f32 {
+    let knee = threshold * 0.5;
+    let magnitude = sample.abs();
+    if magnitude <= knee {
+        return sample;
+    }
+    let headroom = threshold - knee;
+    let shaped = knee + headroom * ((magnitude - knee) / headroom).tanh();
+    shaped.copysign(sample)
+}
+
+pub struct Limiter<S> {
+    source: S,
+    control: LimiterControl,
+}
+
+impl<S> Limiter<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, control: LimiterControl) -> Self {
+        Self { source, control }
+    }
+}
+
+impl<S> Iterator for Limiter<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        self.source
+            .next()
+            .map(|sample| self.control.process(sample))
+    }
+}
+
+impl<S> Source for Limiter<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum LfoShape {
+    Sine,
+    Square,
+}
+
+impl LfoShape {
+    pub const ALL: [Self; 2] = [Self::Sine, Self::Square];
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::Sine => Text::LfoSine,
+            Self::Square => Text::LfoSquare,
+        }
+    }
+
+    fn value(self, phase: f32) -> f32 {
+        match self {
+            Self::Sine => (phase * std::f32::consts::TAU).sin(),
+            Self::Square if phase < 0.5 => 1.0,
+            Self::Square => -1.0,
+        }
+    }
+}
+
+pub struct Tremolo<S> {
+    source: S,
+    shape: LfoShape,
+    depth: f32,
+    phase: f32,
+    phase_step: f32,
+    gain: f32,
+    channel: u16,
+}
+
+impl<S> Tremolo<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, rate_hz: f32, depth: f32, shape: LfoShape) -> Self {
+        let phase_step = rate_hz.max(0.0) / source.sample_rate().max(1) as f32;
+        Self {
+            source,
+            shape,
+            depth: depth.clamp(0.0, 1.0),
+            phase: 0.0,
+            phase_step,
+            gain: 1.0,
+            channel: 0,
+        }
+    }
+}
+
+impl<S> Iterator for Tremolo<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.source.next()?;
+        if self.channel == 0 {
+            let lfo = self.shape.value(self.phase);
+            self.gain = 1.0 - self.depth * (0.5 - 0.5 * lfo);
+            self.phase = (self.phase + self.phase_step).fract();
+        }
+        self.channel = (self.channel + 1) % self.source.channels().max(1);
+        Some(sample * self.gain)
+    }
+}
+
+impl<S> Source for Tremolo<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+pub const MIN_FILTER_CUTOFF_HZ: f32 = 80.0;
+pub const MAX_FILTER_CUTOFF_HZ: f32 = 12_000.0;
+const FILTER_UPDATE_FRAMES: u32 = 32;
+
+// Live cutoff shared between the UI slider and every playing filter.
+#[derive(Clone)]
+pub struct FilterControl(Arc<AtomicU32>);
+
+impl FilterControl {
+    pub fn new(cutoff_hz: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(cutoff_hz.to_bits())))
+    }
+
+    pub fn set(&self, cutoff_hz: f32) {
+        self.0.store(cutoff_hz.to_bits(), Ordering::Relaxed);
+    }
+
+    fn cutoff(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+}
+
+// Live pitch bend shared between the UI and playing voices, stored as a speed ratio.
+#[derive(Clone)]
+pub struct BendControl(Arc<AtomicU32>);
+
+impl Default for BendControl {
+    fn default() -> Self {
+        Self(Arc::new(AtomicU32::new(1f32.to_bits())))
+    }
+}
+
+impl BendControl {
+    pub fn set_semitones(&self, semitones: f32) {
+        self.0
+            .store(2f32.powf(semitones / 12.0).to_bits(), Ordering::Relaxed);
+    }
+
+    fn ratio(&self) -> f64 {
+        f64::from(f32::from_bits(self.0.load(Ordering::Relaxed)))
+    }
+}
+
+// Re-reads any voice at the control's current ratio, interpolating linearly between frames.
+// Whatever the voice did to reach its pitch (loops, grains, pre-rendering) is kept.
+pub struct Bend<S> {
+    source: S,
+    control: BendControl,
+    previous: Vec<f32>,
+    next: Vec<f32>,
+    position: f64,
+    channel: usize,
+    finished: bool,
+}
+
+impl<S> Bend<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(mut source: S, control: BendControl) -> Self {
+        let channels = usize::from(source.channels().max(1));
+        let previous = read_frame(&mut source, channels);
+        let next = read_frame(&mut source, channels);
+        Self {
+            finished: previous.is_none() || next.is_none(),
+            previous: previous.unwrap_or_default(),
+            next: next.unwrap_or_default(),
+            source,
+            control,
+            position: 0.0,
+            channel: 0,
+        }
+    }
+}
+
+fn read_frame<S: Iterator<Item = f32>>(source: &mut S, channels: usize) -> Option<Vec<f32>> {
+    let frame: Vec<f32> = source.by_ref().take(channels).collect();
+    (frame.len() == channels).then_some(frame)
+}
+
+impl<S> Iterator for Bend<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.channel == 0 {
+            while self.position >= 1.0 && !self.finished {
+                std::mem::swap(&mut self.previous, &mut self.next);
+                for sample in self.next.iter_mut() {
+                    match self.source.next() {
+                        Some(value) => *sample = value,
+                        None => {
+                            self.finished = true;
+                            break;
+                        }
+                    }
+                }
+                self.position -= 1.0;
+            }
+            if self.finished {
+                return None;
+            }
+        }
+        let t = self.position as f32;
+        let (a, b) = (self.previous[self.channel], self.next[self.channel]);
+        self.channel += 1;
+        if self.channel >= self.previous.len() {
+            self.channel = 0;
+            self.position += self.control.ratio();
+        }
+        Some(a + (b - a) * t)
+    }
+}
+
+impl<S> Source for Bend<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+// Linear interpolation through (time_ms, value) points, holding the first and last values.
+pub fn automation_value(points: &[(f32, f32)], time_ms: f32) -> Option<f32> {
+    let (first, last) = (points.first()?, points.last()?);
+    if time_ms <= first.0 {
+        return Some(first.1);
+    }
+    if time_ms >= last.0 {
+        return Some(last.1);
+    }
+    let next = points.partition_point(|&(time, _)| time <= time_ms);
+    let (t0, v0) = points[next - 1];
+    let (t1, v1) = points[next];
+    let fraction = if t1 > t0 {
+        (time_ms - t0) / (t1 - t0)
+    } else {
+        1.0
+    };
+    Some(v0 + (v1 - v0) * fraction)
+}
+
+// Resonant state-variable low-pass whose cutoff follows either a recorded automation
+// curve or the live control.
+pub struct WahFilter<S> {
+    source: S,
+    control: FilterControl,
+    automation: Option<Arc<Vec<(f32, f32)>>>,
+    damping: f32,
+    coefficients: (f32, f32, f32),
+    state: [(f32, f32); 2],
+    frame: u32,
+    channel: u16,
+}
+
+impl<S> WahFilter<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(
+        source: S,
+        control: FilterControl,
+        resonance: f32,
+        automation: Option<Arc<Vec<(f32, f32)>>>,
+    ) -> Self {
+        Self {
+            source,
+            control,
+            automation,
+            damping: 1.0 / resonance.max(0.5),
+            coefficients: (0.0, 0.0, 0.0),
+            state: [(0.0, 0.0); 2],
+            frame: 0,
+            channel: 0,
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        let rate = self.source.sample_rate().max(1) as f32;
+        let time_ms = self.frame as f32 * 1_000.0 / rate;
+        let cutoff = self
+            .automation
+            .as_deref()
+            .and_then(|points| automation_value(points, time_ms))
+            .unwrap_or_else(|| self.control.cutoff())
+            .clamp(MIN_FILTER_CUTOFF_HZ, MAX_FILTER_CUTOFF_HZ.min(rate * 0.45));
+        self.coefficients = svf_coefficients(cutoff, rate, self.damping);
+    }
+}
+
+fn svf_coefficients(cutoff: f32, rate: f32, damping: f32) -> (f32, f32, f32) {
+    let g = (std::f32::consts::PI * cutoff / rate).tan();
+    let a1 = 1.0 / (1.0 + g * (g + damping));
+    let a2 = g * a1;
+    (a1, a2, g * a2)
+}
+
+fn svf_low_pass((a1, a2, a3): (f32, f32, f32), (ic1, ic2): &mut (f32, f32), input: f32) -> f32 {
+    let v3 = input - *ic2;
+    let v1 = a1 * *ic1 + a2 * v3;
+    let v2 = *ic2 + a2 * *ic1 + a3 * v3;
+    *ic1 = 2.0 * v1 - *ic1;
+    *ic2 = 2.0 * v2 - *ic2;
+    v2
+}
+
+impl<S> Iterator for WahFilter<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let input = self.source.next()?;
+        if self.channel == 0 {
+            if self.frame.is_multiple_of(FILTER_UPDATE_FRAMES) {
+                self.update_coefficients();
+            }
+            self.frame += 1;
+        }
+        let state = &mut self.state[usize::from(self.channel.min(1))];
+        let output = svf_low_pass(self.coefficients, state, input);
+        self.channel = (self.channel + 1) % self.source.channels().max(1);
+        Some(output)
+    }
+}
+
+impl<S> Source for WahFilter<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+// Low-pass whose cutoff follows the input's own envelope: louder playing opens the filter,
+// sweeping from `low_hz` to `high_hz` on an exponential scale.
+pub struct AutoWah<S> {
+    source: S,
+    gain: f32,
+    low_hz: f32,
+    high_hz: f32,
+    attack: f32,
+    release: f32,
+    damping: f32,
+    envelope: f32,
+    coefficients: (f32, f32, f32),
+    state: [(f32, f32); 2],
+    channel: u16,
+}
+
+impl<S> AutoWah<S>
+where
+    S: Source<Item = f32>,
+{
+    // `sensitivity` runs 0..=1 and boosts the followed envelope by up to 30 times.
+    pub fn new(
+        source: S,
+        sensitivity: f32,
+        low_hz: f32,
+        high_hz: f32,
+        attack_ms: f32,
+        release_ms: f32,
+        resonance: f32,
+    ) -> Self {
+        let samples_per_ms =
+            source.sample_rate().max(1) as f32 * f32::from(source.channels().max(1)) / 1_000.0;
+        let smoothing = |ms: f32| 1.0 - (-1.0 / (ms.max(0.1) * samples_per_ms)).exp();
+        let low_hz = low_hz.clamp(MIN_FILTER_CUTOFF_HZ, MAX_FILTER_CUTOFF_HZ);
+        Self {
+            gain: 30f32.powf(sensitivity.clamp(0.0, 1.0)),
+            low_hz,
+            high_hz: high_hz.clamp(low_hz, MAX_FILTER_CUTOFF_HZ),
+            attack: smoothing(attack_ms),
+            release: smoothing(release_ms),
+            damping: 1.0 / resonance.max(0.5),
+            envelope: 0.0,
+            coefficients: (0.0, 0.0, 0.0),
+            state: [(0.0, 0.0); 2],
+            channel: 0,
+            source,
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        let rate = self.source.sample_rate().max(1) as f32;
+        let position = (self.envelope * self.gain).min(1.0);
+        let cutoff = (self.low_hz * (self.high_hz / self.low_hz).powf(position))
+            .min(MAX_FILTER_CUTOFF_HZ.min(rate * 0.45));
+        self.coefficients = svf_coefficients(cutoff, rate, self.damping);
+    }
+}
+
+impl<S> Iterator for AutoWah<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let input = self.source.next()?;
+        let level = input.abs();
+        let smoothing = if level > self.envelope {
+            self.attack
+        } else {
+            self.release
+        };
+        self.envelope += (level - self.envelope) * smoothing;
+        if self.channel == 0 {
+            self.update_coefficients();
+        }
+        let state = &mut self.state[usize::from(self.channel.min(1))];
+        let output = svf_low_pass(self.coefficients, state, input);
+        self.channel = (self.channel + 1) % self.source.channels().max(1);
+        Some(output)
+    }
+}
+
+impl<S> Source for AutoWah<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct LevelMeter(Arc<[AtomicU32; 2]>);
+
+impl LevelMeter {
+    pub fn take_peaks(&self) -> [f32; 2] {
+        [0, 1].map(|channel| f32::from_bits(self.0[channel].swap(0, Ordering::Relaxed)))
+    }
+
+    fn record(&self, channel: usize, sample: f32) {
+        // Positive f32 bit patterns order the same as their values.
+        let level = sample.abs().to_bits();
+        self.0[channel].fetch_max(level, Ordering::Relaxed);
+    }
+}
+
+pub const SCOPE_FRAMES: usize = 2_048;
+
+struct ScopeRing {
+    samples: [AtomicU32; SCOPE_FRAMES],
+    write: AtomicUsize,
+}
+
+// Rolling window of the mono output for drawing; the audio thread never waits on the UI.
+#[derive(Clone)]
+pub struct Scope(Arc<ScopeRing>);
+
+impl Default for Scope {
+    fn default() -> Self {
+        Self(Arc::new(ScopeRing {
+            samples: std::array::from_fn(|_| AtomicU32::new(0)),
+            write: AtomicUsize::new(0),
+        }))
+    }
+}
+
+impl Scope {
+    // Oldest frame first.
+    pub fn snapshot(&self) -> Vec<f32> {
+        let write = self.0.write.load(Ordering::Relaxed);
+        (0..SCOPE_FRAMES)
+            .map(|i| {
+                f32::from_bits(self.0.samples[(write + i) % SCOPE_FRAMES].load(Ordering::Relaxed))
+            })
+            .collect()
+    }
+
+    fn push(&self, sample: f32) {
+        let index = self.0.write.fetch_add(1, Ordering::Relaxed) % SCOPE_FRAMES;
+        self.0.samples[index].store(sample.to_bits(), Ordering::Relaxed);
+    }
+}
+
+pub struct Metered<S> {
+    source: S,
+    meter: LevelMeter,
+    scope: Scope,
+    channel: usize,
+    frame_sum: f32,
+}
+
+impl<S> Metered<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, meter: LevelMeter, scope: Scope) -> Self {
+        Self {
+            source,
+            meter,
+            scope,
+            channel: 0,
+            frame_sum: 0.0,
+        }
+    }
+}
+
+impl<S> Iterator for Metered<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.source.next()?;
+        self.meter.record(self.channel.min(1), sample);
+        let channels = usize::from(self.source.channels().max(1));
+        self.frame_sum += sample;
+        self.channel = (self.channel + 1) % channels;
+        if self.channel == 0 {
+            self.scope.push(self.frame_sum / channels as f32);
+            self.frame_sum = 0.0;
+        }
+        Some(sample)
+    }
+}
+
+impl<S> Source for Metered<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DriveCurve {
+    SoftClip,
+    HardClip,
+    Asymmetric,
+}
+
+impl DriveCurve {
+    pub const ALL: [Self; 3] = [Self::SoftClip, Self::HardClip, Self::Asymmetric];
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::SoftClip => Text::DriveSoftClip,
+            Self::HardClip => Text::DriveHardClip,
+            Self::Asymmetric => Text::DriveAsymmetric,
+        }
+    }
+
+    fn shape(self, x: f32) -> f32 {
+        match self {
+            Self::SoftClip => x.tanh(),
+            Self::HardClip => x.clamp(-1.0, 1.0),
+            // The negative half flattens earlier, adding even harmonics.
+            Self::Asymmetric if x >= 0.0 => x.tanh(),
+            Self::Asymmetric => 0.5 * (2.0 * x).tanh(),
+        }
+    }
+}
+
+// Level the compensation is tuned for, roughly where a normalized bite sits.
+const DRIVE_REFERENCE_LEVEL: f32 = 0.5;
+
+pub struct Overdrive<S> {
+    source: S,
+    curve: DriveCurve,
+    drive: f32,
+    output: f32,
+    mix: f32,
+    // One-pole DC blocker per channel; only the asymmetric curve needs it.
+    dc_in: Vec<f32>,
+    dc_out: Vec<f32>,
+    channel: usize,
+}
+
+impl<S> Overdrive<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, curve: DriveCurve, drive_db: f32, level_db: f32, mix: f32) -> Self {
+        let channels = usize::from(source.channels().max(1));
+        let drive = db_to_gain(drive_db.max(0.0));
+        Self {
+            source,
+            curve,
+            drive,
+            output: db_to_gain(level_db) / Self::drive_gain(curve, drive),
+            mix: mix.clamp(0.0, 1.0),
+            dc_in: vec![0.0; channels],
+            dc_out: vec![0.0; channels],
+            channel: 0,
+        }
+    }
+
+    // RMS gain of the shaper on a reference sine, so more drive adds grit, not level.
+    fn drive_gain(curve: DriveCurve, drive: f32) -> f32 {
+        const STEPS: usize = 256;
+        let (dry, wet) = (0..STEPS).fold((0.0, 0.0), |(dry, wet), step| {
+            let x =
+                DRIVE_REFERENCE_LEVEL * (step as f32 / STEPS as f32 * std::f32::consts::TAU).sin();
+            let y = curve.shape(x * drive);
+            (dry + x * x, wet + y * y)
+        });
+        (wet / dry).sqrt().max(1e-3)
+    }
+}
+
+impl<S> Iterator for Overdrive<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let dry = self.source.next()?;
+        let mut wet = self.curve.shape(dry * self.drive) * self.output;
+        if self.curve == DriveCurve::Asymmetric {
+            let channel = self.channel;
+            let blocked = wet - self.dc_in[channel] + 0.995 * self.dc_out[channel];
+            self.dc_in[channel] = wet;
+            self.dc_out[channel] = blocked;
+            wet = blocked;
+        }
+        self.channel = (self.channel + 1) % self.dc_in.len();
+        Some(dry + (wet - dry) * self.mix)
+    }
+}
+
+impl<S> Source for Overdrive<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+pub struct BitCrush<S> {
+    source: S,
+    steps: f32,
+    hold_frames: u32,
+    mix: f32,
+    held: Vec<f32>,
+    frame_index: u32,
+    channel: usize,
+}
+
+impl<S> BitCrush<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, bits: u32, downsample: u32, mix: f32) -> Self {
+        let channels = usize::from(source.channels().max(1));
+        Self {
+            source,
+            steps: 2f32.powi(bits.clamp(1, 16) as i32 - 1),
+            hold_frames: downsample.max(1),
+            mix: mix.clamp(0.0, 1.0),
+            held: vec![0.0; channels],
+            frame_index: 0,
+            channel: 0,
+        }
+    }
+}
+
+impl<S> Iterator for BitCrush<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let dry = self.source.next()?;
+        if self.frame_index == 0 {
+            // Rounding (mid-tread) keeps silence at zero, so no DC offset.
+            self.held[self.channel] = (dry * self.steps).round() / self.steps;
+        }
+        let wet = self.held[self.channel];
+
+        self.channel += 1;
+        if self.channel >= self.held.len() {
+            self.channel = 0;
+            self.frame_index = (self.frame_index + 1) % self.hold_frames;
+        }
+        Some(dry + (wet - dry) * self.mix)
+    }
+}
+
+impl<S> Source for BitCrush<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.source.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.source.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.source.total_duration()
+    }
+}
+
+const COMB_TUNING: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+const STEREO_SPREAD: usize = 23;
+const REVERB_INPUT_GAIN: f32 = 0.015;
+
+struct Comb {
+    buffer: Vec<f32>,
+    index: usize,
+    filter_store: f32,
+}
+
+impl Comb {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+            filter_store: 0.0,
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damp: f32) -> f32 {
+        let output = self.buffer[self.index];
+        self.filter_store = output * (1.0 - damp) + self.filter_store * damp;
+        self.buffer[self.index] = input + self.filter_store * feedback;
+        self.index = (self.index + 1) % self.buffer.len();
+        output
+    }
+}
+
+struct Allpass {
+    buffer: Vec<f32>,
+    index: usize,
+}
+
+impl Allpass {
+    fn new(len: usize) -> Self {
+        Self {
+            buffer: vec![0.0; len.max(1)],
+            index: 0,
+        }
+    }
+
+    fn process(&mut self, input: f32) -> f32 {
+        let delayed = self.buffer[self.index];
+        self.buffer[self.index] = input + delayed * 0.5;
+        self.index = (self.index + 1) % self.buffer.len();
+        delayed - input
+    }
+}
+
+pub struct Reverb<S> {
+    source: S,
+    sample_rate: u32,
+    combs: [Vec<Comb>; 2],
+    allpasses: [Vec<Allpass>; 2],
+    feedback: f32,
+    damp: f32,
+    wet: f32,
+    dry: f32,
+    frame: [f32; 2],
+    frame_pos: usize,
+    source_done: bool,
+    silent_frames: u32,
+}
+
+impl<S> Reverb<S>
+where
+    S: Source<Item = f32>,
+{
+    pub fn new(source: S, room_size: f32, damping: f32, mix: f32) -> Self {
+        let sample_rate = source.sample_rate();
+        let scale = |len: usize| len * sample_rate as usize / 44_100;
+        let combs = [0, STEREO_SPREAD].map(|spread| {
+            COMB_TUNING
+                .iter()
+                .map(|&len| Comb::new(scale(len + spread)))
+                .collect()
+        });
+        let allpasses = [0, STEREO_SPREAD].map(|spread| {
+            ALLPASS_TUNING
+                .iter()
+                .map(|&len| Allpass::new(scale(len + spread)))
+                .collect()
+        });
+        let mix = mix.clamp(0.0, 1.0);
+        Self {
+            source,
+            sample_rate,
+            combs,
+            allpasses,
+            feedback: room_size.clamp(0.0, 1.0) * 0.28 + 0.7,
+            damp: damping.clamp(0.0, 1.0) * 0.4,
+            wet: mix * 3.0,
+            dry: 1.0 - mix,
+            frame: [0.0; 2],
+            frame_pos: 2,
+            source_done: false,
+            silent_frames: 0,
+        }
+    }
+
+    fn read_input(&mut self) -> [f32; 2] {
+        if self.source_done {
+            return [0.0; 2];
+        }
+        let left = self.source.next();
+        let right = if self.source.channels() == 2 {
+            self.source.next()
+        } else {
+            left
+        };
+        match (left, right) {
+            (Some(left), Some(right)) => [left, right],
+            _ => {
+                self.source_done = true;
+                [0.0; 2]
+            }
+        }
+    }
+
+    fn render_frame(&mut self) -> bool {
+        let input = self.read_input();
+        let mono_in = (input[0] + input[1]) * REVERB_INPUT_GAIN;
+        for (channel, dry) in input.into_iter().enumerate() {
+            let mut out = self.combs[channel]
+                .iter_mut()
+                .map(|comb| comb.process(mono_in, self.feedback, self.damp))
+                .sum::<f32>();
+            for allpass in &mut self.allpasses[channel] {
+                out = allpass.process(out);
+            }
+            self.frame[channel] = dry * self.dry + out * self.wet;
+        }
+        self.frame_pos = 0;
+
+        if self.source_done {
+            if self.frame.iter().all(|s| s.abs() < 1e-5) {
+                self.silent_frames += 1;
+            } else {
+                self.silent_frames = 0;
+            }
+            return self.silent_frames < self.sample_rate / 10;
+        }
+        true
+    }
+}
+
+impl<S> Iterator for Reverb<S>
+where
+    S: Source<Item = f32>,
+{
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.frame_pos >= 2 && !self.render_frame() {
+            return None;
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+}
+
+impl<S> Source for Reverb<S>
+where
+    S: Source<Item = f32>,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum InterpQuality {
+    Nearest,
+    Linear,
+    Cubic,
+}
+
+impl InterpQuality {
+    pub const ALL: [Self; 3] = [Self::Nearest, Self::Linear, Self::Cubic];
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::Nearest => Text::InterpNearest,
+            Self::Linear => Text::InterpLinear,
+            Self::Cubic => Text::InterpCubic,
+        }
+    }
+}
+
+// Loop bounds shared with every looping voice, so moving the handles retunes held notes.
+// An end of 0 means "no loop": voices play through to the end of the clip.
+#[derive(Clone, Default)]
+pub struct LoopControl {
+    start: Arc<AtomicUsize>,
+    end: Arc<AtomicUsize>,
+}
+
+impl LoopControl {
+    pub fn set(&self, bounds: Option<(usize, usize)>) {
+        let (start, end) = bounds.unwrap_or((0, 0));
+        self.start.store(start, Ordering::Relaxed);
+        self.end.store(end, Ordering::Relaxed);
+    }
+
+    fn bounds(&self) -> Option<(usize, usize)> {
+        let start = self.start.load(Ordering::Relaxed);
+        let end = self.end.load(Ordering::Relaxed);
+        (end > start).then_some((start, end))
+    }
+}
+
+// Plays the clip at its own rate and wraps from the loop end back to the loop start on the
+// exact frame. The last `crossfade` frames before the end blend into the audio leading up
+// to the start, so the jump lands on matching material.
+pub struct LoopedClip {
+    samples: Arc<Vec<f32>>,
+    channels: usize,
+    sample_rate: u32,
+    control: LoopControl,
+    crossfade: usize,
+    frame: usize,
+    channel: usize,
+}
+
+impl LoopedClip {
+    pub fn new(
+        samples: Arc<Vec<f32>>,
+        channels: u16,
+        sample_rate: u32,
+        control: LoopControl,
+        crossfade: usize,
+    ) -> Self {
+        Self {
+            samples,
+            channels: usize::from(channels.max(1)),
+            sample_rate,
+            control,
+            crossfade,
+            frame: 0,
+            channel: 0,
+        }
+    }
+}
+
+impl Iterator for LoopedClip {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let frames = self.samples.len() / self.channels;
+        let bounds = self
+            .control
+            .bounds()
+            .map(|(start, end)| (start, end.min(frames)))
+            .filter(|&(start, end)| end > start);
+        if let Some((start, end)) = bounds {
+            if self.frame >= end {
+                self.frame = start;
+            }
+        }
+        if self.frame >= frames {
+            return None;
+        }
+
+        let value = |frame: usize| self.samples[frame * self.channels + self.channel];
+        let mut sample = value(self.frame);
+        if let Some((start, end)) = bounds {
+            let crossfade = self.crossfade.min(start).min((end - start) / 2);
+            let remaining = end.saturating_sub(self.frame);
+            if remaining <= crossfade && remaining > 0 {
+                let t = (crossfade + 1 - remaining) as f32 / crossfade as f32;
+                sample += (value(start - remaining) - sample) * t;
+            }
+        }
+
+        self.channel += 1;
+        if self.channel >= self.channels {
+            self.channel = 0;
+            self.frame += 1;
+        }
+        Some(sample)
+    }
+}
+
+impl Source for LoopedClip {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+pub struct Resampler {
+    samples: Arc<Vec<f32>>,
+    channels: usize,
+    sample_rate: u32,
+    ratio: f64,
+    quality: InterpQuality,
+    position: f64,
+    channel: usize,
+}
+
+impl Resampler {
+    pub fn new(
+        samples: Arc<Vec<f32>>,
+        channels: u16,
+        sample_rate: u32,
+        ratio: f32,
+        quality: InterpQuality,
+    ) -> Self {
+        Self {
+            samples,
+            channels: usize::from(channels.max(1)),
+            sample_rate,
+            ratio: f64::from(ratio.max(0.01)),
+            quality,
+            position: 0.0,
+            channel: 0,
+        }
+    }
+
+    fn frame_value(&self, frame: isize) -> f32 {
+        let frames = (self.samples.len() / self.channels) as isize;
+        if frames == 0 {
+            return 0.0;
+        }
+        let frame = frame.clamp(0, frames - 1) as usize;
+        self.samples[frame * self.channels + self.channel]
+    }
+}
+
+impl Iterator for Resampler {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let frames = self.samples.len() / self.channels;
+        if self.position >= frames as f64 {
+            return None;
+        }
+        let base = self.position.floor() as isize;
+        let t = (self.position - base as f64) as f32;
+        let sample = match self.quality {
+            InterpQuality::Nearest => self.frame_value(self.position.round() as isize),
+            InterpQuality::Linear => {
+                let (a, b) = (self.frame_value(base), self.frame_value(base + 1));
+                a + (b - a) * t
+            }
+            InterpQuality::Cubic => {
+                // Catmull-Rom through the four surrounding frames.
+                let p0 = self.frame_value(base - 1);
+                let p1 = self.frame_value(base);
+                let p2 = self.frame_value(base + 1);
+                let p3 = self.frame_value(base + 2);
+                p1 + 0.5
+                    * t
+                    * (p2 - p0
+                        + t * (2.0 * p0 - 5.0 * p1 + 4.0 * p2 - p3
+                            + t * (3.0 * (p1 - p2) + p3 - p0)))
+            }
+        };
+
+        self.channel += 1;
+        if self.channel >= self.channels {
+            self.channel = 0;
+            self.position += self.ratio;
+        }
+        Some(sample)
+    }
+}
+
+impl Source for Resampler {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+pub struct GranularFreeze {
+    samples: Arc<Vec<f32>>,
+    channels: usize,
+    sample_rate: u32,
+    grain_frames: usize,
+    center_frame: f32,
+    ratio: f32,
+    grains: [Grain; 2],
+    frame: Vec<f32>,
+    frame_pos: usize,
+    jitter_state: u32,
+}
+
+#[derive(Clone, Copy)]
+struct Grain {
+    start: f32,
+    phase: usize,
+}
+
+impl GranularFreeze {
+    pub fn new(
+        samples: Arc<Vec<f32>>,
+        channels: u16,
+        sample_rate: u32,
+        grain_ms: f32,
+        position: f32,
+        ratio: f32,
+    ) -> Self {
+        let channels = usize::from(channels.max(1));
+        let total_frames = samples.len() / channels;
+        let grain_frames =
+            ((sample_rate as f32 * grain_ms / 1_000.0) as usize).clamp(16, total_frames.max(16));
+        let center_frame = position.clamp(0.0, 1.0) * total_frames as f32;
+        let mut freeze = Self {
+            samples,
+            channels,
+            sample_rate,
+            grain_frames,
+            center_frame,
+            ratio: ratio.max(0.01),
+            grains: [Grain {
+                start: 0.0,
+                phase: 0,
+            }; 2],
+            frame: vec![0.0; channels],
+            frame_pos: channels,
+            jitter_state: 0x9E37_79B9,
+        };
+        freeze.grains[0].start = freeze.next_grain_start();
+        freeze.grains[1] = Grain {
+            start: freeze.next_grain_start(),
+            phase: grain_frames / 2,
+        };
+        freeze
+    }
+
+    fn next_grain_start(&mut self) -> f32 {
+        self.jitter_state ^= self.jitter_state << 13;
+        self.jitter_state ^= self.jitter_state >> 17;
+        self.jitter_state ^= self.jitter_state << 5;
+        let jitter = (self.jitter_state as f32 / u32::MAX as f32 - 0.5) * 0.2;
+
+        let total_frames = (self.samples.len() / self.channels) as f32;
+        let span = self.grain_frames as f32 * self.ratio;
+        let start = self.center_frame - span * 0.5 + jitter * self.grain_frames as f32;
+        start.clamp(0.0, (total_frames - span - 1.0).max(0.0))
+    }
+
+    fn render_frame(&mut self) {
+        self.frame.iter_mut().for_each(|s| *s = 0.0);
+        let total_frames = self.samples.len() / self.channels;
+
+        for index in 0..self.grains.len() {
+            let grain = self.grains[index];
+            let t = grain.phase as f32 / self.grain_frames as f32;
+            let window = 0.5 - 0.5 * (2.0 * std::f32::consts::PI * t).cos();
+            let read = grain.start + grain.phase as f32 * self.ratio;
+            let base = read.floor() as usize;
+            let frac = read - base as f32;
+
+            if base + 1 < total_frames {
+                for channel in 0..self.channels {
+                    let a = self.samples[base * self.channels + channel];
+                    let b = self.samples[(base + 1) * self.channels + channel];
+                    self.frame[channel] += (a + (b - a) * frac) * window;
+                }
+            }
+
+            self.grains[index].phase += 1;
+            if self.grains[index].phase >= self.grain_frames {
+                self.grains[index] = Grain {
+                    start: self.next_grain_start(),
+                    phase: 0,
+                };
+            }
+        }
+        self.frame_pos = 0;
+    }
+}
+
+impl Iterator for GranularFreeze {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.samples.len() < self.channels * 2 {
+            return None;
+        }
+        if self.frame_pos >= self.channels {
+            self.render_frame();
+        }
+        let sample = self.frame[self.frame_pos];
+        self.frame_pos += 1;
+        Some(sample)
+    }
+}
+
+impl Source for GranularFreeze {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels as u16
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::source::SineWave;
+
+    #[test]
+    fn looped_clip_repeats_between_loop_points() {
+        let samples = Arc::new((0..10).map(|i| i as f32).collect::<Vec<f32>>());
+        let control = LoopControl::default();
+        control.set(Some((4, 8)));
+        let mut looped = LoopedClip::new(Arc::clone(&samples), 1, 44_100, control.clone(), 0);
+
+        let played: Vec<f32> = looped.by_ref().take(14).collect();
+        assert_eq!(
+            played,
+            [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 4.0, 5.0, 6.0, 7.0, 4.0, 5.0]
+        );
+
+        // Moving the loop while a voice plays takes effect on its next wrap.
+        control.set(Some((2, 4)));
+        let played: Vec<f32> = looped.by_ref().take(4).collect();
+        assert_eq!(played, [2.0, 3.0, 2.0, 3.0]);
+
+        // Clearing the loop lets the voice run out.
+        control.set(None);
+        assert_eq!(looped.count(), 6);
+
+        // Stereo frames stay together, and the crossfade ends on the frame just before the
+        // loop start, so the wrap continues seamlessly.
+        let stereo = Arc::new((0..20).map(|i| (i / 2) as f32).collect::<Vec<f32>>());
+        control.set(Some((4, 8)));
+        let played: Vec<f32> = LoopedClip::new(stereo, 2, 44_100, control, 2)
+            .take(24)
+            .collect();
+        assert_eq!(
+            played,
+            [
+                0.0, 0.0, 1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0, 4.0, 5.0, 5.0, 4.0, 4.0, 3.0, 3.0,
+                4.0, 4.0, 5.0, 5.0, 4.0, 4.0, 3.0, 3.0,
+            ]
+        );
+    }
+
+    #[test]
+    fn bend_follows_the_control_while_playing() {
+        let ramp: Vec<f32> = (0..200).map(|i| (i / 2) as f32).collect();
+        let control = BendControl::default();
+        let mut bent = Bend::new(
+            rodio::buffer::SamplesBuffer::new(2, 44_100, ramp),
+            control.clone(),
+        );
+
+        // Unbent, the stereo frames come through untouched.
+        let played: Vec<f32> = bent.by_ref().take(6).collect();
+        assert_eq!(played, [0.0, 0.0, 1.0, 1.0, 2.0, 2.0]);
+
+        // An octave up skips every other frame; a fifth down lands between frames.
+        control.set_semitones(12.0);
+        let played: Vec<f32> = bent.by_ref().take(6).collect();
+        assert_eq!(played, [3.0, 3.0, 5.0, 5.0, 7.0, 7.0]);
+        control.set_semitones(-12.0);
+        let played: Vec<f32> = bent.by_ref().take(6).collect();
+        assert_eq!(played, [9.0, 9.0, 9.5, 9.5, 10.0, 10.0]);
+
+        // The voice still ends when its source does.
+        control.set_semitones(0.0);
+        assert_eq!(bent.count(), 2 * (100 - 11));
+    }
+
+    #[test]
+    fn stereo_width_scales_the_side_signal() {
+        let frames = vec![1.0, 0.0, 0.5, 0.5, -0.25, 0.25];
+        let widened = |width: f32| -> Vec<f32> {
+            let source = rodio::buffer::SamplesBuffer::new(2, 44_100, frames.clone());
+            StereoWidth::new(source, width).collect()
+        };
+
+        assert_eq!(widened(1.0), frames);
+        assert_eq!(widened(0.0), vec![0.5, 0.5, 0.5, 0.5, 0.0, 0.0]);
+        assert_eq!(widened(2.0), vec![1.5, -0.5, 0.5, 0.5, -0.5, 0.5]);
+        assert_eq!(widened(10.0), widened(MAX_STEREO_WIDTH));
+    }
+
+    #[test]
+    fn overdrive_adds_drive_without_adding_level() {
+        let rms = |curve: DriveCurve, drive_db: f32| {
+            let driven =
+                Overdrive::new(SineWave::new(220.0).amplify(0.5), curve, drive_db, 0.0, 1.0);
+            let samples: Vec<f32> = driven.skip(4_800).take(48_000).collect();
+            (samples.iter().map(|s| s * s).sum::<f32>() / samples.len() as f32).sqrt()
+        };
+
+        for curve in DriveCurve::ALL {
+            let clean = rms(curve, 0.0);
+            let driven = rms(curve, 30.0);
+            let change_db = 20.0 * (driven / clean).log10();
+            assert!(
+                change_db.abs() < 1.0,
+                "{:?} changed level by {change_db} dB",
+                curve.label()
+            );
+        }
+    }
+
+    #[test]
+    fn auto_wah_opens_with_level() {
+        // Steady-state level of a 2 kHz tone after the filter, relative to its input.
+        let pass_through = |amplitude: f32| {
+            let wah = AutoWah::new(
+                SineWave::new(2_000.0).amplify(amplitude),
+                0.0,
+                200.0,
+                8_000.0,
+                5.0,
+                50.0,
+                1.0,
+            );
+            let peak = wah
+                .skip(24_000)
+                .take(4_800)
+                .fold(0.0f32, |peak, s| peak.max(s.abs()));
+            peak / amplitude
+        };
+
+        let quiet = pass_through(0.02);
+        let loud = pass_through(1.0);
+        assert!(quiet < 0.1, "quiet tone passed at {quiet}");
+        assert!(loud > 0.8, "loud tone passed at {loud}");
+    }
+
+    #[test]
+    fn limiter_keeps_summed_voices_within_threshold() {
+        let (controller, mixer) = rodio::dynamic_mixer::mixer::<f32>(1, 44_100);
+        for frequency in [220.0, 330.0, 440.0, 550.0] {
+            controller.add(SineWave::new(frequency).amplify(0.8));
+        }
+        let control = LimiterControl::new(-1.0, 6.0);
+        let threshold = db_to_gain(-1.0);
+
+        let limited: Vec<f32> = Limiter::new(mixer, control).take(44_100).collect();
+
+        assert!(limited.iter().all(|s| s.abs() <= threshold));
+        assert!(limited.iter().any(|s| s.abs() > threshold * 0.9));
+    }
+
+    #[test]
+    fn release_fades_without_clicks() {
+        let sample_rate = 48_000;
+        let frequency = 440.0;
+        // Largest step a full-scale sine takes on its own between two samples.
+        let sine_slope = std::f32::consts::TAU * frequency / sample_rate as f32;
+        let fade_ms = 10;
+        let fade_samples = (sample_rate * fade_ms / 1_000) as f32;
+
+        for curve in ReleaseCurve::ALL {
+            let handle = ReleaseHandle::new();
+            let mut voice = Releasable::new(SineWave::new(frequency), handle.clone());
+            // SineWave reports 48 kHz mono, so the fade length is exact.
+            assert_eq!(voice.sample_rate(), sample_rate);
+            let mut output: Vec<f32> = voice.by_ref().take(1_000).collect();
+            handle.release(fade_ms, curve);
+            output.extend(voice.by_ref().take(sample_rate as usize));
+
+            assert_eq!(output.len(), 1_000 + fade_samples as usize, "{curve:?}");
+            let max_step = output
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .fold(0.0f32, f32::max);
+            let fade_slope = ReleaseCurve::EXPONENTIAL_RATE / fade_samples;
+            assert!(
+                max_step <= sine_slope + fade_slope,
+                "{curve:?}: step {max_step}"
+            );
+            assert!(
+                output.last().unwrap().abs() < 0.01,
+                "{curve:?} ends on a jump"
+            );
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use crate::clip::MemoryClip;
+
+// Short synthesized one-shots (22.05 kHz mono, about 200 kB in all), each with a `smpl`
+// root note so the base note follows the pitch it was made at.
+pub struct BundledSample {
+    pub name: &'static str,
+    bytes: &'static [u8],
+}
+
+pub const BUNDLED: [BundledSample; 4] = [
+    BundledSample {
+        name: "Pluck",
+        bytes: include_bytes!("../assets/samples/pluck.wav"),
+    },
+    BundledSample {
+        name: "Marimba",
+        bytes: include_bytes!("../assets/samples/marimba.wav"),
+    },
+    BundledSample {
+        name: "Bell",
+        bytes: include_bytes!("../assets/samples/bell.wav"),
+    },
+    BundledSample {
+        name: "Kick",
+        bytes: include_bytes!("../assets/samples/kick.wav"),
+    },
+];
+
+impl BundledSample {
+    pub fn clip(&self) -> MemoryClip {
+        MemoryClip {
+            name: format!("{}.wav", self.name),
+            bytes: Arc::new(self.bytes.to_vec()),
+            extension: Some("wav".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clip::SampleClip;
+
+    #[test]
+    fn bundled_samples_decode_with_their_root_note() {
+        for (sample, root) in BUNDLED.iter().zip([60, 67, 72, 60]) {
+            let clip = SampleClip::from_memory(&sample.clip(), 500, false, None, None).unwrap();
+            assert_eq!(clip.root_midi, Some(root), "{}", sample.name);
+            assert_eq!(clip.frames(), 22_050 / 2, "{}", sample.name);
+            assert!(
+                clip.samples.iter().any(|s| s.abs() > 0.1),
+                "{}",
+                sample.name
+            );
+        }
+    }
+}
@@ -1,16 +1,48 @@
 use std::{
-    fs::File,
+    collections::{BTreeMap, HashSet, VecDeque},
     path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
 };
 
 use anyhow::{anyhow, Context, Result};
 use eframe::egui::{self, Color32, FontId, Pos2, Rect, RichText, Sense, Stroke, Vec2};
-use rodio::{buffer::SamplesBuffer, OutputStream, OutputStreamHandle, Sink, Source};
-use symphonia::core::{
-    audio::SampleBuffer, codecs::DecoderOptions, formats::FormatOptions, io::MediaSourceStream,
-    meta::MetadataOptions, probe::Hint,
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rodio::{
+    cpal::{
+        self,
+        traits::{DeviceTrait, HostTrait, StreamTrait},
+    },
+    dynamic_mixer::{self, DynamicMixerController},
+    source::{SineWave, UniformSourceIterator, Zero},
+    OutputStream, Sink, Source,
 };
+use serde::{Deserialize, Serialize};
+
+mod clip;
+mod dsp;
+mod library;
+mod midi_in;
+mod midi_out;
+mod osc;
+mod paste;
+mod pitch;
+mod preset;
+mod raw;
+mod remote;
+mod render;
+mod riff;
+mod screenshot;
+mod strings;
+mod voice_cache;
+
+use clip::{ClipSource, MemoryClip, PendingLoad, SampleClip, ToneSettings, ToneWaveform};
+use preset::{EffectSnapshot, Preset, PRESET_EXTENSION};
+use strings::{Language, Text};
 
 const BASE_MIDI_NOTE: i32 = 60; // C4
 const PIANO_START_MIDI: i32 = 48; // C3
@@ -18,177 +50,882 @@ const PIANO_END_MIDI: i32 = 84; // C6
 const DEFAULT_BITE_MS: u32 = 500;
 const MIN_BITE_MS: u32 = 500;
 const MAX_BITE_MS: u32 = 5_000;
+const DEFAULT_MAX_VOICES: usize = 16;
+const MAX_VOICE_LIMIT: usize = 64;
+// Above these the mixer may not keep up on slower machines; every effect is per voice.
+const SAFE_VOICES: usize = 32;
+const SAFE_VOICES_WITH_EFFECTS: usize = 16;
+// Releasing tails may sound alongside held voices, up to this many times the limit.
+const VOICE_TAIL_FACTOR: usize = 2;
+// The voice list is sized once for the highest limit so note-ons never grow it.
+const VOICE_SLOTS: usize = MAX_VOICE_LIMIT * VOICE_TAIL_FACTOR;
+const VOICE_STEAL_FLASH_S: f64 = 0.8;
+const SCREENSHOT_TIMEOUT_S: f64 = 2.0;
+const BYPASS_MATCH_MS: u32 = 1_000;
+const BYPASS_MATCH_RANGE_DB: f32 = 12.0;
+// Slider drags change the effects every frame; the level is matched once they settle.
+const BYPASS_MATCH_DEBOUNCE_S: f64 = 0.3;
+const MIX_SAMPLE_RATE: u32 = 44_100;
+const DEFAULT_SMOOTHING_MS: u32 = 15;
+const MAX_SMOOTHING_MS: u32 = 100;
+const NOTE_OFF_FADE_MS: u32 = 60;
+const DEFAULT_ATTACK_MS: u32 = 3;
+const MAX_ATTACK_MS: u32 = 50;
+const SEQUENCER_STEPS: usize = 16;
+const MAX_NUDGE_FRAMES: usize = 4_800;
+const MIN_WHITE_KEY_WIDTH: f32 = 24.0;
+const MAX_WHITE_KEY_WIDTH: f32 = 64.0;
+const WHITE_KEY_ASPECT: f32 = 180.0 / 44.0;
+const BLACK_KEY_WIDTH_RATIO: f32 = 28.0 / 44.0;
+const BLACK_KEY_HEIGHT_RATIO: f32 = 112.0 / 180.0;
+const KEY_LABEL_FONT_RATIO: f32 = 0.28;
+const MIN_KEY_LABEL_FONT: f32 = 9.0;
+const MAX_KEY_LABEL_FONT: f32 = 16.0;
+const LANDMARK_LABELS_BELOW_WIDTH: f32 = 32.0;
+const DEFAULT_FULL_DECODE_S: u32 = 60;
+const MAX_FULL_DECODE_S: u32 = 600;
+const MIN_RATE_OVERRIDE_HZ: u32 = 1_000;
+const MAX_RATE_OVERRIDE_HZ: u32 = 384_000;
+const MIN_PITCH_CONFIDENCE: f32 = 0.8;
+const AUTO_LEVEL_TARGET_DB: f32 = -18.0;
+const AUTO_LEVEL_RANGE_DB: f32 = 18.0;
+const BITE_HISTORY_LIMIT: usize = 50;
+const STATUS_LOG_LIMIT: usize = 50;
+const BROWSE_FOLDER_KEY: &str = "browse_folder";
+const LATENCY_MODE_KEY: &str = "latency_mode";
+const LANGUAGE_KEY: &str = "language";
+const COMPACT_KEY: &str = "compact_layout";
+const FULL_DECODE_KEY: &str = "full_decode_seconds";
+const SCRATCH_WAV_KEY: &str = "scratch_wav";
+const KEY_HIT_SCALE_KEY: &str = "key_hit_scale";
+const MAX_VOICES_KEY: &str = "max_voices";
+const SCRATCH_WAV_NAME: &str = "openwah-bite.wav";
+// Slider drags change the bite every frame; it is written once they settle.
+const SCRATCH_WAV_DEBOUNCE_S: f64 = 0.5;
+const NOTE_SHORTCUTS: [(egui::Key, i32); 13] = [
+    (egui::Key::A, 60),
+    (egui::Key::W, 61),
+    (egui::Key::S, 62),
+    (egui::Key::E, 63),
+    (egui::Key::D, 64),
+    (egui::Key::F, 65),
+    (egui::Key::T, 66),
+    (egui::Key::G, 67),
+    (egui::Key::Y, 68),
+    (egui::Key::H, 69),
+    (egui::Key::U, 70),
+    (egui::Key::J, 71),
+    (egui::Key::K, 72),
+];
+const TAP_KEYS: [egui::Key; 5] = [
+    egui::Key::B,
+    egui::Key::N,
+    egui::Key::M,
+    egui::Key::Period,
+    egui::Key::Backtick,
+];
+const TAP_HISTORY: usize = 6;
+const MAX_EFFECT_SNAPSHOTS: usize = 5;
+const MAX_DRIVE_DB: f32 = 36.0;
+const MAX_TRANSPOSE: i32 = 24;
+const MAX_KEY_HIT_SCALE: f32 = 2.0;
+const MAX_BEND_RANGE: f32 = 12.0;
+// Vertical drag that reaches the full bend range, after a dead zone that keeps taps in tune.
+const BEND_DRAG_POINTS: f32 = 100.0;
+const BEND_DEAD_ZONE_POINTS: f32 = 4.0;
+const TAP_RESET_S: f64 = 2.0;
+const LOW_LATENCY_BUFFER_FRAMES: u32 = 256;
+const HOLD_VELOCITY_SHORT_S: f64 = 0.05;
+const HOLD_VELOCITY_LONG_S: f64 = 1.0;
+const HOLD_VELOCITY_MIN: f32 = 0.3;
+const MIDI_OUT_DEFAULT_VELOCITY: u8 = 100;
+const ZERO_SNAP_RADIUS_MS: u32 = 10;
+const MIN_LOOP_FRAMES: usize = 64;
+const LOOP_CROSSFADE_MS: u32 = 10;
+const MAX_WAVEFORM_ZOOM: f32 = 64.0;
+const MAX_NOTE_TRIM_DB: f32 = 12.0;
+const REFERENCE_LEVEL: f32 = 0.25;
+const METER_FLOOR_DB: f32 = -60.0;
+const METER_FALL_DB_PER_S: f32 = 24.0;
+const PEAK_HOLD_S: f64 = 1.5;
+const HUMANIZE_MAX_CENTS: f32 = 12.0;
+const HUMANIZE_MAX_DELAY_MS: f32 = 20.0;
+const HUMANIZE_MAX_GAIN_DB: f32 = 3.0;
+const HUMANIZE_MAX_PAN: f32 = 0.2;
+const MAX_START_JITTER_MS: u32 = 200;
+const REVERSE_ATTACK_CROSSFADE_MS: u32 = 5;
+const FILL_LOOP_CROSSFADE_MS: u32 = 20;
+const MAX_AUTOMATION_MS: f32 = 10_000.0;
 
 fn main() -> eframe::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("--render") {
+        if let Err(err) = render::run_cli(&args[1..]) {
+            eprintln!("render failed: {err:#}");
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
     let options = eframe::NativeOptions::default();
     eframe::run_native(
         "OpenWah - Sample Piano",
         options,
-        Box::new(|_cc| {
-            let audio = AudioEngine::new().unwrap_or_else(|err| {
-                eprintln!("audio initialization failed: {err:#}");
-                AudioEngine::silent_fallback()
-            });
-            Ok(Box::new(SamplePianoApp::new(audio)))
+        Box::new(|cc| {
+            let latency = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, LATENCY_MODE_KEY))
+                .unwrap_or_default();
+            let mut app = SamplePianoApp::new(AudioEngine::open(latency));
+            app.latency = latency;
+            app.language = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, LANGUAGE_KEY))
+                .unwrap_or_default();
+            app.compact = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, COMPACT_KEY))
+                .unwrap_or_default();
+            app.key_hit_scale = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, KEY_HIT_SCALE_KEY))
+                .unwrap_or(1.0f32)
+                .clamp(1.0, MAX_KEY_HIT_SCALE);
+            app.max_voices = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, MAX_VOICES_KEY))
+                .unwrap_or(DEFAULT_MAX_VOICES)
+                .clamp(1, MAX_VOICE_LIMIT);
+            app.scratch_wav = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, SCRATCH_WAV_KEY))
+                .unwrap_or_default();
+            app.full_decode_s = cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, FULL_DECODE_KEY))
+                .unwrap_or(DEFAULT_FULL_DECODE_S)
+                .clamp(1, MAX_FULL_DECODE_S);
+            app.refresh_midi_ports();
+            if let Some(folder) = cc
+                .storage
+                .and_then(|storage| {
+                    eframe::get_value::<Option<PathBuf>>(storage, BROWSE_FOLDER_KEY)
+                })
+                .flatten()
+            {
+                app.open_folder(folder);
+            }
+            Ok(Box::new(app))
         }),
     )
 }
 
-struct SampleClip {
-    sample_rate: u32,
-    mono_samples: Arc<Vec<f32>>,
+#[derive(Clone, Copy)]
+struct FreezeParams {
+    grain_ms: f32,
+    position: f32,
+}
+
+#[derive(Clone, Copy)]
+struct CrushParams {
+    bits: u32,
+    downsample: u32,
+    mix: f32,
 }
 
-impl SampleClip {
-    fn from_file(path: &Path, duration_ms: u32) -> Result<Self> {
-        let file = File::open(path)
-            .with_context(|| format!("failed to open selected file: {}", path.display()))?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+#[derive(Clone, Copy)]
+struct OverdriveParams {
+    curve: dsp::DriveCurve,
+    drive_db: f32,
+    level_db: f32,
+    mix: f32,
+}
 
-        let mut hint = Hint::new();
-        if let Some(ext) = path.extension().and_then(|x| x.to_str()) {
-            hint.with_extension(ext);
-        }
+#[derive(Clone, Copy)]
+struct TremoloParams {
+    rate_hz: f32,
+    depth: f32,
+    shape: dsp::LfoShape,
+}
 
-        let probed = symphonia::default::get_probe().format(
-            &hint,
-            mss,
-            &FormatOptions::default(),
-            &MetadataOptions::default(),
-        )?;
+#[derive(Clone, Copy)]
+struct ReverbParams {
+    room_size: f32,
+    damping: f32,
+    mix: f32,
+}
 
-        let mut format = probed.format;
-        let track = format
-            .default_track()
-            .ok_or_else(|| anyhow!("no playable audio track found"))?;
+#[derive(Clone)]
+struct FilterParams {
+    control: dsp::FilterControl,
+    resonance: f32,
+    automation: Option<Arc<Vec<(f32, f32)>>>,
+}
 
-        let codec_params = &track.codec_params;
-        let mut decoder =
-            symphonia::default::get_codecs().make(codec_params, &DecoderOptions::default())?;
+#[derive(Clone, Copy)]
+struct AutoWahParams {
+    sensitivity: f32,
+    low_hz: f32,
+    high_hz: f32,
+    attack_ms: f32,
+    release_ms: f32,
+    resonance: f32,
+}
 
-        let mut sample_rate = codec_params
-            .sample_rate
-            .ok_or_else(|| anyhow!("audio file missing sample rate"))?;
+#[derive(Clone)]
+struct NoteParams {
+    base_midi: i32,
+    detune_cents: f32,
+    transpose: i32,
+    pan: f32,
+    width: f32,
+    gain: f32,
+    delay_ms: f32,
+    interp: dsp::InterpQuality,
+    freeze: Option<FreezeParams>,
+    crush: Option<CrushParams>,
+    overdrive: Option<OverdriveParams>,
+    reverb: Option<ReverbParams>,
+    tremolo: Option<TremoloParams>,
+    filter: Option<FilterParams>,
+    auto_wah: Option<AutoWahParams>,
+    release_curve: dsp::ReleaseCurve,
+    release_ms: u32,
+    smoothing_ms: u32,
+    attack_ms: u32,
+    gate: bool,
+    loop_control: Option<dsp::LoopControl>,
+    prerendered: Option<Arc<Vec<f32>>>,
+    bend: Option<dsp::BendControl>,
+}
 
-        let target_frames = (sample_rate as f32 * duration_ms as f32 / 1_000.0) as usize;
-        let mut out_mono: Vec<f32> = Vec::with_capacity(target_frames);
+impl Default for NoteParams {
+    fn default() -> Self {
+        Self {
+            base_midi: BASE_MIDI_NOTE,
+            detune_cents: 0.0,
+            transpose: 0,
+            pan: 0.0,
+            width: 1.0,
+            gain: 1.0,
+            delay_ms: 0.0,
+            interp: dsp::InterpQuality::Linear,
+            freeze: None,
+            crush: None,
+            overdrive: None,
+            reverb: None,
+            tremolo: None,
+            filter: None,
+            auto_wah: None,
+            release_curve: dsp::ReleaseCurve::Linear,
+            release_ms: NOTE_OFF_FADE_MS,
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
+            attack_ms: DEFAULT_ATTACK_MS,
+            gate: false,
+            loop_control: None,
+            prerendered: None,
+            bend: None,
+        }
+    }
+}
 
-        while out_mono.len() < target_frames {
-            let packet = match format.next_packet() {
-                Ok(packet) => packet,
-                Err(symphonia::core::errors::Error::IoError(_)) => break,
-                Err(err) => return Err(err.into()),
-            };
+// Everything the processed level depends on; the match is redone when it changes.
+// Holding the clip's samples keeps their address from being reused by a later clip.
+#[derive(Clone)]
+struct BypassKey {
+    samples: Option<Arc<Vec<f32>>>,
+    base_midi: i32,
+    gain: f32,
+    tremolo_rate_hz: f32,
+    filter_play_automation: bool,
+    filter_automation: Arc<Vec<(f32, f32)>>,
+    effects: EffectSnapshot,
+}
 
-            let decoded = match decoder.decode(&packet) {
-                Ok(decoded) => decoded,
-                Err(symphonia::core::errors::Error::DecodeError(_)) => continue,
-                Err(err) => return Err(err.into()),
-            };
+impl PartialEq for BypassKey {
+    fn eq(&self, other: &Self) -> bool {
+        let same_samples = match (&self.samples, &other.samples) {
+            (Some(a), Some(b)) => Arc::ptr_eq(a, b),
+            (None, None) => true,
+            _ => false,
+        };
+        same_samples
+            && self.base_midi == other.base_midi
+            && self.gain == other.gain
+            && self.tremolo_rate_hz == other.tremolo_rate_hz
+            && self.filter_play_automation == other.filter_play_automation
+            && self.filter_automation == other.filter_automation
+            && self.effects == other.effects
+    }
+}
 
-            sample_rate = decoded.spec().rate;
-            let channels = decoded.spec().channels.count().max(1);
+struct Voice {
+    midi: i32,
+    started: Instant,
+    state: dsp::VoiceState,
+    release: dsp::ReleaseHandle,
+    released: bool,
+    gated: bool,
+}
 
-            let mut sample_buffer =
-                SampleBuffer::<f32>::new(decoded.capacity() as u64, *decoded.spec());
-            sample_buffer.copy_interleaved_ref(decoded);
-            let decoded_samples = sample_buffer.samples();
+// Rodio always opens the device with its default buffer size; for low latency the
+// stream is built directly through cpal with a small fixed buffer instead.
+fn open_low_latency_stream<S>(source: S) -> Result<cpal::Stream>
+where
+    S: Source<Item = f32> + Send + 'static,
+{
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or_else(|| anyhow!("no default audio output device found"))?;
+    let supported = device.default_output_config()?;
+    if supported.sample_format() != cpal::SampleFormat::F32 {
+        return Err(anyhow!(
+            "low-latency mode needs a 32-bit float output device, found {}",
+            supported.sample_format()
+        ));
+    }
+    let buffer_size = match *supported.buffer_size() {
+        cpal::SupportedBufferSize::Range { min, max } => LOW_LATENCY_BUFFER_FRAMES.clamp(min, max),
+        cpal::SupportedBufferSize::Unknown => LOW_LATENCY_BUFFER_FRAMES,
+    };
+    let config = cpal::StreamConfig {
+        channels: supported.channels(),
+        sample_rate: supported.sample_rate(),
+        buffer_size: cpal::BufferSize::Fixed(buffer_size),
+    };
+    let mut samples =
+        UniformSourceIterator::<_, f32>::new(source, config.channels, config.sample_rate.0);
+    let stream = device.build_output_stream(
+        &config,
+        move |data: &mut [f32], _| {
+            data.iter_mut()
+                .for_each(|sample| *sample = samples.next().unwrap_or(0.0))
+        },
+        |err| eprintln!("audio output error: {err}"),
+        None,
+    )?;
+    stream.play()?;
+    Ok(stream)
+}
 
-            for frame in decoded_samples.chunks(channels) {
-                let mixed = frame.iter().copied().sum::<f32>() / channels as f32;
-                out_mono.push(mixed);
-                if out_mono.len() >= target_frames {
-                    break;
-                }
-            }
-        }
+impl Voice {
+    fn release(&mut self, fade_ms: u32, curve: dsp::ReleaseCurve) {
+        self.release.release(fade_ms, curve);
+        self.released = true;
+    }
+}
 
-        if out_mono.is_empty() {
-            return Err(anyhow!("failed to decode audio samples from selected file"));
-        }
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+enum LatencyMode {
+    #[default]
+    Stable,
+    Low,
+}
 
-        if out_mono.len() < target_frames {
-            out_mono.resize(target_frames, 0.0);
-        } else {
-            out_mono.truncate(target_frames);
+impl LatencyMode {
+    fn label(self) -> Text {
+        match self {
+            Self::Stable => Text::LatencyStable,
+            Self::Low => Text::LatencyLow,
         }
-
-        Ok(Self {
-            sample_rate,
-            mono_samples: Arc::new(out_mono),
-        })
     }
+}
 
-    fn generated_test_tone(duration_ms: u32) -> Self {
-        let sample_rate = 44_100;
-        let target_frames = (sample_rate as f32 * duration_ms as f32 / 1_000.0) as usize;
-        let mut out_mono = Vec::with_capacity(target_frames);
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+enum PlayMode {
+    #[default]
+    OneShot,
+    Gate,
+}
 
-        for i in 0..target_frames {
-            let t = i as f32 / sample_rate as f32;
-            let envelope = (1.0 - t).max(0.0).powf(2.0);
-            let fundamental = (2.0 * std::f32::consts::PI * 261.63 * t).sin();
-            let overtone = (2.0 * std::f32::consts::PI * 523.25 * t).sin() * 0.35;
-            let sub = (2.0 * std::f32::consts::PI * 130.81 * t).sin() * 0.15;
-            let sample = (fundamental + overtone + sub) * envelope * 0.6;
-            out_mono.push(sample.clamp(-1.0, 1.0));
-        }
+impl PlayMode {
+    const ALL: [Self; 2] = [Self::OneShot, Self::Gate];
 
-        Self {
-            sample_rate,
-            mono_samples: Arc::new(out_mono),
+    fn label(self) -> Text {
+        match self {
+            Self::OneShot => Text::PlayOneShot,
+            Self::Gate => Text::PlayGate,
         }
     }
 }
 
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct KeyZone {
+    low: i32,
+    high: i32,
+    play_mode: PlayMode,
+}
+
 struct AudioEngine {
     _stream: Option<OutputStream>,
-    handle: Option<OutputStreamHandle>,
-    current_sink: Mutex<Option<Sink>>,
+    _master: Option<Sink>,
+    _direct_stream: Option<cpal::Stream>,
+    mixer: Option<Arc<DynamicMixerController<f32>>>,
+    limiter: dsp::LimiterControl,
+    meter: dsp::LevelMeter,
+    scope: dsp::Scope,
+    voices: Mutex<Vec<Voice>>,
+    // The tuning reference has its own slot, so notes never steal it or count it.
+    reference: Mutex<Option<dsp::VoiceState>>,
+    max_voices: AtomicUsize,
+    stolen: AtomicUsize,
 }
 
 impl AudioEngine {
-    fn new() -> Result<Self> {
-        let (stream, handle) =
-            OutputStream::try_default().context("no default audio output device found")?;
-        Ok(Self {
-            _stream: Some(stream),
-            handle: Some(handle),
-            current_sink: Mutex::new(None),
+    fn open(latency: LatencyMode) -> Self {
+        Self::new(latency).unwrap_or_else(|err| {
+            eprintln!("audio initialization failed: {err:#}");
+            Self::silent_fallback()
         })
     }
 
+    fn new(latency: LatencyMode) -> Result<Self> {
+        let (mixer, output) = dynamic_mixer::mixer(2, MIX_SAMPLE_RATE);
+        // Keeps the mixer running while no voices are playing.
+        mixer.add(Zero::<f32>::new(2, MIX_SAMPLE_RATE));
+        let limiter = dsp::LimiterControl::default();
+        let meter = dsp::LevelMeter::default();
+        let scope = dsp::Scope::default();
+        let output = dsp::Metered::new(
+            dsp::Limiter::new(output, limiter.clone()),
+            meter.clone(),
+            scope.clone(),
+        );
+
+        let mut engine = Self {
+            mixer: Some(mixer),
+            limiter,
+            meter,
+            scope,
+            ..Self::silent_fallback()
+        };
+        match latency {
+            LatencyMode::Stable => {
+                let (stream, handle) =
+                    OutputStream::try_default().context("no default audio output device found")?;
+                let master = Sink::try_new(&handle)?;
+                master.append(output);
+                engine._stream = Some(stream);
+                engine._master = Some(master);
+            }
+            LatencyMode::Low => engine._direct_stream = Some(open_low_latency_stream(output)?),
+        }
+        Ok(engine)
+    }
+
     fn silent_fallback() -> Self {
         Self {
             _stream: None,
-            handle: None,
-            current_sink: Mutex::new(None),
+            _master: None,
+            _direct_stream: None,
+            mixer: None,
+            limiter: dsp::LimiterControl::default(),
+            meter: dsp::LevelMeter::default(),
+            scope: dsp::Scope::default(),
+            voices: Mutex::new(Vec::with_capacity(VOICE_SLOTS)),
+            reference: Mutex::new(None),
+            max_voices: AtomicUsize::new(DEFAULT_MAX_VOICES),
+            stolen: AtomicUsize::new(0),
+        }
+    }
+
+    fn play_note(
+        &self,
+        clip: &SampleClip,
+        midi_note: i32,
+        params: NoteParams,
+    ) -> Result<dsp::ReleaseHandle> {
+        let release = dsp::ReleaseHandle::new();
+        let Some(mixer) = &self.mixer else {
+            return Ok(release);
+        };
+
+        let state = dsp::VoiceState::default();
+        let source = render::releasable_voice(clip, midi_note, &params, release.clone());
+
+        // The voice reaches the mixer only once it is tracked and room has been made for
+        // it, so the limit holds even for a moment.
+        let mut voices = self.lock_voices()?;
+        voices.retain(|voice| !voice.state.is_finished());
+        for voice in voices
+            .iter_mut()
+            .filter(|v| v.midi == midi_note && !v.released)
+        {
+            voice.release(params.smoothing_ms, params.release_curve);
+        }
+        // Every step below walks a list capped at VOICE_SLOTS and never reallocates it, so
+        // a burst of notes costs the same however long it lasts. Removal swaps, so age
+        // comes from `started` rather than list order.
+        let max_voices = self.max_voices.load(Ordering::Relaxed);
+        let mut held = voices.iter().filter(|v| !v.released).count();
+        while held >= max_voices {
+            let Some(oldest) = voices
+                .iter_mut()
+                .filter(|v| !v.released)
+                .min_by_key(|v| v.started)
+            else {
+                break;
+            };
+            oldest.release(params.smoothing_ms, params.release_curve);
+            self.stolen.fetch_add(1, Ordering::Relaxed);
+            held -= 1;
+        }
+        // Stolen voices still fade out; past the tail budget the oldest tails are faded
+        // quickly over the smoothing time and no longer tracked.
+        while voices.len() >= max_voices * VOICE_TAIL_FACTOR {
+            let Some(index) = voices
+                .iter()
+                .enumerate()
+                .filter(|(_, v)| v.released)
+                .min_by_key(|(_, v)| v.started)
+                .map(|(index, _)| index)
+            else {
+                break;
+            };
+            voices
+                .swap_remove(index)
+                .state
+                .stop(params.smoothing_ms, params.release_curve);
         }
+        voices.push(Voice {
+            midi: midi_note,
+            state: state.clone(),
+            release: release.clone(),
+            started: Instant::now(),
+            released: false,
+            gated: params.gate || params.freeze.is_some(),
+        });
+        mixer.add(dsp::Tracked::new(source, state));
+        Ok(release)
     }
 
-    fn play_note(&self, clip: &SampleClip, midi_note: i32) -> Result<()> {
-        let Some(handle) = &self.handle else {
-            return Ok(());
+    fn play_reference(&self, frequency: f32) -> Result<dsp::ReleaseHandle> {
+        let release = dsp::ReleaseHandle::new();
+        let Some(mixer) = &self.mixer else {
+            return Ok(release);
         };
 
-        let ratio = 2.0f32.powf((midi_note - BASE_MIDI_NOTE) as f32 / 12.0);
-        let source = SamplesBuffer::new(1, clip.sample_rate, (*clip.mono_samples).clone())
-            .speed(ratio)
-            .amplify(0.75);
+        let state = dsp::VoiceState::default();
+        let tone = SineWave::new(frequency).amplify(REFERENCE_LEVEL);
+        let source = dsp::Releasable::new(tone, release.clone());
+        mixer.add(dsp::Tracked::new(source, state.clone()));
+        *self
+            .reference
+            .lock()
+            .map_err(|_| anyhow!("audio reference lock poisoned"))? = Some(state);
+        Ok(release)
+    }
 
-        let sink = Sink::try_new(handle)?;
-        sink.append(source);
+    fn release_note(&self, midi_note: i32, fade_ms: u32, curve: dsp::ReleaseCurve) -> Result<()> {
+        for voice in self
+            .lock_voices()?
+            .iter_mut()
+            .filter(|v| v.midi == midi_note && v.gated && !v.released)
+        {
+            voice.release(fade_ms, curve);
+        }
+        Ok(())
+    }
 
-        let mut active_sink = self
-            .current_sink
+    fn stop_all(&self, fade_ms: u32, curve: dsp::ReleaseCurve) -> Result<()> {
+        for voice in self.lock_voices()?.drain(..) {
+            voice.state.stop(fade_ms, curve);
+        }
+        if let Some(reference) = self
+            .reference
             .lock()
-            .map_err(|_| anyhow!("audio sink lock poisoned"))?;
-        if let Some(previous) = active_sink.take() {
-            previous.stop();
+            .map_err(|_| anyhow!("audio reference lock poisoned"))?
+            .take()
+        {
+            reference.stop(fade_ms, curve);
         }
-        *active_sink = Some(sink);
         Ok(())
     }
+
+    fn set_max_voices(&self, max_voices: usize) {
+        self.max_voices
+            .store(max_voices.clamp(1, MAX_VOICE_LIMIT), Ordering::Relaxed);
+    }
+
+    // Drops finished voices so the count follows what is actually still sounding. Held
+    // voices are copied into the caller's buffer, which is reused from frame to frame;
+    // returns how many are releasing.
+    fn reap_voices(&self, held: &mut Vec<(i32, Duration)>) -> Result<usize> {
+        let mut voices = self.lock_voices()?;
+        voices.retain(|voice| !voice.state.is_finished());
+        held.clear();
+        held.extend(
+            voices
+                .iter()
+                .filter(|v| !v.released)
+                .map(|v| (v.midi, v.started.elapsed())),
+        );
+        Ok(voices.len() - held.len())
+    }
+
+    fn take_stolen(&self) -> usize {
+        self.stolen.swap(0, Ordering::Relaxed)
+    }
+
+    fn lock_voices(&self) -> Result<std::sync::MutexGuard<'_, Vec<Voice>>> {
+        self.voices
+            .lock()
+            .map_err(|_| anyhow!("audio voice lock poisoned"))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Subdivision {
+    Quarter,
+    Eighth,
+    Sixteenth,
+}
+
+impl Subdivision {
+    const ALL: [Self; 3] = [Self::Quarter, Self::Eighth, Self::Sixteenth];
+
+    fn label(self) -> &'static str {
+        match self {
+            Self::Quarter => "1/4",
+            Self::Eighth => "1/8",
+            Self::Sixteenth => "1/16",
+        }
+    }
+
+    fn seconds(self, bpm: f32) -> f64 {
+        let beat = 60.0 / f64::from(bpm.max(1.0));
+        match self {
+            Self::Quarter => beat,
+            Self::Eighth => beat / 2.0,
+            Self::Sixteenth => beat / 4.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ChordType {
+    Major,
+    Minor,
+    Dominant7,
+    Major7,
+    Minor7,
+}
+
+impl ChordType {
+    const ALL: [Self; 5] = [
+        Self::Major,
+        Self::Minor,
+        Self::Dominant7,
+        Self::Major7,
+        Self::Minor7,
+    ];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Major => Text::ChordMajor,
+            Self::Minor => Text::ChordMinor,
+            Self::Dominant7 => Text::ChordDominant7,
+            Self::Major7 => Text::ChordMajor7,
+            Self::Minor7 => Text::ChordMinor7,
+        }
+    }
+
+    fn intervals(self) -> &'static [i32] {
+        match self {
+            Self::Major => &[0, 4, 7],
+            Self::Minor => &[0, 3, 7],
+            Self::Dominant7 => &[0, 4, 7, 10],
+            Self::Major7 => &[0, 4, 7, 11],
+            Self::Minor7 => &[0, 3, 7, 10],
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum RoundRobinMode {
+    Sequential,
+    Random,
+}
+
+impl RoundRobinMode {
+    fn label(self) -> Text {
+        match self {
+            Self::Sequential => Text::RoundRobinSequential,
+            Self::Random => Text::RoundRobinRandom,
+        }
+    }
+}
+
+// A waveform PNG export: the caption is drawn for one frame before the screenshot is
+// requested so it appears in the captured image.
+enum WaveformCapture {
+    Armed(PathBuf),
+    Requested(PathBuf, f64),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CueMode {
+    Off,
+    Pick,
+    Kit,
+}
+
+impl CueMode {
+    const ALL: [Self; 3] = [Self::Off, Self::Pick, Self::Kit];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Off => Text::CueOff,
+            Self::Pick => Text::CueSingle,
+            Self::Kit => Text::CueKit,
+        }
+    }
+}
+
+// A folder entry held down for a quick listen; it never replaces the loaded clip.
+struct Audition {
+    index: usize,
+    pending: Option<PendingLoad>,
+    voice: Option<dsp::ReleaseHandle>,
+}
+
+// `time` is seconds since launch, as reported by egui.
+struct StatusEntry {
+    time: f64,
+    message: String,
+    error: bool,
+}
+
+struct RoundRobinClip {
+    path: PathBuf,
+    sample: Option<SampleClip>,
+    pending: Option<PendingLoad>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum ScaleHighlight {
+    Off,
+    Major,
+    Minor,
+    MajorPentatonic,
+    MinorPentatonic,
+}
+
+impl ScaleHighlight {
+    const ALL: [Self; 5] = [
+        Self::Off,
+        Self::Major,
+        Self::Minor,
+        Self::MajorPentatonic,
+        Self::MinorPentatonic,
+    ];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Off => Text::ScaleOff,
+            Self::Major => Text::ScaleMajor,
+            Self::Minor => Text::ScaleMinor,
+            Self::MajorPentatonic => Text::ScaleMajorPentatonic,
+            Self::MinorPentatonic => Text::ScaleMinorPentatonic,
+        }
+    }
+
+    fn intervals(self) -> &'static [i32] {
+        match self {
+            Self::Off => &[],
+            Self::Major => &[0, 2, 4, 5, 7, 9, 11],
+            Self::Minor => &[0, 2, 3, 5, 7, 8, 10],
+            Self::MajorPentatonic => &[0, 2, 4, 7, 9],
+            Self::MinorPentatonic => &[0, 3, 5, 7, 10],
+        }
+    }
+
+    fn contains(self, root: i32, midi: i32) -> bool {
+        self.intervals().contains(&(midi - root).rem_euclid(12))
+    }
+
+    // Whether the key signature is written with flats, judged from its relative major.
+    fn uses_flats(self, root: i32) -> bool {
+        let major = match self {
+            Self::Minor | Self::MinorPentatonic => root + 3,
+            _ => root,
+        };
+        matches!(major.rem_euclid(12), 1 | 3 | 5 | 8 | 10)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Accidentals {
+    Sharps,
+    Flats,
+    FollowKey,
+}
+
+impl Accidentals {
+    const ALL: [Self; 3] = [Self::Sharps, Self::Flats, Self::FollowKey];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Sharps => Text::AccidentalsSharps,
+            Self::Flats => Text::AccidentalsFlats,
+            Self::FollowKey => Text::AccidentalsFollowKey,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NoteLabelMode {
+    Name,
+    Number,
+    Both,
+}
+
+impl NoteLabelMode {
+    const ALL: [Self; 3] = [Self::Name, Self::Number, Self::Both];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Name => Text::NoteLabelsNames,
+            Self::Number => Text::NoteLabelsNumbers,
+            Self::Both => Text::NoteLabelsBoth,
+        }
+    }
+
+    fn key_label(
+        self,
+        midi: i32,
+        is_black: bool,
+        naming: NoteNaming,
+        key: i32,
+        flats: bool,
+    ) -> String {
+        let name = || note_name(midi, naming, key, flats);
+        match self {
+            Self::Name => name(),
+            Self::Number => midi.to_string(),
+            Self::Both if is_black => format!("{}\n{midi}", name()),
+            Self::Both => format!("{} ({midi})", name()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum NoteNaming {
+    Letters,
+    FixedDo,
+    MovableDo,
+}
+
+impl NoteNaming {
+    const ALL: [Self; 3] = [Self::Letters, Self::FixedDo, Self::MovableDo];
+
+    fn label(self) -> Text {
+        match self {
+            Self::Letters => Text::NamingLetters,
+            Self::FixedDo => Text::NamingFixedDo,
+            Self::MovableDo => Text::NamingMovableDo,
+        }
+    }
 }
 
 #[derive(Clone, Copy)]
@@ -199,126 +936,4916 @@ struct PianoKey {
     width: f32,
 }
 
+// What a bounce replaced, so it can be put back. `bounced` identifies the rendered clip.
+struct BounceOriginal {
+    source: Option<ClipSource>,
+    detune_cents: f32,
+    transpose: i32,
+    effects: EffectSnapshot,
+    bounced: Arc<Vec<u8>>,
+}
+
 struct SamplePianoApp {
     audio: AudioEngine,
     sample: Option<SampleClip>,
     selected_path: Option<PathBuf>,
     status: String,
+    status_error: bool,
+    status_log: VecDeque<StatusEntry>,
+    full_decode_s: u32,
+    full_decode_used_s: u32,
+    waveform_rect: Option<Rect>,
+    waveform_capture: Option<WaveformCapture>,
+    bypass_effects: bool,
+    bypass_gain: f32,
+    bypass_key: Option<BypassKey>,
+    bypass_pending: Option<(BypassKey, f64)>,
+    bypass_match: Option<mpsc::Receiver<f32>>,
+    tap_times: VecDeque<f64>,
+    tap_key: egui::Key,
+    effect_snapshots: Vec<EffectSnapshot>,
+    active_snapshot: Option<usize>,
+    snapshot_name: String,
+    snapshot_cuts_notes: bool,
+    voice_steal_flash: f64,
     bite_ms: u32,
+    bite_committed: u32,
+    bite_undo: Vec<u32>,
+    bite_redo: Vec<u32>,
+    preserve_stereo: bool,
+    stereo_width: f32,
+    pan: f32,
+    random_pan: bool,
+    tone: ToneSettings,
+    presets: Vec<PathBuf>,
+    active_preset: Option<usize>,
+    note_labels: NoteLabelMode,
+    note_naming: NoteNaming,
+    accidentals: Accidentals,
+    scale: ScaleHighlight,
+    scale_root: i32,
+    time: f64,
+    bpm: f32,
+    quantize: bool,
+    subdivision: Subdivision,
+    // Quantized triggers waiting for the grid; the flag marks keys already let go.
+    pending_notes: Vec<(i32, f64, Option<f32>, bool)>,
+    base_midi: i32,
+    detune_cents: f32,
+    transpose: i32,
+    memory_source: Option<MemoryClip>,
+    raw_format: Option<raw::RawPcmFormat>,
+    raw_import: Option<(PathBuf, raw::RawPcmFormat)>,
+    track_id: Option<u32>,
+    rate_override: Option<u32>,
+    language: Language,
+    compact: bool,
+    scratch_wav: bool,
+    key_hit_scale: f32,
+    max_voices: usize,
+    held_voices: Vec<(i32, Duration)>,
+    bounce_original: Option<BounceOriginal>,
+    scratch_written: Option<Arc<Vec<f32>>>,
+    scratch_pending: Option<(Arc<Vec<f32>>, f64)>,
+    scrub_mode: bool,
+    scrub_position: f32,
+    full_clip: Option<SampleClip>,
+    scrub_slice: Option<SampleClip>,
+    full_load: Option<PendingLoad>,
+    scrub_player: Option<dsp::ReleaseHandle>,
+    cue_mode: CueMode,
+    // Detected onsets (in full-file frames) that replace the file's cue markers when set.
+    transient_frames: Option<Vec<usize>>,
+    transient_sensitivity: f32,
+    detect_transients_pending: bool,
+    bite_start: usize,
+    // Bite start of a preset whose file is still loading; the preset's base note also
+    // wins over the file's own root note.
+    preset_bite_start: Option<usize>,
+    scope_frozen: Option<Vec<f32>>,
+    nudge_frames: usize,
+    cue_selected: usize,
+    cue_slices: Vec<SampleClip>,
+    sequencer: Vec<(i32, [bool; SEQUENCER_STEPS])>,
+    sequencer_playing: bool,
+    sequencer_step: usize,
+    sequencer_next_at: f64,
+    sequencer_held: Vec<i32>,
+    url_input: String,
+    show_url_dialog: bool,
+    pending_load: Option<PendingLoad>,
+    freeze: bool,
+    grain_ms: f32,
+    freeze_position: f32,
+    mouse_note: Option<i32>,
+    snap_zero: bool,
+    interp: dsp::InterpQuality,
+    reverse_attack: bool,
+    reverse_attack_ms: u32,
+    meter_levels: [f32; 2],
+    meter_peaks: [(f32, f64); 2],
+    meter_stereo: bool,
+    meter_time: f64,
+    hold_velocity: bool,
+    humanize: bool,
+    humanize_amount: f32,
+    humanize_seed: Option<u64>,
+    humanize_rng: StdRng,
+    start_jitter_ms: u32,
+    start_jitter_seed: u64,
+    start_jitter_rng: StdRng,
+    next_velocity: f32,
+    key_down_at: Vec<(i32, f64)>,
+    held_keys: HashSet<egui::Key>,
+    chord_mode: bool,
+    chord_type: ChordType,
+    chord_root: i32,
+    chord_button: Option<ChordType>,
+    held_chords: Vec<(i32, Vec<i32>)>,
+    auto_level: bool,
+    bite_gain_comp: bool,
+    // Loudness of the first slice of the current source; later re-slices are matched to it.
+    reference_loudness_db: Option<f32>,
+    auto_preview: bool,
+    preview: Option<dsp::ReleaseHandle>,
+    audition: Option<Audition>,
+    browse_folder: Option<PathBuf>,
+    folder_files: Vec<PathBuf>,
+    folder_index: Option<usize>,
+    crush: bool,
+    crush_bits: u32,
+    crush_downsample: u32,
+    crush_mix: f32,
+    overdrive: bool,
+    overdrive_curve: dsp::DriveCurve,
+    overdrive_drive_db: f32,
+    overdrive_level_db: f32,
+    overdrive_mix: f32,
+    reverb: bool,
+    reverb_room: f32,
+    reverb_damping: f32,
+    reverb_mix: f32,
+    limiter_threshold_db: f32,
+    limiter_makeup_db: f32,
+    tremolo: bool,
+    tremolo_rate: f32,
+    tremolo_depth: f32,
+    tremolo_shape: dsp::LfoShape,
+    tremolo_sync: bool,
+    tremolo_subdivision: Subdivision,
+    midi_out_enabled: bool,
+    midi_out_ports: Vec<String>,
+    midi_out_port: Option<String>,
+    midi_out: Option<midi_out::MidiOut>,
+    midi_in_enabled: bool,
+    midi_in_ports: Vec<String>,
+    midi_in_port: Option<String>,
+    midi_in: Option<midi_in::MidiIn>,
+    osc_enabled: bool,
+    osc_port: u16,
+    osc_network: bool,
+    osc: Option<osc::OscServer>,
+    round_robin: Vec<RoundRobinClip>,
+    round_robin_mode: RoundRobinMode,
+    round_robin_index: usize,
+    waveform_zoom: f32,
+    waveform_scroll: f32,
+    note_trims: BTreeMap<i32, f32>,
+    zones: Vec<KeyZone>,
+    loop_control: dsp::LoopControl,
+    reference_hz: f32,
+    reference: Option<dsp::ReleaseHandle>,
+    layers: Vec<clip::ClipLayer>,
+    layer_mode: clip::LayerMode,
+    use_layers: bool,
+    latency: LatencyMode,
+    remove_dc: bool,
+    fill_loop: bool,
+    removed_dc: Option<f32>,
+    filter: bool,
+    filter_cutoff: f32,
+    filter_resonance: f32,
+    filter_control: dsp::FilterControl,
+    filter_automation: Arc<Vec<(f32, f32)>>,
+    filter_record_armed: bool,
+    filter_recording: Option<f64>,
+    filter_play_automation: bool,
+    auto_wah: bool,
+    auto_wah_sensitivity: f32,
+    auto_wah_low_hz: f32,
+    auto_wah_high_hz: f32,
+    auto_wah_attack_ms: f32,
+    auto_wah_release_ms: f32,
+    auto_wah_resonance: f32,
+    glissando: bool,
+    drag_bend: bool,
+    bend_range: f32,
+    bend: dsp::BendControl,
+    bend_semitones: f32,
+    release_ms: u32,
+    smoothing_ms: u32,
+    attack_ms: u32,
+    release_curve: dsp::ReleaseCurve,
+    saved_state: Option<String>,
+    close_dialog: bool,
+    allow_close: bool,
+    performance_mode: bool,
+    voice_cache: Option<voice_cache::VoiceCache>,
+    pending_cache: Option<voice_cache::PendingCache>,
 }
 
 impl SamplePianoApp {
     fn new(audio: AudioEngine) -> Self {
-        Self {
+        let mut app = Self {
             audio,
-            sample: Some(SampleClip::generated_test_tone(DEFAULT_BITE_MS)),
+            sample: Some(SampleClip::generated_test_tone(
+                DEFAULT_BITE_MS,
+                &ToneSettings::default(),
+            )),
             selected_path: None,
-            status: "Loaded generated 500 ms test tone. Open a file to replace it.".to_string(),
+            status: String::new(),
+            status_error: false,
+            status_log: VecDeque::new(),
+            full_decode_s: DEFAULT_FULL_DECODE_S,
+            full_decode_used_s: DEFAULT_FULL_DECODE_S,
+            waveform_rect: None,
+            waveform_capture: None,
+            bypass_effects: false,
+            bypass_gain: 1.0,
+            bypass_key: None,
+            bypass_pending: None,
+            bypass_match: None,
+            tap_times: VecDeque::new(),
+            tap_key: TAP_KEYS[0],
+            effect_snapshots: Vec::new(),
+            active_snapshot: None,
+            snapshot_name: String::new(),
+            snapshot_cuts_notes: false,
+            voice_steal_flash: f64::NEG_INFINITY,
             bite_ms: DEFAULT_BITE_MS,
+            bite_committed: DEFAULT_BITE_MS,
+            bite_undo: Vec::new(),
+            bite_redo: Vec::new(),
+            preserve_stereo: false,
+            stereo_width: 100.0,
+            pan: 0.0,
+            random_pan: false,
+            tone: ToneSettings::default(),
+            presets: Vec::new(),
+            active_preset: None,
+            note_labels: NoteLabelMode::Name,
+            note_naming: NoteNaming::Letters,
+            accidentals: Accidentals::Sharps,
+            scale: ScaleHighlight::Off,
+            scale_root: 0,
+            time: 0.0,
+            bpm: 120.0,
+            quantize: false,
+            subdivision: Subdivision::Sixteenth,
+            pending_notes: Vec::new(),
+            base_midi: BASE_MIDI_NOTE,
+            detune_cents: 0.0,
+            transpose: 0,
+            memory_source: None,
+            raw_format: None,
+            raw_import: None,
+            track_id: None,
+            rate_override: None,
+            language: Language::English,
+            compact: false,
+            scratch_wav: false,
+            key_hit_scale: 1.0,
+            max_voices: DEFAULT_MAX_VOICES,
+            held_voices: Vec::with_capacity(VOICE_SLOTS),
+            bounce_original: None,
+            scratch_written: None,
+            scratch_pending: None,
+            scrub_mode: false,
+            scrub_position: 0.0,
+            full_clip: None,
+            scrub_slice: None,
+            full_load: None,
+            scrub_player: None,
+            cue_mode: CueMode::Off,
+            transient_frames: None,
+            transient_sensitivity: 0.5,
+            detect_transients_pending: false,
+            bite_start: 0,
+            preset_bite_start: None,
+            scope_frozen: None,
+            nudge_frames: 1,
+            cue_selected: 0,
+            cue_slices: Vec::new(),
+            sequencer: [0, 7, 12]
+                .map(|offset| (BASE_MIDI_NOTE + offset, [false; SEQUENCER_STEPS]))
+                .to_vec(),
+            sequencer_playing: false,
+            sequencer_step: 0,
+            sequencer_next_at: 0.0,
+            sequencer_held: Vec::new(),
+            url_input: String::new(),
+            show_url_dialog: false,
+            pending_load: None,
+            freeze: false,
+            grain_ms: 80.0,
+            freeze_position: 0.5,
+            mouse_note: None,
+            snap_zero: true,
+            interp: dsp::InterpQuality::Linear,
+            reverse_attack: false,
+            reverse_attack_ms: 200,
+            meter_levels: [METER_FLOOR_DB; 2],
+            meter_peaks: [(METER_FLOOR_DB, 0.0); 2],
+            meter_stereo: true,
+            meter_time: 0.0,
+            hold_velocity: false,
+            humanize: false,
+            humanize_amount: 0.5,
+            humanize_seed: None,
+            humanize_rng: StdRng::from_entropy(),
+            start_jitter_ms: 0,
+            start_jitter_seed: 1,
+            start_jitter_rng: StdRng::seed_from_u64(1),
+            next_velocity: 1.0,
+            key_down_at: Vec::new(),
+            held_keys: HashSet::new(),
+            chord_mode: false,
+            chord_type: ChordType::Major,
+            chord_root: BASE_MIDI_NOTE,
+            chord_button: None,
+            held_chords: Vec::new(),
+            auto_level: true,
+            bite_gain_comp: false,
+            reference_loudness_db: None,
+            auto_preview: false,
+            preview: None,
+            audition: None,
+            browse_folder: None,
+            folder_files: Vec::new(),
+            folder_index: None,
+            crush: false,
+            crush_bits: 8,
+            crush_downsample: 4,
+            crush_mix: 1.0,
+            overdrive: false,
+            overdrive_curve: dsp::DriveCurve::SoftClip,
+            overdrive_drive_db: 12.0,
+            overdrive_level_db: 0.0,
+            overdrive_mix: 1.0,
+            reverb: false,
+            reverb_room: 0.6,
+            reverb_damping: 0.5,
+            reverb_mix: 0.3,
+            limiter_threshold_db: dsp::DEFAULT_LIMITER_THRESHOLD_DB,
+            limiter_makeup_db: 0.0,
+            tremolo: false,
+            tremolo_rate: 5.0,
+            tremolo_depth: 0.5,
+            tremolo_shape: dsp::LfoShape::Sine,
+            tremolo_sync: false,
+            tremolo_subdivision: Subdivision::Eighth,
+            midi_out_enabled: false,
+            midi_out_ports: Vec::new(),
+            midi_out_port: None,
+            midi_out: None,
+            midi_in_enabled: false,
+            midi_in_ports: Vec::new(),
+            midi_in_port: None,
+            midi_in: None,
+            osc_enabled: false,
+            osc_port: osc::DEFAULT_OSC_PORT,
+            osc_network: false,
+            osc: None,
+            round_robin: Vec::new(),
+            round_robin_mode: RoundRobinMode::Sequential,
+            round_robin_index: 0,
+            waveform_zoom: 1.0,
+            waveform_scroll: 0.0,
+            note_trims: BTreeMap::new(),
+            zones: Vec::new(),
+            loop_control: dsp::LoopControl::default(),
+            reference_hz: 440.0,
+            reference: None,
+            layers: Vec::new(),
+            layer_mode: clip::LayerMode::Sum,
+            use_layers: false,
+            latency: LatencyMode::Stable,
+            remove_dc: false,
+            fill_loop: false,
+            removed_dc: None,
+            filter: false,
+            filter_cutoff: 2_000.0,
+            filter_resonance: 4.0,
+            filter_control: dsp::FilterControl::new(2_000.0),
+            filter_automation: Arc::new(Vec::new()),
+            filter_record_armed: false,
+            filter_recording: None,
+            filter_play_automation: false,
+            auto_wah: false,
+            auto_wah_sensitivity: 0.5,
+            auto_wah_low_hz: 300.0,
+            auto_wah_high_hz: 3_000.0,
+            auto_wah_attack_ms: 10.0,
+            auto_wah_release_ms: 150.0,
+            auto_wah_resonance: 4.0,
+            glissando: false,
+            drag_bend: false,
+            bend_range: 2.0,
+            bend: dsp::BendControl::default(),
+            bend_semitones: 0.0,
+            release_ms: NOTE_OFF_FADE_MS,
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
+            attack_ms: DEFAULT_ATTACK_MS,
+            release_curve: dsp::ReleaseCurve::Linear,
+            saved_state: None,
+            close_dialog: false,
+            allow_close: false,
+            performance_mode: false,
+            voice_cache: None,
+            pending_cache: None,
+        };
+        app.set_status(strings::fill(app.tr(Text::TestToneLoaded), app.bite_ms));
+        app.mark_saved();
+        app
+    }
+
+    // Everything a preset stores, serialised for cheap comparison.
+    fn saved_snapshot(&self) -> String {
+        serde_json::to_string(&self.current_preset()).unwrap_or_default()
+    }
+
+    fn mark_saved(&mut self) {
+        self.saved_state = Some(self.saved_snapshot());
+    }
+
+    fn is_dirty(&self) -> bool {
+        self.saved_state.as_ref() != Some(&self.saved_snapshot())
+    }
+
+    fn current_preset(&self) -> Preset {
+        Preset {
+            source_path: self.selected_path.clone(),
+            layers: self.layers.clone(),
+            layer_mode: self.layer_mode,
+            use_layers: self.use_layers,
+            bite_ms: self.bite_ms,
+            bite_start: self.bite_start,
+            preserve_stereo: self.preserve_stereo,
+            stereo_width: self.stereo_width,
+            pan: self.pan,
+            random_pan: self.random_pan,
+            tone: self.tone,
+            base_midi: self.base_midi,
+            detune_cents: self.detune_cents,
+            transpose: self.transpose,
+            freeze: self.freeze,
+            grain_ms: self.grain_ms,
+            freeze_position: self.freeze_position,
+            auto_level: self.auto_level,
+            bite_gain_comp: self.bite_gain_comp,
+            crush: self.crush,
+            crush_bits: self.crush_bits,
+            crush_downsample: self.crush_downsample,
+            crush_mix: self.crush_mix,
+            overdrive: self.overdrive,
+            overdrive_curve: self.overdrive_curve,
+            overdrive_drive_db: self.overdrive_drive_db,
+            overdrive_level_db: self.overdrive_level_db,
+            overdrive_mix: self.overdrive_mix,
+            reverb: self.reverb,
+            reverb_room: self.reverb_room,
+            reverb_damping: self.reverb_damping,
+            reverb_mix: self.reverb_mix,
+            limiter_threshold_db: self.limiter_threshold_db,
+            limiter_makeup_db: self.limiter_makeup_db,
+            tremolo: self.tremolo,
+            tremolo_rate: self.tremolo_rate,
+            tremolo_depth: self.tremolo_depth,
+            tremolo_shape: self.tremolo_shape,
+            tremolo_sync: self.tremolo_sync,
+            reverse_attack: self.reverse_attack,
+            reverse_attack_ms: self.reverse_attack_ms,
+            interp: self.interp,
+            remove_dc: self.remove_dc,
+            fill_loop: self.fill_loop,
+            release_ms: self.release_ms,
+            release_curve: self.release_curve,
+            smoothing_ms: self.smoothing_ms,
+            attack_ms: self.attack_ms,
+            filter: self.filter,
+            filter_cutoff: self.filter_cutoff,
+            filter_resonance: self.filter_resonance,
+            filter_automation: self.filter_automation.to_vec(),
+            filter_play_automation: self.filter_play_automation,
+            auto_wah: self.auto_wah,
+            auto_wah_sensitivity: self.auto_wah_sensitivity,
+            auto_wah_low_hz: self.auto_wah_low_hz,
+            auto_wah_high_hz: self.auto_wah_high_hz,
+            auto_wah_attack_ms: self.auto_wah_attack_ms,
+            auto_wah_release_ms: self.auto_wah_release_ms,
+            auto_wah_resonance: self.auto_wah_resonance,
+            note_trims: self.note_trims.clone(),
+            zones: self.zones.clone(),
+            effect_snapshots: self.effect_snapshots.clone(),
         }
     }
 
-    fn load_clip(&mut self, path: PathBuf) {
-        match SampleClip::from_file(&path, self.bite_ms) {
-            Ok(sample) => {
-                self.status = format!(
-                    "Loaded {} ({} Hz). First {} ms is now mapped across C3–C6.",
-                    path.file_name().and_then(|n| n.to_str()).unwrap_or("clip"),
-                    sample.sample_rate,
-                    self.bite_ms,
-                );
-                self.sample = Some(sample);
-                self.selected_path = Some(path);
+    fn remember_preset(&mut self, path: PathBuf) {
+        let index = match self.presets.iter().position(|p| *p == path) {
+            Some(index) => index,
+            None => {
+                self.presets.push(path);
+                self.presets.len() - 1
             }
+        };
+        self.active_preset = Some(index);
+    }
+
+    fn export_instrument(&mut self, parent: &Path) {
+        let Some(sample) = &self.sample else {
+            self.set_status(self.tr(Text::NothingToExport));
+            return;
+        };
+        let source_name = self
+            .current_source()
+            .map_or("test-tone".to_string(), |source| source.name());
+        let stem = Path::new(&source_name)
+            .file_stem()
+            .map_or(source_name.clone(), |stem| {
+                stem.to_string_lossy().into_owned()
+            });
+        let name: String = stem
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let params = NoteParams {
+            pan: self.pan,
+            ..self.note_params()
+        };
+        let dir = parent.join(format!("{name} (OpenWah)"));
+        match render::export_instrument(
+            sample,
+            &params,
+            PIANO_START_MIDI..=PIANO_END_MIDI,
+            &dir,
+            &name,
+        ) {
+            Ok(sfz) => self.set_status(strings::fill(
+                self.tr(Text::ExportedInstrument),
+                sfz.display(),
+            )),
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::ExportFailed),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    // Bounce in place: the base note as it sounds now becomes the new clip. Whatever was
+    // baked in is reset so it isn't applied twice.
+    fn resample_to_bite(&mut self) {
+        if self.pending_load.is_some() {
+            self.set_status(self.tr(Text::LoadBusy));
+            return;
+        }
+        let Some(sample) = &self.sample else {
+            return;
+        };
+        let params = NoteParams {
+            pan: 0.0,
+            width: 1.0,
+            ..self.note_params()
+        };
+        let bytes = match render::bounce_base_note(sample, &params) {
+            Ok(bytes) => Arc::new(bytes),
             Err(err) => {
-                self.status = format!("Could not load clip: {err:#}");
+                self.set_error(strings::fill(
+                    self.tr(Text::ResampleFailed),
+                    format!("{err:#}"),
+                ));
+                return;
             }
+        };
+        let source = self.current_source();
+        let name = format!(
+            "{} (resampled)",
+            source
+                .as_ref()
+                .map_or("test tone".to_string(), ClipSource::name)
+        );
+        let original = self
+            .bounce_original
+            .take()
+            .unwrap_or_else(|| BounceOriginal {
+                source,
+                detune_cents: self.detune_cents,
+                transpose: self.transpose,
+                effects: self.effect_snapshot(String::new()),
+                bounced: Arc::clone(&bytes),
+            });
+        self.bounce_original = Some(BounceOriginal {
+            bounced: Arc::clone(&bytes),
+            ..original
+        });
+
+        if !self.bypass_effects {
+            self.apply_effects(&EffectSnapshot {
+                crush: false,
+                overdrive: false,
+                reverb: false,
+                tremolo: false,
+                filter: false,
+                auto_wah: false,
+                ..self.effect_snapshot(String::new())
+            });
         }
+        self.detune_cents = 0.0;
+        self.transpose = 0;
+        self.set_status(self.tr(Text::ResampledBase));
+        self.start_load(
+            ClipSource::Memory(MemoryClip {
+                name,
+                bytes,
+                extension: Some("wav".to_string()),
+            }),
+            true,
+        );
     }
 
-    fn refresh_clip_for_duration(&mut self) {
-        if let Some(path) = self.selected_path.clone() {
-            self.load_clip(path);
-        } else {
-            self.sample = Some(SampleClip::generated_test_tone(self.bite_ms));
-            self.status = format!(
-                "Loaded generated {} ms test tone. Open a file to replace it.",
-                self.bite_ms
-            );
+    fn restore_original(&mut self) {
+        let Some(original) = self.bounce_original.take() else {
+            return;
+        };
+        self.apply_effects(&original.effects);
+        self.detune_cents = original.detune_cents;
+        self.transpose = original.transpose;
+        match original.source {
+            Some(source) => self.start_load(source, true),
+            None => self.use_test_tone(),
         }
     }
 
-    fn try_play(&mut self, midi_note: i32) {
-        if let Some(sample) = &self.sample {
-            if let Err(err) = self.audio.play_note(sample, midi_note) {
-                self.status = format!("Playback error: {err:#}");
+    fn save_preset(&mut self, path: PathBuf) {
+        match self.current_preset().save(&path) {
+            Ok(()) => {
+                self.set_status(strings::fill(
+                    self.tr(Text::SavedPreset),
+                    preset_name(&path),
+                ));
+                self.remember_preset(path);
+                self.mark_saved();
             }
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::PresetSaveFailed),
+                format!("{err:#}"),
+            )),
         }
     }
 
-    fn piano_keys() -> Vec<PianoKey> {
-        let white_width = 44.0;
-        let black_width = 28.0;
-        let mut keys = Vec::new();
-        let mut white_index = 0;
+    fn load_preset(&mut self, path: PathBuf) {
+        let preset = match Preset::load(&path) {
+            Ok(preset) => preset,
+            Err(err) => {
+                self.set_error(strings::fill(
+                    self.tr(Text::PresetLoadFailed),
+                    format!("{err:#}"),
+                ));
+                return;
+            }
+        };
 
-        for midi in PIANO_START_MIDI..=PIANO_END_MIDI {
-            if is_black_key(midi) {
-                let x = (white_index as f32 * white_width) - black_width * 0.5;
-                keys.push(PianoKey {
-                    midi,
-                    is_black: true,
-                    x,
-                    width: black_width,
-                });
-            } else {
-                let x = white_index as f32 * white_width;
-                keys.push(PianoKey {
-                    midi,
-                    is_black: false,
-                    x,
-                    width: white_width,
-                });
-                white_index += 1;
+        let use_layers = preset.use_layers && !preset.layers.is_empty();
+        let missing = if use_layers {
+            preset
+                .layers
+                .iter()
+                .map(|layer| &layer.path)
+                .find(|p| !p.is_file())
+        } else {
+            preset.source_path.as_ref().filter(|p| !p.is_file())
+        };
+        if let Some(source) = missing {
+            self.set_status(strings::fill_all(
+                self.tr(Text::PresetMissingFile),
+                &[&preset_name(&path), &source.display()],
+            ));
+            return;
+        }
+
+        self.bite_ms = preset.bite_ms.clamp(MIN_BITE_MS, MAX_BITE_MS);
+        self.commit_bite();
+        self.preserve_stereo = preset.preserve_stereo;
+        self.stereo_width = preset
+            .stereo_width
+            .clamp(0.0, dsp::MAX_STEREO_WIDTH * 100.0);
+        self.pan = preset.pan.clamp(-1.0, 1.0);
+        self.random_pan = preset.random_pan;
+        self.tone = preset.tone;
+        let effects = EffectSnapshot::from_preset(String::new(), &preset);
+        let new_source = if use_layers {
+            !self.use_layers || self.layers != preset.layers || self.layer_mode != preset.layer_mode
+        } else {
+            self.use_layers
+                || self.memory_source.is_some()
+                || self.raw_format.is_some()
+                || self.selected_path != preset.source_path
+        };
+        self.selected_path = preset.source_path;
+        self.memory_source = None;
+        self.raw_format = None;
+        self.layers = preset.layers;
+        self.layer_mode = preset.layer_mode;
+        self.use_layers = use_layers;
+        self.track_id = None;
+        self.rate_override = None;
+        self.reverse_attack = preset.reverse_attack;
+        self.reverse_attack_ms = preset.reverse_attack_ms.clamp(10, 1_000);
+        self.interp = preset.interp;
+        self.remove_dc = preset.remove_dc;
+        self.fill_loop = preset.fill_loop;
+        self.release_ms = preset.release_ms.clamp(5, 2_000);
+        self.smoothing_ms = preset.smoothing_ms.clamp(1, MAX_SMOOTHING_MS);
+        self.attack_ms = preset.attack_ms.min(MAX_ATTACK_MS);
+        self.release_curve = preset.release_curve;
+        // A different file starts fresh instead of being cut at the old file's bite start.
+        if new_source {
+            self.forget_full_clip();
+        }
+        self.bite_start = preset.bite_start;
+        match self.current_source().filter(|_| new_source) {
+            Some(source) => {
+                self.reload_round_robin();
+                self.start_load(source, true);
+                self.preset_bite_start = Some(preset.bite_start);
             }
+            None => self.refresh_clip_for_duration(),
         }
+        self.base_midi = preset.base_midi.clamp(0, 127);
+        self.detune_cents = preset.detune_cents.clamp(-100.0, 100.0);
+        self.transpose = preset.transpose.clamp(-MAX_TRANSPOSE, MAX_TRANSPOSE);
+        self.freeze = preset.freeze;
+        self.grain_ms = preset.grain_ms.clamp(10.0, 250.0);
+        self.freeze_position = preset.freeze_position.clamp(0.0, 1.0);
+        self.auto_level = preset.auto_level;
+        self.bite_gain_comp = preset.bite_gain_comp;
+        self.limiter_threshold_db = preset.limiter_threshold_db.clamp(-24.0, 0.0);
+        self.limiter_makeup_db = preset.limiter_makeup_db.clamp(0.0, 24.0);
+        self.apply_limiter();
+        self.apply_effects(&effects);
+        self.effect_snapshots = preset.effect_snapshots;
+        self.effect_snapshots.truncate(MAX_EFFECT_SNAPSHOTS);
+        self.active_snapshot = None;
+        self.filter_automation = Arc::new(
+            preset
+                .filter_automation
+                .into_iter()
+                .filter(|&(time, _)| (0.0..=MAX_AUTOMATION_MS).contains(&time))
+                .map(|(time, value)| {
+                    (
+                        time,
+                        value.clamp(dsp::MIN_FILTER_CUTOFF_HZ, dsp::MAX_FILTER_CUTOFF_HZ),
+                    )
+                })
+                .collect(),
+        );
+        self.filter_play_automation = preset.filter_play_automation;
+        self.filter_record_armed = false;
+        self.filter_recording = None;
+        self.note_trims = preset
+            .note_trims
+            .into_iter()
+            .filter(|(midi, db)| (0..=127).contains(midi) && *db != 0.0)
+            .map(|(midi, db)| (midi, db.clamp(-MAX_NOTE_TRIM_DB, MAX_NOTE_TRIM_DB)))
+            .collect();
+        self.zones = preset
+            .zones
+            .into_iter()
+            .map(|zone| KeyZone {
+                low: zone.low.clamp(0, 127),
+                high: zone.high.clamp(zone.low.clamp(0, 127), 127),
+                ..zone
+            })
+            .collect();
+        self.set_status(strings::fill_all(
+            self.tr(Text::LoadedPreset),
+            &[&preset_name(&path), &self.status],
+        ));
+        self.remember_preset(path);
+        self.mark_saved();
+    }
 
-        keys
+    // Saves over the active preset when there is one, otherwise asks where to save.
+    fn save_before_close(&mut self) -> bool {
+        let path = self
+            .active_preset
+            .and_then(|index| self.presets.get(index))
+            .cloned()
+            .or_else(|| {
+                rfd::FileDialog::new()
+                    .add_filter(self.tr(Text::PresetFileFilter), &[PRESET_EXTENSION])
+                    .set_file_name(format!("instrument.{PRESET_EXTENSION}"))
+                    .save_file()
+            });
+        let Some(path) = path else {
+            return false;
+        };
+        self.save_preset(path);
+        !self.is_dirty()
     }
 
-    fn draw_piano(&mut self, ui: &mut egui::Ui) {
-        let keys = Self::piano_keys();
-        let white_height = 180.0;
-        let black_height = 112.0;
-        let total_width = keys
-            .iter()
-            .filter(|k| !k.is_black)
-            .map(|k| k.width)
-            .sum::<f32>();
+    fn handle_close_request(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested()) && !self.allow_close && self.is_dirty() {
+            ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+            self.close_dialog = true;
+        }
+        if !self.close_dialog {
+            return;
+        }
+        egui::Window::new(self.tr(Text::UnsavedChanges))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, Vec2::ZERO)
+            .show(ctx, |ui| {
+                ui.label(self.tr(Text::UnsavedChangesBody));
+                ui.horizontal(|ui| {
+                    let close = if ui.button(self.tr(Text::Save)).clicked() {
+                        self.save_before_close()
+                    } else {
+                        ui.button(self.tr(Text::Discard)).clicked()
+                    };
+                    if close {
+                        self.allow_close = true;
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                    if ui.button(self.tr(Text::Cancel)).clicked() {
+                        self.close_dialog = false;
+                    }
+                });
+            });
+    }
 
-        let (rect, _) =
-            ui.allocate_exact_size(Vec2::new(total_width, white_height), Sense::hover());
-        let painter = ui.painter_at(rect);
+    fn load_clip(&mut self, path: PathBuf) {
+        let new_source = self.selected_path.as_ref() != Some(&path);
+        self.start_load(ClipSource::File(path), new_source);
+    }
+
+    fn open_folder(&mut self, folder: PathBuf) {
+        match clip::list_folder(&folder) {
+            Ok(files) => {
+                self.set_status(strings::fill_all(
+                    self.tr(Text::FoundAudioFiles),
+                    &[&files.len(), &folder.display()],
+                ));
+                self.folder_files = files;
+                self.folder_index = None;
+                self.browse_folder = Some(folder);
+            }
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::OpenFolderFailed),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn load_folder_file(&mut self, index: usize) {
+        if let Some(path) = self.folder_files.get(index).cloned() {
+            self.folder_index = Some(index);
+            self.load_clip(path);
+        }
+    }
+
+    fn step_folder(&mut self, forward: bool) {
+        let count = self.folder_files.len();
+        if count == 0 {
+            return;
+        }
+        let index = match self.folder_index {
+            Some(index) if forward => (index + 1) % count,
+            Some(index) => (index + count - 1) % count,
+            None if forward => 0,
+            None => count - 1,
+        };
+        self.load_folder_file(index);
+    }
+
+    fn start_load(&mut self, source: ClipSource, new_source: bool) {
+        self.preset_bite_start = None;
+        if new_source {
+            self.track_id = None;
+            self.rate_override = None;
+        }
+        let load = PendingLoad::spawn(
+            source,
+            self.bite_ms,
+            self.preserve_stereo,
+            self.track_id,
+            self.rate_override,
+            new_source,
+        );
+        self.set_status(strings::fill(self.tr(Text::Loading), &load.name));
+        self.pending_load = Some(load);
+    }
+
+    fn poll_pending_load(&mut self, ctx: &egui::Context) {
+        let Some(load) = &self.pending_load else {
+            return;
+        };
+        let Some(result) = load.poll() else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+            return;
+        };
+
+        let new_source = load.new_source;
+        self.pending_load = None;
+        let preset_bite_start = self.preset_bite_start.take();
+        match result {
+            Ok((source, sample)) => {
+                // Loading anything but the bounce itself leaves nothing to restore.
+                let bounced = match &source {
+                    ClipSource::Memory(memory) => self
+                        .bounce_original
+                        .as_ref()
+                        .is_some_and(|original| Arc::ptr_eq(&original.bounced, &memory.bytes)),
+                    _ => false,
+                };
+                if new_source && !bounced {
+                    self.bounce_original = None;
+                }
+                self.install_clip(
+                    &source.name(),
+                    sample,
+                    new_source,
+                    preset_bite_start.is_some(),
+                );
+                self.use_layers = matches!(source, ClipSource::Layers(..));
+                if new_source {
+                    self.forget_full_clip();
+                    self.bite_start = preset_bite_start.unwrap_or_default();
+                }
+                let reload_full = new_source
+                    && (self.scrub_mode
+                        || self.cue_mode != CueMode::Off
+                        || self.start_jitter_ms > 0
+                        || self.bite_start > 0);
+                match source {
+                    ClipSource::File(path) => {
+                        self.selected_path = Some(path);
+                        self.raw_format = None;
+                        self.memory_source = None;
+                    }
+                    ClipSource::RawPcm(path, format) => {
+                        self.selected_path = Some(path);
+                        self.raw_format = Some(format);
+                        self.memory_source = None;
+                    }
+                    ClipSource::Memory(memory) => {
+                        self.selected_path = None;
+                        self.raw_format = None;
+                        self.memory_source = Some(memory);
+                    }
+                    ClipSource::Layers(..) => {
+                        self.selected_path = None;
+                        self.raw_format = None;
+                        self.memory_source = None;
+                    }
+                    ClipSource::Url(_) => {}
+                }
+                if reload_full {
+                    self.reload_full_clip();
+                } else {
+                    self.update_cue_slices();
+                    if self.bite_start > 0 {
+                        self.recut_bite_start();
+                    }
+                }
+            }
+            Err(err) => {
+                self.set_error(strings::fill(
+                    self.tr(Text::ClipLoadFailed),
+                    format!("{err:#}"),
+                ));
+            }
+        }
+    }
+
+    fn install_clip(
+        &mut self,
+        name: &str,
+        mut sample: SampleClip,
+        new_source: bool,
+        from_preset: bool,
+    ) {
+        self.removed_dc = self.shape_clip(&mut sample);
+        if new_source {
+            self.reference_loudness_db = sample.loudness_db;
+        } else {
+            // Keep loop edits when the same source is re-sliced.
+            let frames = sample.frames();
+            if let Some(previous) = self.sample.as_ref().and_then(|s| s.loop_frames) {
+                sample.loop_frames = Some(previous).filter(|&(_, end)| end <= frames);
+            }
+        }
+        let channels = self.tr(if sample.channels == 2 {
+            Text::Stereo
+        } else {
+            Text::Mono
+        });
+        let mut status = strings::fill_all(
+            self.tr(Text::ClipLoaded),
+            &[&name, &sample.sample_rate, &channels, &self.bite_ms],
+        );
+        if let Some(root) = sample.root_midi {
+            if new_source && !from_preset {
+                self.base_midi = root;
+                status.push_str(&strings::fill(
+                    self.tr(Text::BaseNoteFromFile),
+                    midi_note_name(root, self.flats()),
+                ));
+            }
+        }
+        if let Some((start, end)) = sample.loop_frames {
+            status.push_str(&strings::fill_all(
+                self.tr(Text::LoopFramesStatus),
+                &[&start, &end],
+            ));
+        }
+        if let Some(db) = sample.loudness_db {
+            status.push_str(&strings::fill(
+                self.tr(Text::LevelStatus),
+                format!("{db:.1}"),
+            ));
+        }
+        if sample.decoded_frames < sample.frames() {
+            let to_ms = |frames: usize| frames as u64 * 1_000 / u64::from(sample.sample_rate);
+            status.push_str(&strings::fill_all(
+                self.tr(Text::DecodedPartly),
+                &[&to_ms(sample.decoded_frames), &to_ms(sample.frames())],
+            ));
+        }
+        if sample.skipped_packets > 0 {
+            status.push_str(&strings::fill(
+                self.tr(Text::SkippedPackets),
+                sample.skipped_packets,
+            ));
+        }
+        self.set_status(status);
+        self.sample = Some(sample);
+        self.play_preview();
+    }
+
+    fn start_url_load(&mut self) {
+        let url = self.url_input.trim().to_string();
+        if url.is_empty() {
+            return;
+        }
+        self.start_load(ClipSource::Url(url), true);
+        self.show_url_dialog = false;
+    }
+
+    fn paste_clip(&mut self, text: &str) {
+        match paste::clip_from_clipboard(text) {
+            Ok(_) if self.pending_load.is_some() => self.set_status(self.tr(Text::LoadBusy)),
+            Ok(ClipSource::File(path)) => self.load_clip(path),
+            Ok(source) => self.start_load(source, true),
+            Err(paste::PasteError::NoFile) => self.set_status(self.tr(Text::ClipboardNoFile)),
+            Err(paste::PasteError::NotAudio(path)) => self.set_error(strings::fill(
+                self.tr(Text::ClipboardNotAudio),
+                path.display(),
+            )),
+            Err(paste::PasteError::BadData(reason)) => {
+                self.set_error(strings::fill(self.tr(Text::ClipboardBadData), reason))
+            }
+        }
+    }
+
+    fn using_test_tone(&self) -> bool {
+        self.selected_path.is_none() && self.memory_source.is_none() && !self.use_layers
+    }
+
+    fn use_test_tone(&mut self) {
+        self.pending_load = None;
+        self.bounce_original = None;
+        self.selected_path = None;
+        self.memory_source = None;
+        self.raw_format = None;
+        self.use_layers = false;
+        self.track_id = None;
+        self.rate_override = None;
+        self.folder_index = None;
+        self.forget_full_clip();
+        self.refresh_clip_for_duration();
+        if self.scrub_mode {
+            self.update_scrub_slice();
+        }
+    }
+
+    // The bite start, cue slices and transients all index into the previous source's full
+    // decode, so none of them may be re-cut onto a different file.
+    fn forget_full_clip(&mut self) {
+        self.bite_start = 0;
+        self.full_clip = None;
+        self.full_load = None;
+        self.cue_slices.clear();
+        self.cue_selected = 0;
+        self.transient_frames = None;
+        self.detect_transients_pending = false;
+    }
+
+    fn shape_clip(&self, sample: &mut SampleClip) -> Option<f32> {
+        let removed_dc = self.remove_dc.then(|| sample.remove_dc_offset());
+        if self.fill_loop {
+            sample.fill_by_looping(FILL_LOOP_CROSSFADE_MS);
+        }
+        if self.snap_zero {
+            sample.snap_start_to_zero_crossing(clip::frames_for_ms(
+                sample.sample_rate,
+                ZERO_SNAP_RADIUS_MS,
+            ));
+        }
+        if self.reverse_attack {
+            sample.reverse_attack(self.reverse_attack_ms, REVERSE_ATTACK_CROSSFADE_MS);
+        }
+        removed_dc
+    }
+
+    fn add_round_robin_clip(&mut self, path: PathBuf) {
+        let pending = Some(self.spawn_bite_load(&path));
+        self.round_robin.push(RoundRobinClip {
+            path,
+            sample: None,
+            pending,
+        });
+    }
+
+    fn reload_round_robin(&mut self) {
+        for index in 0..self.round_robin.len() {
+            let load = self.spawn_bite_load(&self.round_robin[index].path);
+            self.round_robin[index].pending = Some(load);
+        }
+    }
+
+    fn spawn_bite_load(&self, path: &Path) -> PendingLoad {
+        PendingLoad::spawn(
+            ClipSource::File(path.to_path_buf()),
+            self.bite_ms,
+            self.preserve_stereo,
+            None,
+            None,
+            false,
+        )
+    }
+
+    fn update_audition(&mut self, held: Option<usize>) {
+        if self.audition.as_ref().map(|audition| audition.index) == held {
+            return;
+        }
+        // Dropping the audition cancels a load that has not finished yet.
+        if let Some(voice) = self.audition.take().and_then(|audition| audition.voice) {
+            self.smooth_stop(&voice);
+        }
+        let Some(path) = held.and_then(|index| self.folder_files.get(index)) else {
+            return;
+        };
+        let pending = Some(self.spawn_bite_load(path));
+        self.audition = held.map(|index| Audition {
+            index,
+            pending,
+            voice: None,
+        });
+    }
+
+    fn poll_audition(&mut self, ctx: &egui::Context) {
+        let Some(load) = self.audition.as_ref().and_then(|a| a.pending.as_ref()) else {
+            return;
+        };
+        let Some(result) = load.poll() else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+            return;
+        };
+        let mut sample = match result {
+            Ok((_, sample)) => sample,
+            Err(err) => {
+                self.set_error(strings::fill(
+                    self.tr(Text::PreviewFailed),
+                    format!("{err:#}"),
+                ));
+                self.audition = None;
+                return;
+            }
+        };
+        self.shape_clip(&mut sample);
+        if let Some(previous) = self.preview.take() {
+            self.smooth_stop(&previous);
+        }
+        let root = sample.root_midi.unwrap_or(self.base_midi);
+        let params = NoteParams {
+            base_midi: root,
+            gain: self.level_gain(sample.loudness_db),
+            freeze: None,
+            ..self.note_params()
+        };
+        let voice = match self.audio.play_note(&sample, root, params) {
+            Ok(voice) => Some(voice),
+            Err(err) => {
+                self.set_error(strings::fill(
+                    self.tr(Text::PlaybackError),
+                    format!("{err:#}"),
+                ));
+                None
+            }
+        };
+        if let Some(audition) = &mut self.audition {
+            audition.pending = None;
+            audition.voice = voice;
+        }
+    }
+
+    fn poll_round_robin(&mut self, ctx: &egui::Context) {
+        let mut failed = Vec::new();
+        let mut loaded = Vec::new();
+        for (index, clip) in self.round_robin.iter_mut().enumerate() {
+            let Some(load) = &clip.pending else {
+                continue;
+            };
+            let Some(result) = load.poll() else {
+                ctx.request_repaint_after(std::time::Duration::from_millis(50));
+                continue;
+            };
+            clip.pending = None;
+            match result {
+                Ok((_, sample)) => loaded.push((index, sample)),
+                Err(err) => failed.push((index, err)),
+            }
+        }
+        for (index, mut sample) in loaded {
+            self.shape_clip(&mut sample);
+            self.round_robin[index].sample = Some(sample);
+        }
+        for (index, err) in failed.into_iter().rev() {
+            let clip = self.round_robin.remove(index);
+            self.set_error(strings::fill_all(
+                self.tr(Text::RoundRobinRemoved),
+                &[&clip.path.display(), &format!("{err:#}")],
+            ));
+        }
+    }
+
+    // Index 0 is the main clip; 1.. are the extra round-robin clips.
+    fn next_round_robin_index(&mut self) -> usize {
+        let count = self.round_robin.len() + 1;
+        self.round_robin_index = match self.round_robin_mode {
+            RoundRobinMode::Sequential => (self.round_robin_index + 1) % count,
+            RoundRobinMode::Random if count > 1 => {
+                // Never repeat the previous clip, which is the whole point of rotating.
+                let step = rand::thread_rng().gen_range(1..count);
+                (self.round_robin_index + step) % count
+            }
+            RoundRobinMode::Random => 0,
+        };
+        self.round_robin_index
+    }
+
+    // The source the current bite was cut from, or None for the generated test tone.
+    fn current_source(&self) -> Option<ClipSource> {
+        if self.use_layers {
+            Some(ClipSource::Layers(self.layers.clone(), self.layer_mode))
+        } else if let (Some(path), Some(format)) = (self.selected_path.clone(), self.raw_format) {
+            Some(ClipSource::RawPcm(path, format))
+        } else if let Some(path) = self.selected_path.clone() {
+            Some(ClipSource::File(path))
+        } else {
+            self.memory_source.clone().map(ClipSource::Memory)
+        }
+    }
+
+    fn refresh_clip_for_duration(&mut self) {
+        self.reload_round_robin();
+        if let Some(source) = self.current_source() {
+            self.start_load(source, false);
+        } else {
+            self.pending_load = None;
+            let mut sample = SampleClip::generated_test_tone(self.bite_ms, &self.tone);
+            self.removed_dc = self.shape_clip(&mut sample);
+            self.sample = Some(sample);
+            self.set_status(strings::fill(self.tr(Text::TestToneLoaded), self.bite_ms));
+            self.play_preview();
+        }
+    }
+
+    fn try_play(&mut self, midi_note: i32, velocity: Option<f32>) {
+        self.key_down_at.retain(|&(midi, _)| midi != midi_note);
+        self.key_down_at.push((midi_note, self.time));
+
+        let notes = if self.chord_mode {
+            let notes: Vec<i32> = self
+                .chord_type
+                .intervals()
+                .iter()
+                .map(|interval| midi_note + interval)
+                .filter(|midi| (PIANO_START_MIDI..=PIANO_END_MIDI).contains(midi))
+                .collect();
+            self.held_chords.retain(|(root, _)| *root != midi_note);
+            self.held_chords.push((midi_note, notes.clone()));
+            notes
+        } else {
+            vec![midi_note]
+        };
+
+        let fire_at = if self.quantize {
+            let step = self.subdivision.seconds(self.bpm);
+            let previous = (self.time / step).floor() * step;
+            (self.time - previous > step * 0.5).then_some(previous + step)
+        } else {
+            None
+        };
+        for midi in notes {
+            match fire_at {
+                Some(fire_at) => self.pending_notes.push((midi, fire_at, velocity, false)),
+                None => self.trigger_note(midi, velocity),
+            }
+        }
+    }
+
+    fn fire_pending_notes(&mut self, ctx: &egui::Context) {
+        let now = self.time;
+        let (due, waiting): (Vec<_>, Vec<_>) = self
+            .pending_notes
+            .drain(..)
+            .partition(|&(_, fire_at, _, _)| fire_at <= now);
+        self.pending_notes = waiting;
+        // A tap shorter than the wait still sounds, released as soon as it starts.
+        for (midi, _, velocity, released) in due {
+            self.trigger_note(midi, velocity);
+            if released {
+                self.release_voice(midi);
+            }
+        }
+        if let Some(next) = self
+            .pending_notes
+            .iter()
+            .map(|&(_, fire_at, _, _)| fire_at)
+            .reduce(f64::min)
+        {
+            ctx.request_repaint_after(std::time::Duration::from_secs_f64(next - now));
+        }
+    }
+
+    // Re-renders every key in the background whenever the clip or its tuning changes.
+    fn update_voice_cache(&mut self, ctx: &egui::Context) {
+        let Some(sample) = self.sample.as_ref().filter(|_| self.performance_mode) else {
+            self.voice_cache = None;
+            self.pending_cache = None;
+            return;
+        };
+        let params = self.note_params();
+        if let Some(pending) = &self.pending_cache {
+            if pending.matches(sample, &params) {
+                match pending.poll() {
+                    None => ctx.request_repaint_after(std::time::Duration::from_millis(50)),
+                    Some(Ok(cache)) => {
+                        self.voice_cache = Some(cache);
+                        self.pending_cache = None;
+                    }
+                    Some(Err(err)) => {
+                        self.set_error(strings::fill(
+                            self.tr(Text::PerformanceModeOff),
+                            format!("{err:#}"),
+                        ));
+                        self.performance_mode = false;
+                        self.pending_cache = None;
+                    }
+                }
+                return;
+            }
+        }
+        if self
+            .voice_cache
+            .as_ref()
+            .is_some_and(|cache| cache.matches(sample, &params))
+        {
+            return;
+        }
+        self.voice_cache = None;
+        self.pending_cache = Some(voice_cache::PendingCache::spawn(
+            sample,
+            &params,
+            PIANO_START_MIDI..=PIANO_END_MIDI,
+        ));
+    }
+
+    // The first zone containing the note wins; notes outside every zone play one-shot.
+    fn play_mode(&self, midi_note: i32) -> PlayMode {
+        self.zones
+            .iter()
+            .find(|zone| (zone.low..=zone.high).contains(&midi_note))
+            .map_or(PlayMode::OneShot, |zone| zone.play_mode)
+    }
+
+    fn trigger_note(&mut self, midi_note: i32, velocity: Option<f32>) {
+        if self.filter && self.filter_record_armed && self.filter_recording.is_none() {
+            self.start_filter_recording();
+        }
+        let mut params = self.note_params();
+        let velocity = velocity.or(self.hold_velocity.then_some(self.next_velocity));
+        if let Some(velocity) = velocity {
+            params.gain *= velocity;
+        }
+        if let Some(db) = self.note_trims.get(&midi_note) {
+            params.gain *= 10f32.powf(db / 20.0);
+        }
+        if self.humanize {
+            self.humanize_params(&mut params);
+        }
+        params.gate = self.play_mode(midi_note) == PlayMode::Gate;
+        // Gated notes sustain through the clip's loop for as long as the key is held.
+        if params.gate {
+            params.loop_control = Some(self.loop_control.clone());
+        }
+        let round_robin = self.next_round_robin_index();
+        let jittered = if round_robin == 0 {
+            self.jittered_bite()
+        } else {
+            None
+        };
+        let cue_slice = match self.cue_mode {
+            CueMode::Off => None,
+            CueMode::Pick => self.cue_slices.get(self.cue_selected),
+            CueMode::Kit => {
+                // Kit slices play at their recorded pitch from the base note upwards.
+                let slice = usize::try_from(midi_note - self.base_midi)
+                    .ok()
+                    .and_then(|index| self.cue_slices.get(index));
+                if slice.is_some() {
+                    params.base_midi = midi_note;
+                }
+                slice
+            }
+        };
+        let sample = match round_robin {
+            _ if cue_slice.is_some() => cue_slice,
+            _ if self.scrub_mode && self.scrub_slice.is_some() => self.scrub_slice.as_ref(),
+            0 => jittered.as_ref().or(self.sample.as_ref()),
+            index => self.round_robin[index - 1]
+                .sample
+                .as_ref()
+                .or(self.sample.as_ref()),
+        };
+        if let (Some(sample), Some(cache)) = (sample, &self.voice_cache) {
+            params.prerendered = cache.voice(sample, midi_note, &params);
+        }
+        // With no clip at all the key still sounds, as a plain sine at its own pitch.
+        let fallback = sample.is_none().then(|| {
+            params.base_midi = midi_note;
+            SampleClip::generated_test_tone(
+                self.bite_ms,
+                &ToneSettings {
+                    waveform: ToneWaveform::Sine,
+                    frequency: 440.0 * 2f32.powf((midi_note - 69) as f32 / 12.0),
+                    harmonics: 1,
+                },
+            )
+        });
+        if let Some(sample) = sample.or(fallback.as_ref()) {
+            if let Err(err) = self.audio.play_note(sample, midi_note, params) {
+                self.set_error(strings::fill(
+                    self.tr(Text::PlaybackError),
+                    format!("{err:#}"),
+                ));
+            }
+        }
+        if fallback.is_some() && self.status != self.tr(Text::FallbackSynth) {
+            self.set_status(self.tr(Text::FallbackSynth));
+        }
+        if let Some(out) = &mut self.midi_out {
+            let velocity = velocity.map_or(MIDI_OUT_DEFAULT_VELOCITY, |velocity| {
+                (velocity * 127.0).round() as u8
+            });
+            if let Err(err) = out.note_on(midi_note, velocity) {
+                self.set_error(strings::fill(
+                    self.tr(Text::MidiOutError),
+                    format!("{err:#}"),
+                ));
+            }
+        }
+    }
+
+    fn start_osc(&mut self) {
+        self.osc = None;
+        if !self.osc_enabled {
+            self.set_status(self.tr(Text::OscStopped));
+            return;
+        }
+        match osc::OscServer::bind(self.osc_port, self.osc_network) {
+            Ok(server) => {
+                self.set_status(strings::fill(self.tr(Text::OscListening), server.address));
+                self.osc = Some(server);
+            }
+            Err(err) => {
+                self.osc_enabled = false;
+                self.set_error(strings::fill(self.tr(Text::OscError), format!("{err:#}")));
+            }
+        }
+    }
+
+    fn poll_osc(&mut self, ctx: &egui::Context) {
+        let Some(server) = &self.osc else {
+            return;
+        };
+        for command in server.poll() {
+            match command {
+                osc::OscCommand::Note {
+                    midi,
+                    velocity: Some(velocity),
+                } if velocity <= 0.0 => self.release_note(midi),
+                osc::OscCommand::Note { midi, velocity } => {
+                    if (PIANO_START_MIDI..=PIANO_END_MIDI).contains(&midi) {
+                        self.try_play(midi, velocity);
+                    }
+                }
+                osc::OscCommand::Param { name, value } => self.set_osc_param(&name, value),
+            }
+        }
+        ctx.request_repaint_after(std::time::Duration::from_millis(10));
+    }
+
+    fn set_osc_param(&mut self, name: &str, value: f32) {
+        match name {
+            "bite" => {
+                let bite_ms = (value.round() as u32).clamp(MIN_BITE_MS, MAX_BITE_MS);
+                if bite_ms != self.bite_ms {
+                    self.bite_ms = bite_ms;
+                    self.commit_bite();
+                    self.refresh_clip_for_duration();
+                }
+            }
+            "pan" => self.pan = value.clamp(-1.0, 1.0),
+            "detune" => self.detune_cents = value.clamp(-100.0, 100.0),
+            "transpose" => {
+                self.transpose = (value.round() as i32).clamp(-MAX_TRANSPOSE, MAX_TRANSPOSE)
+            }
+            "base" => self.base_midi = (value.round() as i32).clamp(0, 127),
+            "bpm" => self.bpm = value.clamp(30.0, 300.0),
+            _ => self.set_error(strings::fill(self.tr(Text::OscUnknownParam), name)),
+        }
+    }
+
+    fn refresh_midi_ports(&mut self) {
+        match midi_out::port_names() {
+            Ok(ports) => self.midi_out_ports = ports,
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::MidiOutError),
+                format!("{err:#}"),
+            )),
+        }
+        match midi_in::port_names() {
+            Ok(ports) => self.midi_in_ports = ports,
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::MidiInError),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn connect_midi_in(&mut self) {
+        self.midi_in = None;
+        if !self.midi_in_enabled {
+            return;
+        }
+        let Some(port) = &self.midi_in_port else {
+            return;
+        };
+        match midi_in::MidiIn::connect(port) {
+            Ok(input) => {
+                self.set_status(strings::fill(
+                    self.tr(Text::MidiInListening),
+                    &input.port_name,
+                ));
+                self.midi_in = Some(input);
+            }
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::MidiInError),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    // Program N loads the Nth preset in the session's preset list.
+    fn poll_midi_in(&mut self, ctx: &egui::Context) {
+        let Some(input) = &self.midi_in else {
+            return;
+        };
+        ctx.request_repaint_after(std::time::Duration::from_millis(50));
+        for program in input.poll_programs() {
+            match self.presets.get(usize::from(program)).cloned() {
+                Some(path) => self.load_preset(path),
+                None => {
+                    self.set_status(strings::fill_all(
+                        self.tr(Text::ProgramChangeIgnored),
+                        &[&(program + 1), &self.presets.len()],
+                    ));
+                }
+            }
+        }
+    }
+
+    fn connect_midi_out(&mut self) {
+        self.midi_out = None;
+        if !self.midi_out_enabled {
+            return;
+        }
+        let Some(port) = &self.midi_out_port else {
+            return;
+        };
+        match midi_out::MidiOut::connect(port) {
+            Ok(out) => {
+                self.set_status(strings::fill(self.tr(Text::MidiOutSending), &out.port_name));
+                self.midi_out = Some(out);
+            }
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::MidiOutError),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn humanize_params(&mut self, params: &mut NoteParams) {
+        let amount = self.humanize_amount;
+        let rng = &mut self.humanize_rng;
+        params.detune_cents += rng.gen_range(-1.0..=1.0) * HUMANIZE_MAX_CENTS * amount;
+        params.delay_ms = rng.gen_range(0.0..=1.0) * HUMANIZE_MAX_DELAY_MS * amount;
+        let gain_db = rng.gen_range(-1.0..=1.0) * HUMANIZE_MAX_GAIN_DB * amount;
+        params.gain *= 10f32.powf(gain_db / 20.0);
+        let spread = rng.gen_range(-1.0..=1.0) * HUMANIZE_MAX_PAN * amount;
+        params.pan = (params.pan + spread).clamp(-1.0, 1.0);
+    }
+
+    // Re-cuts the bite up to the jitter later in the cached full decode, never past its end.
+    fn jittered_bite(&mut self) -> Option<SampleClip> {
+        if self.start_jitter_ms == 0 || self.cue_mode != CueMode::Off || self.scrub_mode {
+            return None;
+        }
+        let clip = self.full_clip.as_ref()?;
+        let bite_frames = clip::frames_for_ms(clip.sample_rate, self.bite_ms);
+        let spare = clip
+            .decoded_frames
+            .saturating_sub(self.bite_start + bite_frames);
+        let max_offset = clip::frames_for_ms(clip.sample_rate, self.start_jitter_ms).min(spare);
+        if max_offset == 0 {
+            return None;
+        }
+        let offset = self.start_jitter_rng.gen_range(0..=max_offset);
+        let mut sample = clip.slice(self.bite_start + offset, bite_frames);
+        self.shape_clip(&mut sample);
+        Some(sample)
+    }
+
+    fn reseed_humanize(&mut self) {
+        self.humanize_rng = match self.humanize_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+    }
+
+    fn reload_full_clip(&mut self) {
+        self.full_clip = None;
+        self.scrub_slice = None;
+        self.cue_slices.clear();
+        self.full_decode_used_s = self.full_decode_s;
+        self.full_load = self.current_source().map(|source| {
+            PendingLoad::spawn(
+                source,
+                self.full_decode_s * 1_000,
+                self.preserve_stereo,
+                self.track_id,
+                self.rate_override,
+                false,
+            )
+        });
+        if self.full_load.is_none() {
+            self.update_scrub_slice();
+        }
+    }
+
+    fn poll_full_load(&mut self, ctx: &egui::Context) {
+        let Some(load) = &self.full_load else {
+            return;
+        };
+        let Some(result) = load.poll() else {
+            ctx.request_repaint_after(std::time::Duration::from_millis(50));
+            return;
+        };
+        self.full_load = None;
+        match result {
+            Ok((_, sample)) => {
+                self.full_clip = Some(sample);
+                if std::mem::take(&mut self.detect_transients_pending) {
+                    self.detect_transients();
+                }
+                self.update_scrub_slice();
+                self.update_cue_slices();
+                if self.bite_start > 0 {
+                    self.apply_bite_start();
+                }
+            }
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::FullFileFailed),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn nudge_bite_start(&mut self, forward: bool) {
+        if self.using_test_tone() {
+            self.set_status(self.tr(Text::NudgeNeedsFile));
+            return;
+        }
+        self.bite_start = if forward {
+            self.bite_start + self.nudge_frames
+        } else {
+            self.bite_start.saturating_sub(self.nudge_frames)
+        };
+        self.recut_bite_start();
+    }
+
+    fn recut_bite_start(&mut self) {
+        if self.full_clip.is_some() {
+            self.apply_bite_start();
+        } else if self.full_load.is_none() {
+            self.reload_full_clip();
+        }
+    }
+
+    // Re-cuts the bite from the cached full decode so nudges never re-read the file.
+    fn apply_bite_start(&mut self) {
+        let Some(clip) = &self.full_clip else {
+            return;
+        };
+        self.bite_start = self.bite_start.min(clip.decoded_frames.saturating_sub(1));
+        let bite_frames = clip::frames_for_ms(clip.sample_rate, self.bite_ms);
+        let mut sample = clip.slice(self.bite_start, bite_frames);
+        self.removed_dc = self.shape_clip(&mut sample);
+        self.sample = Some(sample);
+    }
+
+    fn draw_bite_start(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let sample_rate = self
+                .full_clip
+                .as_ref()
+                .or(self.sample.as_ref())
+                .map_or(44_100, |clip| clip.sample_rate);
+            let ms = self.bite_start as f64 * 1_000.0 / f64::from(sample_rate);
+            ui.label(strings::fill_all(
+                self.tr(Text::BiteStart),
+                &[&format!("{ms:.3}"), &self.bite_start],
+            ));
+            if ui
+                .small_button("◀")
+                .on_hover_text(self.tr(Text::NudgeEarlier))
+                .clicked()
+            {
+                self.nudge_bite_start(false);
+            }
+            if ui
+                .small_button("▶")
+                .on_hover_text(self.tr(Text::NudgeLater))
+                .clicked()
+            {
+                self.nudge_bite_start(true);
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.nudge_frames)
+                    .range(1..=MAX_NUDGE_FRAMES)
+                    .prefix(strings::get(self.language, Text::NudgeStep))
+                    .suffix(strings::get(self.language, Text::NudgeSamples)),
+            );
+            if self.full_load.is_some() && self.bite_start > 0 {
+                ui.spinner();
+            }
+        });
+    }
+
+    // Each cue starts a bite that runs for the bite length or up to the next cue.
+    fn update_cue_slices(&mut self) {
+        let Some(clip) = self
+            .full_clip
+            .as_ref()
+            .filter(|_| self.cue_mode != CueMode::Off)
+        else {
+            self.cue_slices.clear();
+            return;
+        };
+        let bite_frames = clip::frames_for_ms(clip.sample_rate, self.bite_ms);
+        let points = self.transient_frames.as_ref().unwrap_or(&clip.cue_frames);
+        let mut slices: Vec<SampleClip> = points
+            .iter()
+            .enumerate()
+            .map(|(index, &start)| {
+                let next = points.get(index + 1).map_or(usize::MAX, |&next| next);
+                clip.slice(start, bite_frames.min(next - start))
+            })
+            .collect();
+        for slice in &mut slices {
+            self.shape_clip(slice);
+        }
+        self.cue_selected = self.cue_selected.min(slices.len().saturating_sub(1));
+        self.cue_slices = slices;
+    }
+
+    fn detect_transients(&mut self) {
+        let Some(clip) = &self.full_clip else {
+            self.detect_transients_pending = true;
+            if self.full_load.is_none() {
+                self.reload_full_clip();
+            }
+            return;
+        };
+        let onsets = clip.detect_onsets(self.transient_sensitivity);
+        self.set_status(match onsets.len() {
+            0 => self.tr(Text::NoTransients).to_string(),
+            count => strings::fill(self.tr(Text::FoundTransients), count),
+        });
+        if !onsets.is_empty() && self.cue_mode == CueMode::Off {
+            self.cue_mode = CueMode::Kit;
+        }
+        self.transient_frames = Some(onsets).filter(|onsets| !onsets.is_empty());
+        self.cue_selected = 0;
+        self.update_cue_slices();
+    }
+
+    fn draw_transient_slicing(&mut self, ui: &mut egui::Ui) {
+        if self.using_test_tone() {
+            return;
+        }
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::Slider::new(&mut self.transient_sensitivity, 0.0..=1.0)
+                    .text(strings::get(self.language, Text::Sensitivity)),
+            )
+            .on_hover_text(self.tr(Text::SensitivityHint));
+            if ui
+                .button(self.tr(Text::AutoSlice))
+                .on_hover_text(self.tr(Text::AutoSliceHint))
+                .clicked()
+            {
+                self.detect_transients();
+            }
+            if self.transient_frames.is_some() && ui.button(self.tr(Text::UseCueMarkers)).clicked()
+            {
+                self.transient_frames = None;
+                self.cue_selected = 0;
+                self.update_cue_slices();
+            }
+            if self.detect_transients_pending {
+                ui.spinner();
+            }
+        });
+    }
+
+    fn slice_points(&self) -> Option<&[usize]> {
+        let points = match &self.transient_frames {
+            Some(onsets) => onsets,
+            None => &self.sample.as_ref()?.cue_frames,
+        };
+        (!points.is_empty()).then_some(points.as_slice())
+    }
+
+    fn draw_cue_slices(&mut self, ui: &mut egui::Ui) {
+        self.draw_transient_slicing(ui);
+        let Some(cues) = self.slice_points().map(<[usize]>::to_vec) else {
+            return;
+        };
+        let Some(sample) = &self.sample else {
+            return;
+        };
+        let sample_rate = sample.sample_rate.max(1) as f32;
+        let kind = self.tr(if self.transient_frames.is_some() {
+            Text::Transients
+        } else {
+            Text::CueMarkers
+        });
+        let cue_template = self.tr(Text::CueLabel);
+        let cue_label = |index: usize| {
+            strings::fill_all(
+                cue_template,
+                &[
+                    &(index + 1),
+                    &format!("{:.2}", cues[index] as f32 / sample_rate),
+                ],
+            )
+        };
+        ui.horizontal(|ui| {
+            let before = self.cue_mode;
+            egui::ComboBox::from_label(format!("{} {kind}", cues.len()))
+                .selected_text(strings::get(self.language, self.cue_mode.label()))
+                .show_ui(ui, |ui| {
+                    for mode in CueMode::ALL {
+                        ui.selectable_value(
+                            &mut self.cue_mode,
+                            mode,
+                            strings::get(self.language, mode.label()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(self.tr(Text::CueModeHint));
+            if self.cue_mode == CueMode::Pick {
+                egui::ComboBox::from_id_source("cue_selected")
+                    .selected_text(cue_label(self.cue_selected.min(cues.len() - 1)))
+                    .show_ui(ui, |ui| {
+                        for index in 0..cues.len() {
+                            ui.selectable_value(&mut self.cue_selected, index, cue_label(index));
+                        }
+                    });
+            }
+            if self.cue_mode != before {
+                if self.full_clip.is_none() && self.full_load.is_none() {
+                    self.reload_full_clip();
+                } else {
+                    self.update_cue_slices();
+                }
+            }
+            if self.cue_mode != CueMode::Off && self.full_load.is_some() {
+                ui.spinner();
+            }
+        });
+    }
+
+    // Falls back to the bite itself for the test tone or while the long decode runs.
+    fn update_scrub_slice(&mut self) {
+        let Some(clip) = self.full_clip.as_ref().or(self.sample.as_ref()) else {
+            self.scrub_slice = None;
+            return;
+        };
+        let frame = (self.scrub_position * clip.decoded_frames as f32) as usize;
+        self.scrub_slice = Some(clip.slice_from(frame));
+    }
+
+    fn toggle_scrub_player(&mut self) {
+        if let Some(player) = self.scrub_player.take() {
+            self.smooth_stop(&player);
+            return;
+        }
+        let Some(slice) = &self.scrub_slice else {
+            return;
+        };
+        let params = NoteParams {
+            freeze: None,
+            ..self.note_params()
+        };
+        match self.audio.play_note(slice, self.base_midi, params) {
+            Ok(release) => self.scrub_player = Some(release),
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::PlaybackError),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn draw_scrub_preview(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .checkbox(
+                    &mut self.scrub_mode,
+                    strings::get(self.language, Text::PlayFromScrub),
+                )
+                .on_hover_text(self.tr(Text::PlayFromScrubHint))
+                .changed()
+            {
+                if self.scrub_mode {
+                    self.reload_full_clip();
+                } else if let Some(player) = self.scrub_player.take() {
+                    self.smooth_stop(&player);
+                }
+            }
+            if self.full_load.is_some() {
+                ui.spinner();
+            }
+        });
+        ui.horizontal(|ui| {
+            let cap = ui
+                .add(
+                    egui::DragValue::new(&mut self.full_decode_s)
+                        .range(1..=MAX_FULL_DECODE_S)
+                        .prefix(strings::get(self.language, Text::DecodeUpTo))
+                        .suffix(" s"),
+                )
+                .on_hover_text(self.tr(Text::DecodeUpToHint));
+            // Re-decode once the drag settles rather than on every step.
+            if (cap.drag_stopped() || cap.lost_focus())
+                && self.full_decode_s != self.full_decode_used_s
+                && (self.full_clip.is_some() || self.full_load.is_some())
+            {
+                self.reload_full_clip();
+            }
+            if self.full_clip.as_ref().is_some_and(|clip| clip.truncated) {
+                ui.weak(strings::fill(self.tr(Text::FileLonger), self.full_decode_s));
+            }
+        });
+        ui.add_enabled_ui(self.scrub_mode, |ui| {
+            let clip = self.full_clip.as_ref().or(self.sample.as_ref());
+            let seconds = clip.map_or(0.0, |clip| {
+                clip.decoded_frames as f32 / clip.sample_rate.max(1) as f32
+            });
+            let position = strings::fill_all(
+                self.tr(Text::ScrubPosition),
+                &[
+                    &format!("{:.2}", self.scrub_position * seconds),
+                    &format!("{seconds:.2}"),
+                ],
+            );
+            let slider = ui.add(
+                egui::Slider::new(&mut self.scrub_position, 0.0..=1.0)
+                    .show_value(false)
+                    .text(position),
+            );
+            if slider.changed() {
+                self.update_scrub_slice();
+            }
+            let label = self.tr(if self.scrub_player.is_some() {
+                Text::StopSpace
+            } else {
+                Text::PlaySpace
+            });
+            if ui.button(label).clicked() {
+                self.toggle_scrub_player();
+            }
+        });
+    }
+
+    fn play_preview(&mut self) {
+        if let Some(previous) = self.preview.take() {
+            self.smooth_stop(&previous);
+        }
+        if !self.auto_preview {
+            return;
+        }
+        let params = NoteParams {
+            freeze: None,
+            ..self.note_params()
+        };
+        if let Some(sample) = &self.sample {
+            match self.audio.play_note(sample, self.base_midi, params) {
+                Ok(release) => self.preview = Some(release),
+                Err(err) => self.set_error(strings::fill(
+                    self.tr(Text::PlaybackError),
+                    format!("{err:#}"),
+                )),
+            }
+        }
+    }
+
+    // Bypassing drops the effects but keeps their settings, and scales the dry signal to
+    // the processed level so the comparison isn't skewed by volume.
+    fn note_params(&self) -> NoteParams {
+        let params = self.processed_params();
+        if !self.bypass_effects {
+            return params;
+        }
+        NoteParams {
+            gain: params.gain * self.bypass_gain,
+            ..Self::dry_params(params)
+        }
+    }
+
+    fn dry_params(params: NoteParams) -> NoteParams {
+        NoteParams {
+            crush: None,
+            overdrive: None,
+            reverb: None,
+            tremolo: None,
+            filter: None,
+            auto_wah: None,
+            ..params
+        }
+    }
+
+    fn bypass_key(&self) -> BypassKey {
+        BypassKey {
+            samples: self.sample.as_ref().map(|s| Arc::clone(&s.samples)),
+            base_midi: self.base_midi,
+            gain: self.playback_gain(),
+            tremolo_rate_hz: self.tremolo_rate_hz(),
+            filter_play_automation: self.filter_play_automation,
+            filter_automation: Arc::clone(&self.filter_automation),
+            effects: self.effect_snapshot(String::new()),
+        }
+    }
+
+    // egui reports OS key-repeat as more presses, so a key only strikes again once it has
+    // been let go; `held_keys` forgets keys as soon as they are no longer down.
+    fn key_struck(&mut self, ctx: &egui::Context, key: egui::Key) -> bool {
+        ctx.input(|i| i.key_pressed(key)) && self.held_keys.insert(key)
+    }
+
+    // BPM follows the average interval of the last few taps; a long pause starts over.
+    fn tap_tempo(&mut self) {
+        if self
+            .tap_times
+            .back()
+            .is_some_and(|&last| self.time - last > TAP_RESET_S)
+        {
+            self.tap_times.clear();
+        }
+        if self.tap_times.len() == TAP_HISTORY {
+            self.tap_times.pop_front();
+        }
+        self.tap_times.push_back(self.time);
+        if let (Some(first), Some(last)) = (self.tap_times.front(), self.tap_times.back()) {
+            let taps = self.tap_times.len();
+            if taps >= 2 {
+                let interval = (last - first) / (taps - 1) as f64;
+                self.bpm = (60.0 / interval as f32).clamp(30.0, 300.0);
+            }
+        }
+    }
+
+    fn effect_snapshot(&self, name: String) -> EffectSnapshot {
+        EffectSnapshot {
+            name,
+            crush: self.crush,
+            crush_bits: self.crush_bits,
+            crush_downsample: self.crush_downsample,
+            crush_mix: self.crush_mix,
+            overdrive: self.overdrive,
+            overdrive_curve: self.overdrive_curve,
+            overdrive_drive_db: self.overdrive_drive_db,
+            overdrive_level_db: self.overdrive_level_db,
+            overdrive_mix: self.overdrive_mix,
+            reverb: self.reverb,
+            reverb_room: self.reverb_room,
+            reverb_damping: self.reverb_damping,
+            reverb_mix: self.reverb_mix,
+            tremolo: self.tremolo,
+            tremolo_rate: self.tremolo_rate,
+            tremolo_depth: self.tremolo_depth,
+            tremolo_shape: self.tremolo_shape,
+            tremolo_sync: self.tremolo_sync,
+            filter: self.filter,
+            filter_cutoff: self.filter_cutoff,
+            filter_resonance: self.filter_resonance,
+            auto_wah: self.auto_wah,
+            auto_wah_sensitivity: self.auto_wah_sensitivity,
+            auto_wah_low_hz: self.auto_wah_low_hz,
+            auto_wah_high_hz: self.auto_wah_high_hz,
+            auto_wah_attack_ms: self.auto_wah_attack_ms,
+            auto_wah_release_ms: self.auto_wah_release_ms,
+            auto_wah_resonance: self.auto_wah_resonance,
+        }
+    }
+
+    fn apply_effects(&mut self, snapshot: &EffectSnapshot) {
+        self.crush = snapshot.crush;
+        self.crush_bits = snapshot.crush_bits.clamp(1, 16);
+        self.crush_downsample = snapshot.crush_downsample.clamp(1, 32);
+        self.crush_mix = snapshot.crush_mix.clamp(0.0, 1.0);
+        self.overdrive = snapshot.overdrive;
+        self.overdrive_curve = snapshot.overdrive_curve;
+        self.overdrive_drive_db = snapshot.overdrive_drive_db.clamp(0.0, MAX_DRIVE_DB);
+        self.overdrive_level_db = snapshot.overdrive_level_db.clamp(-24.0, 6.0);
+        self.overdrive_mix = snapshot.overdrive_mix.clamp(0.0, 1.0);
+        self.reverb = snapshot.reverb;
+        self.reverb_room = snapshot.reverb_room.clamp(0.0, 1.0);
+        self.reverb_damping = snapshot.reverb_damping.clamp(0.0, 1.0);
+        self.reverb_mix = snapshot.reverb_mix.clamp(0.0, 1.0);
+        self.tremolo = snapshot.tremolo;
+        self.tremolo_rate = snapshot.tremolo_rate.clamp(0.1, 20.0);
+        self.tremolo_depth = snapshot.tremolo_depth.clamp(0.0, 1.0);
+        self.tremolo_shape = snapshot.tremolo_shape;
+        self.tremolo_sync = snapshot.tremolo_sync;
+        self.filter = snapshot.filter;
+        self.set_filter_cutoff(
+            snapshot
+                .filter_cutoff
+                .clamp(dsp::MIN_FILTER_CUTOFF_HZ, dsp::MAX_FILTER_CUTOFF_HZ),
+        );
+        self.filter_resonance = snapshot.filter_resonance.clamp(0.5, 10.0);
+        self.auto_wah = snapshot.auto_wah;
+        self.auto_wah_sensitivity = snapshot.auto_wah_sensitivity.clamp(0.0, 1.0);
+        self.auto_wah_low_hz = snapshot
+            .auto_wah_low_hz
+            .clamp(dsp::MIN_FILTER_CUTOFF_HZ, dsp::MAX_FILTER_CUTOFF_HZ);
+        self.auto_wah_high_hz = snapshot
+            .auto_wah_high_hz
+            .clamp(self.auto_wah_low_hz, dsp::MAX_FILTER_CUTOFF_HZ);
+        self.auto_wah_attack_ms = snapshot.auto_wah_attack_ms.clamp(1.0, 100.0);
+        self.auto_wah_release_ms = snapshot.auto_wah_release_ms.clamp(10.0, 1_000.0);
+        self.auto_wah_resonance = snapshot.auto_wah_resonance.clamp(0.5, 10.0);
+    }
+
+    // Playing voices keep the effects they started with (only the live filter cutoff
+    // follows), unless the user asked for a clean cut.
+    fn recall_snapshot(&mut self, index: usize) {
+        let Some(snapshot) = self.effect_snapshots.get(index).cloned() else {
+            return;
+        };
+        if self.snapshot_cuts_notes {
+            self.all_notes_off();
+        }
+        self.apply_effects(&snapshot);
+        self.active_snapshot = Some(index);
+    }
+
+    fn draw_effect_snapshots(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.snapshot_name)
+                    .hint_text(strings::get(self.language, Text::SnapshotName))
+                    .desired_width(100.0),
+            );
+            let full = self.effect_snapshots.len() >= MAX_EFFECT_SNAPSHOTS;
+            if ui
+                .add_enabled(!full, egui::Button::new(self.tr(Text::StoreEffects)))
+                .clicked()
+            {
+                let name = match self.snapshot_name.trim() {
+                    "" => strings::fill(
+                        self.tr(Text::SnapshotDefaultName),
+                        self.effect_snapshots.len() + 1,
+                    ),
+                    name => name.to_string(),
+                };
+                self.effect_snapshots.push(self.effect_snapshot(name));
+                self.active_snapshot = Some(self.effect_snapshots.len() - 1);
+                self.snapshot_name.clear();
+            }
+            ui.checkbox(
+                &mut self.snapshot_cuts_notes,
+                strings::get(self.language, Text::RecallStopsNotes),
+            )
+            .on_hover_text(self.tr(Text::RecallStopsNotesHint));
+        });
+        let mut recall = None;
+        let mut update = None;
+        let mut remove = None;
+        for (index, snapshot) in self.effect_snapshots.iter().enumerate() {
+            ui.horizontal(|ui| {
+                let active = self.active_snapshot == Some(index);
+                if ui
+                    .selectable_label(active, format!("{}  {}", index + 1, snapshot.name))
+                    .on_hover_text(strings::fill(self.tr(Text::RecallKey), index + 1))
+                    .clicked()
+                {
+                    recall = Some(index);
+                }
+                if ui
+                    .small_button(self.tr(Text::Update))
+                    .on_hover_text(self.tr(Text::UpdateHint))
+                    .clicked()
+                {
+                    update = Some(index);
+                }
+                if ui
+                    .small_button("🗑")
+                    .on_hover_text(self.tr(Text::Remove))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+            });
+        }
+        if let Some(index) = recall {
+            self.recall_snapshot(index);
+        }
+        if let Some(index) = update {
+            let name = self.effect_snapshots[index].name.clone();
+            self.effect_snapshots[index] = self.effect_snapshot(name);
+            self.active_snapshot = Some(index);
+        }
+        if let Some(index) = remove {
+            self.effect_snapshots.remove(index);
+            self.active_snapshot = match self.active_snapshot {
+                Some(active) if active == index => None,
+                Some(active) if active > index => Some(active - 1),
+                active => active,
+            };
+        }
+    }
+
+    // Renders a second of the base note with and without effects on a worker thread, so
+    // the level match never stalls the UI.
+    fn update_bypass_gain(&mut self, ctx: &egui::Context) {
+        if !self.bypass_effects {
+            self.bypass_key = None;
+            self.bypass_pending = None;
+            self.bypass_match = None;
+            self.bypass_gain = 1.0;
+            return;
+        }
+        if let Some(receiver) = &self.bypass_match {
+            match receiver.try_recv() {
+                Ok(gain) => {
+                    self.bypass_gain = gain;
+                    self.bypass_match = None;
+                }
+                Err(mpsc::TryRecvError::Empty) => {
+                    ctx.request_repaint_after(Duration::from_millis(50));
+                }
+                Err(mpsc::TryRecvError::Disconnected) => self.bypass_match = None,
+            }
+        }
+
+        let key = self.bypass_key();
+        if self.bypass_key.as_ref() == Some(&key) {
+            self.bypass_pending = None;
+            return;
+        }
+        // Engaging bypass matches straight away; later edits wait for the drag to settle.
+        if self.bypass_key.is_some() {
+            let changed_at = match &self.bypass_pending {
+                Some((pending, changed_at)) if *pending == key => *changed_at,
+                _ => {
+                    self.bypass_pending = Some((key.clone(), self.time));
+                    self.time
+                }
+            };
+            let wait = BYPASS_MATCH_DEBOUNCE_S - (self.time - changed_at);
+            if wait > 0.0 {
+                ctx.request_repaint_after(Duration::from_secs_f64(wait));
+                return;
+            }
+        }
+        self.bypass_pending = None;
+        self.bypass_key = Some(key);
+
+        let Some(sample) = self.sample.clone() else {
+            self.bypass_match = None;
+            self.bypass_gain = 1.0;
+            return;
+        };
+        let processed = NoteParams {
+            pan: 0.0,
+            ..self.processed_params()
+        };
+        let base_midi = self.base_midi;
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let dry = Self::dry_params(processed.clone());
+            let wet_rms = render::voice_rms(&sample, base_midi, &processed, BYPASS_MATCH_MS);
+            let dry_rms = render::voice_rms(&sample, base_midi, &dry, BYPASS_MATCH_MS);
+            let mut gain = 1.0;
+            if wet_rms > 1e-5 && dry_rms > 1e-5 {
+                let db = (20.0 * (wet_rms / dry_rms).log10())
+                    .clamp(-BYPASS_MATCH_RANGE_DB, BYPASS_MATCH_RANGE_DB);
+                gain = 10f32.powf(db / 20.0);
+            }
+            let _ = sender.send(gain);
+        });
+        // Dropping an older receiver discards a match for settings that no longer apply.
+        self.bypass_match = Some(receiver);
+        ctx.request_repaint_after(Duration::from_millis(50));
+    }
+
+    fn processed_params(&self) -> NoteParams {
+        NoteParams {
+            base_midi: self.base_midi,
+            detune_cents: self.detune_cents,
+            transpose: self.transpose,
+            pan: if self.random_pan {
+                rand::thread_rng().gen_range(-1.0..=1.0)
+            } else {
+                self.pan
+            },
+            width: self.stereo_width / 100.0,
+            gain: self.playback_gain(),
+            delay_ms: 0.0,
+            interp: self.interp,
+            freeze: self.freeze.then_some(FreezeParams {
+                grain_ms: self.grain_ms,
+                position: self.freeze_position,
+            }),
+            crush: self.crush.then_some(CrushParams {
+                bits: self.crush_bits,
+                downsample: self.crush_downsample,
+                mix: self.crush_mix,
+            }),
+            overdrive: self.overdrive.then_some(OverdriveParams {
+                curve: self.overdrive_curve,
+                drive_db: self.overdrive_drive_db,
+                level_db: self.overdrive_level_db,
+                mix: self.overdrive_mix,
+            }),
+            reverb: self.reverb.then_some(ReverbParams {
+                room_size: self.reverb_room,
+                damping: self.reverb_damping,
+                mix: self.reverb_mix,
+            }),
+            tremolo: self.tremolo.then_some(TremoloParams {
+                rate_hz: self.tremolo_rate_hz(),
+                depth: self.tremolo_depth,
+                shape: self.tremolo_shape,
+            }),
+            filter: self.filter.then(|| FilterParams {
+                control: self.filter_control.clone(),
+                resonance: self.filter_resonance,
+                automation: (self.filter_play_automation
+                    && self.filter_recording.is_none()
+                    && !self.filter_automation.is_empty())
+                .then(|| Arc::clone(&self.filter_automation)),
+            }),
+            auto_wah: self.auto_wah.then_some(AutoWahParams {
+                sensitivity: self.auto_wah_sensitivity,
+                low_hz: self.auto_wah_low_hz,
+                high_hz: self.auto_wah_high_hz,
+                attack_ms: self.auto_wah_attack_ms,
+                release_ms: self.auto_wah_release_ms,
+                resonance: self.auto_wah_resonance,
+            }),
+            release_curve: self.release_curve,
+            release_ms: self.note_off_fade_ms(),
+            smoothing_ms: self.smoothing_ms,
+            attack_ms: self.attack_ms,
+            gate: false,
+            loop_control: None,
+            prerendered: None,
+            bend: self.drag_bend.then(|| self.bend.clone()),
+        }
+    }
+
+    fn set_filter_cutoff(&mut self, cutoff_hz: f32) {
+        self.filter_cutoff = cutoff_hz;
+        self.filter_control.set(cutoff_hz);
+    }
+
+    fn start_filter_recording(&mut self) {
+        self.filter_recording = Some(self.time);
+        self.filter_automation = Arc::new(vec![(0.0, self.filter_cutoff)]);
+        self.set_status(self.tr(Text::FilterRecording));
+    }
+
+    fn update_filter_recording(&mut self, ctx: &egui::Context) {
+        let Some(started) = self.filter_recording else {
+            return;
+        };
+        let elapsed_ms = ((self.time - started) * 1_000.0) as f32;
+        if !self.filter_record_armed || elapsed_ms > MAX_AUTOMATION_MS {
+            self.filter_recording = None;
+            self.filter_record_armed = false;
+            self.filter_play_automation = true;
+            self.set_status(strings::fill_all(
+                self.tr(Text::FilterRecorded),
+                &[
+                    &self.filter_automation.len(),
+                    &format!("{:.1}", elapsed_ms.min(MAX_AUTOMATION_MS) / 1_000.0),
+                ],
+            ));
+            return;
+        }
+        let points = Arc::make_mut(&mut self.filter_automation);
+        if points
+            .last()
+            .is_some_and(|&(_, value)| value != self.filter_cutoff)
+        {
+            points.push((elapsed_ms, self.filter_cutoff));
+        }
+        ctx.request_repaint();
+    }
+
+    fn tremolo_rate_hz(&self) -> f32 {
+        if self.tremolo_sync {
+            (1.0 / self.tremolo_subdivision.seconds(self.bpm)) as f32
+        } else {
+            self.tremolo_rate
+        }
+    }
+
+    fn set_latency(&mut self, latency: LatencyMode) {
+        self.preview = None;
+        self.reference = None;
+        self.pending_notes.clear();
+        // Release the device before reopening it; some backends only allow one stream.
+        self.audio = AudioEngine::silent_fallback();
+        match AudioEngine::new(latency) {
+            Ok(audio) => {
+                self.audio = audio;
+                self.latency = latency;
+                self.set_status(strings::fill(
+                    self.tr(Text::LatencySwitched),
+                    self.tr(latency.label()),
+                ));
+            }
+            Err(err) => {
+                self.audio = AudioEngine::open(self.latency);
+                self.set_error(strings::fill_all(
+                    self.tr(Text::LatencySwitchFailed),
+                    &[&self.tr(latency.label()), &format!("{err:#}")],
+                ));
+            }
+        }
+        self.apply_limiter();
+    }
+
+    fn apply_limiter(&self) {
+        self.audio
+            .limiter
+            .set(self.limiter_threshold_db, self.limiter_makeup_db);
+    }
+
+    fn commit_bite(&mut self) {
+        if self.bite_ms == self.bite_committed {
+            return;
+        }
+        if self.bite_undo.len() >= BITE_HISTORY_LIMIT {
+            self.bite_undo.remove(0);
+        }
+        self.bite_undo.push(self.bite_committed);
+        self.bite_redo.clear();
+        self.bite_committed = self.bite_ms;
+    }
+
+    fn undo_bite(&mut self) {
+        if let Some(bite_ms) = self.bite_undo.pop() {
+            self.bite_redo.push(self.bite_committed);
+            self.restore_bite(bite_ms);
+        }
+    }
+
+    fn redo_bite(&mut self) {
+        if let Some(bite_ms) = self.bite_redo.pop() {
+            self.bite_undo.push(self.bite_committed);
+            self.restore_bite(bite_ms);
+        }
+    }
+
+    fn restore_bite(&mut self, bite_ms: u32) {
+        self.bite_ms = bite_ms;
+        self.bite_committed = bite_ms;
+        self.refresh_clip_for_duration();
+    }
+
+    fn playback_gain(&self) -> f32 {
+        self.level_gain(self.sample.as_ref().and_then(|s| s.loudness_db))
+    }
+
+    fn level_gain(&self, loudness: Option<f32>) -> f32 {
+        // Auto-level already pins every slice to the target, so compensation only matters without it.
+        let target = if self.auto_level {
+            Some(AUTO_LEVEL_TARGET_DB)
+        } else if self.bite_gain_comp {
+            self.reference_loudness_db
+        } else {
+            None
+        };
+        match (loudness, target) {
+            (Some(db), Some(target)) => {
+                let offset = (target - db).clamp(-AUTO_LEVEL_RANGE_DB, AUTO_LEVEL_RANGE_DB);
+                10f32.powf(offset / 20.0)
+            }
+            _ => 1.0,
+        }
+    }
+
+    fn release_note(&mut self, midi_note: i32) {
+        if let Some(index) = self
+            .key_down_at
+            .iter()
+            .position(|&(midi, _)| midi == midi_note)
+        {
+            let (_, pressed_at) = self.key_down_at.remove(index);
+            self.next_velocity = hold_velocity(self.time - pressed_at);
+        }
+
+        let notes = match self
+            .held_chords
+            .iter()
+            .position(|(root, _)| *root == midi_note)
+        {
+            Some(index) => self.held_chords.remove(index).1,
+            None => vec![midi_note],
+        };
+        for note in notes {
+            for pending in self.pending_notes.iter_mut().filter(|p| p.0 == note) {
+                pending.3 = true;
+            }
+            self.release_voice(note);
+        }
+    }
+
+    fn release_voice(&mut self, note: i32) {
+        if let Err(err) = self
+            .audio
+            .release_note(note, self.note_off_fade_ms(), self.release_curve)
+        {
+            self.set_error(strings::fill(
+                self.tr(Text::PlaybackError),
+                format!("{err:#}"),
+            ));
+        }
+        if let Some(out) = &mut self.midi_out {
+            if let Err(err) = out.note_off(note) {
+                self.set_error(strings::fill(
+                    self.tr(Text::MidiOutError),
+                    format!("{err:#}"),
+                ));
+            }
+        }
+    }
+
+    fn draw_round_robin(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(self.tr(Text::RoundRobin));
+            if ui
+                .button(self.tr(Text::AddClip))
+                .on_hover_text(self.tr(Text::AddClipHint))
+                .clicked()
+            {
+                if let Some(paths) = rfd::FileDialog::new().pick_files() {
+                    for path in paths {
+                        self.add_round_robin_clip(path);
+                    }
+                }
+            }
+            let mut remove = None;
+            for (index, clip) in self.round_robin.iter().enumerate() {
+                let name = clip
+                    .path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let text = if clip.sample.is_some() {
+                    format!("{name} ✕")
+                } else {
+                    format!("{name} ({}) ✕", self.tr(Text::LoadingShort))
+                };
+                if ui
+                    .small_button(text)
+                    .on_hover_text(self.tr(Text::Remove))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+            }
+            if let Some(index) = remove {
+                self.round_robin.remove(index);
+                self.round_robin_index = 0;
+            }
+            ui.add_enabled_ui(!self.round_robin.is_empty(), |ui| {
+                for mode in [RoundRobinMode::Sequential, RoundRobinMode::Random] {
+                    ui.radio_value(
+                        &mut self.round_robin_mode,
+                        mode,
+                        strings::get(self.language, mode.label()),
+                    );
+                }
+            });
+        });
+    }
+
+    fn draw_layers(&mut self, ui: &mut egui::Ui) {
+        let before = (self.layers.clone(), self.layer_mode);
+        ui.horizontal(|ui| {
+            if ui.button(self.tr(Text::AddLayer)).clicked() {
+                if let Some(paths) = rfd::FileDialog::new().pick_files() {
+                    self.layers
+                        .extend(paths.into_iter().map(|path| clip::ClipLayer {
+                            path,
+                            gain_db: 0.0,
+                            offset_ms: 0,
+                            muted: false,
+                            solo: false,
+                        }));
+                }
+            }
+            for mode in [clip::LayerMode::Sum, clip::LayerMode::Concatenate] {
+                ui.radio_value(
+                    &mut self.layer_mode,
+                    mode,
+                    strings::get(self.language, mode.label()),
+                );
+            }
+        });
+
+        let mut remove = None;
+        let mut reorder = None;
+        for (index, layer) in self.layers.iter_mut().enumerate() {
+            let row = ui.horizontal(|ui| {
+                let handle_id = egui::Id::new(("layer_drag", index));
+                ui.dnd_drag_source(handle_id, index, |ui| ui.label("☰"))
+                    .response
+                    .on_hover_text(strings::get(self.language, Text::DragToReorder));
+                if ui
+                    .small_button("✕")
+                    .on_hover_text(strings::get(self.language, Text::RemoveLayer))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+                ui.toggle_value(&mut layer.muted, "M")
+                    .on_hover_text(strings::get(self.language, Text::MuteLayer));
+                ui.toggle_value(&mut layer.solo, "S")
+                    .on_hover_text(strings::get(self.language, Text::SoloLayer));
+                ui.label(
+                    layer
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default(),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut layer.gain_db)
+                        .range(-24.0..=12.0)
+                        .speed(0.1)
+                        .suffix(" dB"),
+                );
+                ui.add(
+                    egui::DragValue::new(&mut layer.offset_ms)
+                        .range(0..=MAX_BITE_MS)
+                        .prefix("+")
+                        .suffix(" ms"),
+                );
+            });
+            if let Some(from) = row.response.dnd_release_payload::<usize>() {
+                reorder = Some((*from, index));
+            }
+        }
+        if let Some(index) = remove {
+            self.layers.remove(index);
+        } else if let Some((from, to)) = reorder {
+            let layer = self.layers.remove(from);
+            self.layers.insert(to, layer);
+        }
+
+        // Dragging a value rebuilds on every change; decoding runs in the background loader.
+        if (self.layers.clone(), self.layer_mode) != before {
+            if self.layers.is_empty() {
+                self.use_layers = false;
+            } else {
+                let new_source = !self.use_layers;
+                self.start_load(
+                    ClipSource::Layers(self.layers.clone(), self.layer_mode),
+                    new_source,
+                );
+            }
+        }
+    }
+
+    fn draw_chord_buttons(&mut self, ui: &mut egui::Ui) {
+        let flats = self.flats();
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.chord_mode,
+                strings::get(self.language, Text::ChordMode),
+            )
+            .on_hover_text(self.tr(Text::ChordModeHint));
+            ui.add_enabled_ui(self.chord_mode, |ui| {
+                ui.label(self.tr(Text::ChordRoot));
+                ui.add(
+                    egui::DragValue::new(&mut self.chord_root)
+                        .range(PIANO_START_MIDI..=PIANO_END_MIDI)
+                        .custom_formatter(|value, _| midi_note_name(value as i32, flats)),
+                );
+                let mut pressed = None;
+                for (number, chord) in ChordType::ALL.into_iter().enumerate() {
+                    let response = ui
+                        .selectable_label(
+                            self.chord_type == chord,
+                            strings::get(self.language, chord.label()),
+                        )
+                        .on_hover_text(strings::fill(self.tr(Text::PlayOnRoot), number + 1));
+                    if response.is_pointer_button_down_on() {
+                        pressed = Some(chord);
+                    }
+                }
+                self.set_chord_button(pressed);
+            });
+        });
+    }
+
+    fn set_chord_button(&mut self, pressed: Option<ChordType>) {
+        if pressed == self.chord_button {
+            return;
+        }
+        if self.chord_button.take().is_some() {
+            self.release_note(self.chord_root);
+        }
+        if let Some(chord) = pressed {
+            self.chord_type = chord;
+            self.try_play(self.chord_root, None);
+        }
+        self.chord_button = pressed;
+    }
+
+    fn detect_base_note(&mut self) {
+        let Some(sample) = &self.sample else {
+            return;
+        };
+        let Some(estimate) = pitch::detect(&sample.mono_samples(), sample.sample_rate) else {
+            self.set_status(self.tr(Text::PitchTooShort));
+            return;
+        };
+
+        let midi = estimate.midi_note();
+        if estimate.confidence < MIN_PITCH_CONFIDENCE || !(0..=127).contains(&midi) {
+            self.set_status(strings::fill_all(
+                self.tr(Text::PitchUnclear),
+                &[
+                    &format!("{:.1}", estimate.frequency),
+                    &format!("{:.0}", estimate.confidence * 100.0),
+                ],
+            ));
+            return;
+        }
+
+        self.base_midi = midi;
+        self.set_status(strings::fill_all(
+            self.tr(Text::PitchDetected),
+            &[
+                &format!("{:.1}", estimate.frequency),
+                &format!("{:.0}", estimate.confidence * 100.0),
+                &midi_note_name(midi, self.flats()),
+            ],
+        ));
+    }
+
+    // Every abrupt start or stop fades over the smoothing length so none of them click.
+    fn smooth_stop(&self, voice: &dsp::ReleaseHandle) {
+        voice.release(self.smoothing_ms, self.release_curve);
+    }
+
+    fn note_off_fade_ms(&self) -> u32 {
+        self.release_ms.max(self.smoothing_ms)
+    }
+
+    fn toggle_reference(&mut self) {
+        if let Some(reference) = self.reference.take() {
+            reference.release(self.note_off_fade_ms(), self.release_curve);
+            return;
+        }
+        match self.audio.play_reference(self.reference_hz) {
+            Ok(release) => self.reference = Some(release),
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::PlaybackError),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    fn set_status(&mut self, message: impl Into<String>) {
+        self.push_status(message.into(), false);
+    }
+
+    fn set_error(&mut self, message: impl Into<String>) {
+        self.push_status(message.into(), true);
+    }
+
+    // The newest message stays inline; older ones scroll back in the status log.
+    fn push_status(&mut self, message: String, error: bool) {
+        if self.status_log.len() == STATUS_LOG_LIMIT {
+            self.status_log.pop_front();
+        }
+        self.status_log.push_back(StatusEntry {
+            time: self.time,
+            message: message.clone(),
+            error,
+        });
+        self.status = message;
+        self.status_error = error;
+    }
+
+    fn draw_status(&mut self, ui: &mut egui::Ui) {
+        let color = if self.status_error {
+            Color32::LIGHT_RED
+        } else {
+            Color32::LIGHT_BLUE
+        };
+        ui.label(RichText::new(&self.status).color(color));
+        egui::CollapsingHeader::new(self.tr(Text::StatusLog)).show(ui, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(160.0)
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for entry in &self.status_log {
+                        let seconds = entry.time as u64;
+                        let text =
+                            format!("{:02}:{:02}  {}", seconds / 60, seconds % 60, entry.message);
+                        let color = if entry.error {
+                            Color32::LIGHT_RED
+                        } else {
+                            ui.visuals().text_color()
+                        };
+                        ui.label(RichText::new(text).color(color).monospace());
+                    }
+                });
+            if ui.small_button(self.tr(Text::Clear)).clicked() {
+                self.status_log.clear();
+            }
+        });
+    }
+
+    fn tr(&self, text: Text) -> &'static str {
+        strings::get(self.language, text)
+    }
+
+    fn toggle_sequencer(&mut self) {
+        self.sequencer_playing = !self.sequencer_playing;
+        self.sequencer_step = 0;
+        self.sequencer_next_at = self.time;
+        if !self.sequencer_playing {
+            self.release_sequencer_notes();
+        }
+    }
+
+    fn release_sequencer_notes(&mut self) {
+        for midi in std::mem::take(&mut self.sequencer_held) {
+            self.release_note(midi);
+        }
+    }
+
+    fn advance_sequencer(&mut self, ctx: &egui::Context) {
+        if !self.sequencer_playing {
+            return;
+        }
+        let step_seconds = Subdivision::Sixteenth.seconds(self.bpm);
+        if self.time >= self.sequencer_next_at {
+            // Each step cuts the previous one so frozen voices don't pile up.
+            self.release_sequencer_notes();
+            let notes: Vec<i32> = self
+                .sequencer
+                .iter()
+                .filter(|(_, steps)| steps[self.sequencer_step])
+                .map(|&(midi, _)| midi)
+                .collect();
+            for midi in notes {
+                self.try_play(midi, None);
+                self.sequencer_held.push(midi);
+            }
+            self.sequencer_step = (self.sequencer_step + 1) % SEQUENCER_STEPS;
+            self.sequencer_next_at += step_seconds;
+            // After a stall, resume from now rather than firing a burst of late steps.
+            if self.sequencer_next_at < self.time {
+                self.sequencer_next_at = self.time + step_seconds;
+            }
+        }
+        ctx.request_repaint_after(std::time::Duration::from_secs_f64(
+            (self.sequencer_next_at - self.time).max(0.0),
+        ));
+    }
+
+    fn draw_sequencer(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = self.tr(if self.sequencer_playing {
+                Text::Stop
+            } else {
+                Text::Play
+            });
+            if ui.button(label).clicked() {
+                self.toggle_sequencer();
+            }
+            if ui.button(self.tr(Text::AddRow)).clicked() {
+                let midi = self
+                    .sequencer
+                    .last()
+                    .map_or(self.base_midi, |&(midi, _)| midi);
+                self.sequencer.push((midi, [false; SEQUENCER_STEPS]));
+            }
+            if ui.button(self.tr(Text::Clear)).clicked() {
+                self.sequencer
+                    .iter_mut()
+                    .for_each(|(_, steps)| *steps = [false; SEQUENCER_STEPS]);
+            }
+            ui.weak(strings::fill(
+                self.tr(Text::SixteenthsAt),
+                format!("{:.0}", self.bpm),
+            ));
+        });
+
+        // The playhead marks the step that sounded last.
+        let playhead = self
+            .sequencer_playing
+            .then(|| (self.sequencer_step + SEQUENCER_STEPS - 1) % SEQUENCER_STEPS);
+        let flats = self.flats();
+        let mut remove = None;
+        for (index, (midi, steps)) in self.sequencer.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                if ui
+                    .small_button("✕")
+                    .on_hover_text(strings::get(self.language, Text::RemoveRow))
+                    .clicked()
+                {
+                    remove = Some(index);
+                }
+                ui.add(
+                    egui::DragValue::new(midi)
+                        .range(PIANO_START_MIDI..=PIANO_END_MIDI)
+                        .custom_formatter(|value, _| midi_note_name(value as i32, flats)),
+                );
+                for (step, active) in steps.iter_mut().enumerate() {
+                    let (rect, response) =
+                        ui.allocate_exact_size(Vec2::splat(18.0), Sense::click());
+                    if response.clicked() {
+                        *active = !*active;
+                    }
+                    let fill = match (*active, playhead == Some(step)) {
+                        (true, true) => Color32::from_rgb(255, 210, 120),
+                        (true, false) => Color32::from_rgb(230, 150, 40),
+                        (false, true) => Color32::from_gray(90),
+                        (false, false) if step % 4 == 0 => Color32::from_gray(55),
+                        (false, false) => Color32::from_gray(40),
+                    };
+                    ui.painter().rect_filled(rect.shrink(1.0), 2.0, fill);
+                }
+            });
+        }
+        if let Some(index) = remove {
+            self.sequencer.remove(index);
+        }
+    }
+
+    fn all_notes_off(&mut self) {
+        // Forget every held note too, so a later key-up can't release a phantom chord.
+        self.pending_notes.clear();
+        self.held_chords.clear();
+        self.key_down_at.clear();
+        self.mouse_note = None;
+        self.sequencer_held.clear();
+        self.preview = None;
+        self.scrub_player = None;
+        self.reference = None;
+        match self.audio.stop_all(self.smoothing_ms, self.release_curve) {
+            Ok(()) => self.set_status(self.tr(Text::AllNotesOffDone)),
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::PlaybackError),
+                format!("{err:#}"),
+            )),
+        }
+        if let Some(out) = &mut self.midi_out {
+            if let Err(err) = out.all_notes_off() {
+                self.set_error(strings::fill(
+                    self.tr(Text::MidiOutError),
+                    format!("{err:#}"),
+                ));
+            }
+        }
+    }
+
+    fn piano_keys(white_width: f32) -> Vec<PianoKey> {
+        let black_width = white_width * BLACK_KEY_WIDTH_RATIO;
+        let mut keys = Vec::new();
+        let mut white_index = 0;
+
+        for midi in PIANO_START_MIDI..=PIANO_END_MIDI {
+            if is_black_key(midi) {
+                let x = (white_index as f32 * white_width) - black_width * 0.5;
+                keys.push(PianoKey {
+                    midi,
+                    is_black: true,
+                    x,
+                    width: black_width,
+                });
+            } else {
+                let x = white_index as f32 * white_width;
+                keys.push(PianoKey {
+                    midi,
+                    is_black: false,
+                    x,
+                    width: white_width,
+                });
+                white_index += 1;
+            }
+        }
+
+        keys
+    }
+
+    fn flats(&self) -> bool {
+        match self.accidentals {
+            Accidentals::Sharps => false,
+            Accidentals::Flats => true,
+            Accidentals::FollowKey => self.scale.uses_flats(self.scale_root),
+        }
+    }
+
+    fn key_fill(&self, midi: i32, is_black: bool) -> Color32 {
+        let in_scale = self.scale.contains(self.scale_root, midi);
+        let is_root = in_scale && (midi - self.scale_root).rem_euclid(12) == 0;
+        match (is_black, in_scale, is_root) {
+            (false, true, true) => Color32::from_rgb(255, 196, 120),
+            (false, true, false) => Color32::from_rgb(255, 232, 176),
+            (false, false, _) => Color32::WHITE,
+            (true, true, true) => Color32::from_rgb(160, 90, 20),
+            (true, true, false) => Color32::from_rgb(110, 80, 30),
+            (true, false, _) => Color32::from_rgb(20, 20, 20),
+        }
+    }
+
+    fn draw_raw_import_dialog(&mut self, ctx: &egui::Context) {
+        let Some((path, format)) = &mut self.raw_import else {
+            return;
+        };
+        let mut open = true;
+        let mut import = false;
+        egui::Window::new(strings::get(self.language, Text::ImportRawTitle))
+            .open(&mut open)
+            .collapsible(false)
+            .show(ctx, |ui| {
+                ui.label(path.display().to_string());
+                egui::Grid::new("raw_pcm_format").show(ui, |ui| {
+                    ui.label(strings::get(self.language, Text::SampleRate));
+                    ui.add(
+                        egui::DragValue::new(&mut format.sample_rate)
+                            .range(1_000..=384_000)
+                            .suffix(" Hz"),
+                    );
+                    ui.end_row();
+                    ui.label(strings::get(self.language, Text::SampleFormat));
+                    egui::ComboBox::from_id_source("raw_encoding")
+                        .selected_text(strings::get(self.language, format.encoding.label()))
+                        .show_ui(ui, |ui| {
+                            for encoding in raw::RawEncoding::ALL {
+                                ui.selectable_value(
+                                    &mut format.encoding,
+                                    encoding,
+                                    strings::get(self.language, encoding.label()),
+                                );
+                            }
+                        });
+                    ui.end_row();
+                    ui.label(strings::get(self.language, Text::Channels));
+                    ui.add(egui::DragValue::new(&mut format.channels).range(1..=8));
+                    ui.end_row();
+                    ui.label(strings::get(self.language, Text::ByteOrder));
+                    ui.horizontal(|ui| {
+                        ui.radio_value(
+                            &mut format.big_endian,
+                            false,
+                            strings::get(self.language, Text::LittleEndian),
+                        );
+                        ui.radio_value(
+                            &mut format.big_endian,
+                            true,
+                            strings::get(self.language, Text::BigEndian),
+                        );
+                    });
+                    ui.end_row();
+                });
+                import = ui
+                    .button(strings::get(self.language, Text::Import))
+                    .clicked();
+            });
+        if import {
+            if let Some((path, format)) = self.raw_import.take() {
+                let new_source =
+                    self.selected_path.as_ref() != Some(&path) || self.raw_format != Some(format);
+                self.start_load(ClipSource::RawPcm(path, format), new_source);
+            }
+        } else if !open {
+            self.raw_import = None;
+        }
+    }
+
+    fn draw_track_picker(&mut self, ui: &mut egui::Ui) -> bool {
+        let Some(sample) = self.sample.as_ref().filter(|s| s.tracks.len() > 1) else {
+            return false;
+        };
+        let selected = sample.track_id;
+        let mut chosen = selected;
+        egui::ComboBox::from_label(self.tr(Text::AudioTrack))
+            .selected_text(
+                sample
+                    .tracks
+                    .iter()
+                    .find(|track| Some(track.id) == selected)
+                    .map_or_else(
+                        || self.tr(Text::DefaultTrack).to_string(),
+                        |track| track.label(self.language),
+                    ),
+            )
+            .show_ui(ui, |ui| {
+                for track in &sample.tracks {
+                    ui.selectable_value(&mut chosen, Some(track.id), track.label(self.language));
+                }
+            });
+        if chosen == selected {
+            return false;
+        }
+        self.track_id = chosen;
+        true
+    }
+
+    fn draw_preset_row(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button(self.tr(Text::SavePreset)).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter(self.tr(Text::PresetFileFilter), &[PRESET_EXTENSION])
+                    .set_file_name(format!("instrument.{PRESET_EXTENSION}"))
+                    .save_file()
+                {
+                    self.save_preset(path);
+                }
+            }
+            if ui.button(self.tr(Text::LoadPreset)).clicked() {
+                if let Some(path) = rfd::FileDialog::new()
+                    .add_filter(self.tr(Text::PresetFileFilter), &[PRESET_EXTENSION])
+                    .pick_file()
+                {
+                    self.load_preset(path);
+                }
+            }
+            if ui
+                .button(self.tr(Text::ExportInstrument))
+                .on_hover_text(self.tr(Text::ExportInstrumentHint))
+                .clicked()
+            {
+                if let Some(parent) = rfd::FileDialog::new().pick_folder() {
+                    self.export_instrument(&parent);
+                }
+            }
+            if ui
+                .add_enabled(
+                    self.sample.is_some() && self.pending_load.is_none(),
+                    egui::Button::new(self.tr(Text::ResampleToBite)),
+                )
+                .on_hover_text(self.tr(Text::ResampleToBiteHint))
+                .clicked()
+            {
+                self.resample_to_bite();
+            }
+            if self.bounce_original.is_some()
+                && ui
+                    .button(self.tr(Text::RestoreOriginal))
+                    .on_hover_text(self.tr(Text::RestoreOriginalHint))
+                    .clicked()
+            {
+                self.restore_original();
+            }
+
+            let mut selected = self.active_preset;
+            egui::ComboBox::from_label(self.tr(Text::Preset))
+                .selected_text(
+                    selected
+                        .and_then(|i| self.presets.get(i))
+                        .map(|p| preset_name(p))
+                        .unwrap_or_else(|| "—".to_string()),
+                )
+                .show_ui(ui, |ui| {
+                    for (index, path) in self.presets.iter().enumerate() {
+                        ui.selectable_value(&mut selected, Some(index), preset_name(path));
+                    }
+                });
+            if selected != self.active_preset {
+                if let Some(path) = selected.and_then(|i| self.presets.get(i)).cloned() {
+                    self.load_preset(path);
+                }
+            }
+        });
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.scratch_wav,
+                strings::get(self.language, Text::ScratchWav),
+            )
+            .on_hover_text(self.tr(Text::ScratchWavHint));
+            if self.scratch_wav {
+                let path = Self::scratch_wav_path().display().to_string();
+                ui.monospace(&path);
+                if ui.small_button(self.tr(Text::CopyPath)).clicked() {
+                    ui.ctx().copy_text(path);
+                }
+            }
+        });
+    }
+
+    fn scratch_wav_path() -> PathBuf {
+        std::env::temp_dir().join(SCRATCH_WAV_NAME)
+    }
+
+    // Mirrors the bite to a fixed file for external tools. It is written next to the
+    // target and renamed over it, so a watcher never reads a half-written file.
+    fn update_scratch_wav(&mut self, ctx: &egui::Context) {
+        let Some(sample) = self.sample.as_ref().filter(|_| self.scratch_wav) else {
+            self.scratch_written = None;
+            self.scratch_pending = None;
+            return;
+        };
+        if self
+            .scratch_written
+            .as_ref()
+            .is_some_and(|written| Arc::ptr_eq(written, &sample.samples))
+        {
+            self.scratch_pending = None;
+            return;
+        }
+        let changed_at = match &self.scratch_pending {
+            Some((pending, changed_at)) if Arc::ptr_eq(pending, &sample.samples) => *changed_at,
+            _ => {
+                self.scratch_pending = Some((Arc::clone(&sample.samples), self.time));
+                self.time
+            }
+        };
+        let wait = SCRATCH_WAV_DEBOUNCE_S - (self.time - changed_at);
+        if wait > 0.0 {
+            ctx.request_repaint_after(Duration::from_secs_f64(wait));
+            return;
+        }
+
+        let path = Self::scratch_wav_path();
+        let partial = path.with_extension("wav.part");
+        let result = render::write_wav(
+            &partial,
+            &sample.samples,
+            sample.channels,
+            sample.sample_rate,
+        )
+        .and_then(|()| {
+            std::fs::rename(&partial, &path)
+                .with_context(|| format!("failed to replace {}", path.display()))
+        });
+        self.scratch_written = Some(Arc::clone(&sample.samples));
+        self.scratch_pending = None;
+        if let Err(err) = result {
+            self.scratch_wav = false;
+            self.set_error(strings::fill(
+                self.tr(Text::ScratchWavOff),
+                format!("{err:#}"),
+            ));
+        }
+    }
+
+    fn draw_zones(&mut self, ui: &mut egui::Ui) {
+        if self.zones.is_empty() {
+            ui.weak(self.tr(Text::NoZones));
+        }
+        let flats = self.flats();
+        let note_formatter = |value: f64, _| midi_note_name(value as i32, flats);
+        let mut removed = None;
+        for (index, zone) in self.zones.iter_mut().enumerate() {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::DragValue::new(&mut zone.low)
+                        .range(0..=127)
+                        .custom_formatter(note_formatter),
+                );
+                ui.label(strings::get(self.language, Text::ZoneTo));
+                ui.add(
+                    egui::DragValue::new(&mut zone.high)
+                        .range(zone.low..=127)
+                        .custom_formatter(note_formatter),
+                );
+                egui::ComboBox::from_id_source(("zone_mode", index))
+                    .selected_text(strings::get(self.language, zone.play_mode.label()))
+                    .show_ui(ui, |ui| {
+                        for mode in PlayMode::ALL {
+                            ui.selectable_value(
+                                &mut zone.play_mode,
+                                mode,
+                                strings::get(self.language, mode.label()),
+                            );
+                        }
+                    });
+                if ui.small_button("✕").clicked() {
+                    removed = Some(index);
+                }
+            });
+            zone.high = zone.high.max(zone.low);
+        }
+        if let Some(index) = removed {
+            self.zones.remove(index);
+        }
+        if ui
+            .button(self.tr(Text::AddZone))
+            .on_hover_text(self.tr(Text::AddZoneHint))
+            .clicked()
+        {
+            self.zones.push(KeyZone {
+                low: PIANO_START_MIDI,
+                high: PIANO_END_MIDI,
+                play_mode: PlayMode::Gate,
+            });
+        }
+    }
+
+    fn draw_sound_controls(&mut self, ui: &mut egui::Ui) {
+        let stereo_changed = ui
+            .horizontal(|ui| {
+                let changed = ui
+                    .checkbox(
+                        &mut self.preserve_stereo,
+                        strings::get(self.language, Text::PreserveStereo),
+                    )
+                    .changed();
+                let dc_changed = ui
+                    .checkbox(
+                        &mut self.remove_dc,
+                        strings::get(self.language, Text::RemoveDc),
+                    )
+                    .on_hover_text(self.tr(Text::RemoveDcHint))
+                    .changed();
+                if let Some(offset) = self.removed_dc {
+                    ui.weak(format!("({offset:+.4})"));
+                }
+                let fill_changed = ui
+                    .checkbox(
+                        &mut self.fill_loop,
+                        strings::get(self.language, Text::FillLoop),
+                    )
+                    .on_hover_text(self.tr(Text::FillLoopHint))
+                    .changed();
+                ui.checkbox(
+                    &mut self.auto_level,
+                    strings::get(self.language, Text::AutoLevel),
+                )
+                .on_hover_text(self.tr(Text::AutoLevelHint));
+                ui.add_enabled(
+                    !self.auto_level,
+                    egui::Checkbox::new(
+                        &mut self.bite_gain_comp,
+                        strings::get(self.language, Text::BiteGainComp),
+                    ),
+                )
+                .on_hover_text(self.tr(Text::BiteGainCompHint));
+                ui.checkbox(
+                    &mut self.auto_preview,
+                    strings::get(self.language, Text::AutoPreview),
+                )
+                .on_hover_text(self.tr(Text::AutoPreviewHint));
+                changed | dc_changed | fill_changed | self.draw_track_picker(ui)
+            })
+            .inner;
+        let reverse_changed = ui
+            .horizontal(|ui| {
+                let toggled = ui
+                    .checkbox(
+                        &mut self.reverse_attack,
+                        strings::get(self.language, Text::ReverseAttack),
+                    )
+                    .on_hover_text(self.tr(Text::ReverseAttackHint))
+                    .changed();
+                let length = ui
+                    .add_enabled(
+                        self.reverse_attack,
+                        egui::Slider::new(&mut self.reverse_attack_ms, 10..=1_000)
+                            .suffix(" ms")
+                            .text(strings::get(self.language, Text::ReverseLength)),
+                    )
+                    .changed();
+                toggled || length
+            })
+            .inner;
+        let rate_changed = self.draw_rate_override(ui);
+        if stereo_changed || reverse_changed || rate_changed {
+            self.refresh_clip_for_duration();
+        }
+        self.draw_round_robin(ui);
+        self.draw_cue_slices(ui);
+        let flats = self.flats();
+
+        ui.horizontal(|ui| {
+            ui.label(self.tr(Text::BaseNote));
+            ui.add(
+                egui::DragValue::new(&mut self.base_midi)
+                    .range(0..=127)
+                    .custom_formatter(|value, _| {
+                        let midi = value as i32;
+                        format!("{} ({midi})", midi_note_name(midi, flats))
+                    }),
+            )
+            .on_hover_text(self.tr(Text::BaseNoteHint));
+            if ui
+                .button(self.tr(Text::DetectPitch))
+                .on_hover_text(self.tr(Text::DetectPitchHint))
+                .clicked()
+            {
+                self.detect_base_note();
+            }
+            let label = self.tr(if self.reference.is_some() {
+                Text::StopReference
+            } else {
+                Text::PlayReference
+            });
+            if ui
+                .button(label)
+                .on_hover_text(self.tr(Text::ReferenceHint))
+                .clicked()
+            {
+                self.toggle_reference();
+            }
+            let hz = ui.add(
+                egui::DragValue::new(&mut self.reference_hz)
+                    .range(415.0..=445.0)
+                    .speed(0.1)
+                    .prefix("A")
+                    .suffix(" Hz"),
+            );
+            if hz.changed() {
+                if let Some(reference) = self.reference.take() {
+                    self.smooth_stop(&reference);
+                    self.toggle_reference();
+                }
+            }
+            ui.add(
+                egui::Slider::new(&mut self.detune_cents, -100.0..=100.0)
+                    .suffix(" ct")
+                    .text(strings::get(self.language, Text::Detune)),
+            );
+            ui.add(
+                egui::Slider::new(&mut self.transpose, -MAX_TRANSPOSE..=MAX_TRANSPOSE)
+                    .suffix(" st")
+                    .text(strings::get(self.language, Text::Transpose)),
+            )
+            .on_hover_text(self.tr(Text::TransposeHint));
+            egui::ComboBox::from_label(self.tr(Text::Interpolation))
+                .selected_text(strings::get(self.language, self.interp.label()))
+                .show_ui(ui, |ui| {
+                    for quality in dsp::InterpQuality::ALL {
+                        ui.selectable_value(
+                            &mut self.interp,
+                            quality,
+                            strings::get(self.language, quality.label()),
+                        );
+                    }
+                })
+                .response
+                .on_hover_text(self.tr(Text::InterpolationHint));
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.performance_mode,
+                strings::get(self.language, Text::PerformanceMode),
+            )
+            .on_hover_text(self.tr(Text::PerformanceModeHint));
+            if let Some(pending) = &self.pending_cache {
+                ui.add(
+                    egui::ProgressBar::new(pending.progress() as f32 / pending.total as f32)
+                        .desired_width(160.0)
+                        .text(strings::fill_all(
+                            self.tr(Text::Caching),
+                            &[&pending.progress(), &pending.total],
+                        )),
+                );
+            } else if self.voice_cache.is_some() {
+                ui.weak(self.tr(Text::AllKeysCached));
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add_enabled(
+                !self.random_pan,
+                egui::Slider::new(&mut self.pan, -1.0..=1.0)
+                    .text(strings::get(self.language, Text::Pan)),
+            );
+            ui.checkbox(
+                &mut self.random_pan,
+                strings::get(self.language, Text::RandomPan),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            let stereo = self.sample.as_ref().is_some_and(|s| s.channels == 2);
+            ui.add_enabled(
+                stereo,
+                egui::Slider::new(&mut self.stereo_width, 0.0..=dsp::MAX_STEREO_WIDTH * 100.0)
+                    .suffix("%")
+                    .text(strings::get(self.language, Text::StereoWidth)),
+            )
+            .on_hover_text(self.tr(Text::StereoWidthHint))
+            .on_disabled_hover_text(self.tr(Text::StereoWidthDisabled));
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.hold_velocity,
+                strings::get(self.language, Text::HoldVelocity),
+            )
+            .on_hover_text(self.tr(Text::HoldVelocityHint));
+            if self.hold_velocity {
+                ui.label(strings::fill(
+                    self.tr(Text::NextVelocity),
+                    format!("{:.0}", self.next_velocity * 100.0),
+                ));
+            }
+            ui.checkbox(
+                &mut self.glissando,
+                strings::get(self.language, Text::Glissando),
+            )
+            .on_hover_text(self.tr(Text::GlissandoHint));
+            ui.checkbox(
+                &mut self.drag_bend,
+                strings::get(self.language, Text::DragBend),
+            )
+            .on_hover_text(self.tr(Text::DragBendHint));
+            ui.add_enabled(
+                self.drag_bend,
+                egui::Slider::new(&mut self.bend_range, 1.0..=MAX_BEND_RANGE)
+                    .step_by(1.0)
+                    .suffix(" st")
+                    .text(strings::get(self.language, Text::BendRange)),
+            );
+            if self.bend_semitones != 0.0 {
+                ui.strong(strings::fill(
+                    self.tr(Text::BendAmount),
+                    format!("{:+.2}", self.bend_semitones),
+                ));
+            }
+            ui.add(
+                egui::Slider::new(&mut self.key_hit_scale, 1.0..=MAX_KEY_HIT_SCALE)
+                    .max_decimals(2)
+                    .prefix("×")
+                    .text(strings::get(self.language, Text::BlackKeyArea)),
+            )
+            .on_hover_text(self.tr(Text::BlackKeyAreaHint));
+        });
+
+        ui.horizontal(|ui| {
+            let toggled = ui
+                .checkbox(
+                    &mut self.humanize,
+                    strings::get(self.language, Text::Humanize),
+                )
+                .on_hover_text(self.tr(Text::HumanizeHint))
+                .changed();
+            let mut reseed = toggled;
+            ui.add_enabled_ui(self.humanize, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.humanize_amount, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::Amount)),
+                );
+                let mut seeded = self.humanize_seed.is_some();
+                if ui.checkbox(&mut seeded, self.tr(Text::Seed)).changed() {
+                    self.humanize_seed = seeded.then_some(1);
+                    reseed = true;
+                }
+                if let Some(seed) = &mut self.humanize_seed {
+                    reseed |= ui.add(egui::DragValue::new(seed)).changed();
+                    reseed |= ui
+                        .button(self.tr(Text::Restart))
+                        .on_hover_text(self.tr(Text::RestartHumanizeHint))
+                        .clicked();
+                }
+            });
+            if reseed {
+                self.reseed_humanize();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            let changed = ui
+                .add(
+                    egui::Slider::new(&mut self.start_jitter_ms, 0..=MAX_START_JITTER_MS)
+                        .text(strings::get(self.language, Text::StartJitter))
+                        .suffix(" ms"),
+                )
+                .on_hover_text(self.tr(Text::StartJitterHint))
+                .changed();
+            let mut reseed = changed;
+            ui.add_enabled_ui(self.start_jitter_ms > 0, |ui| {
+                reseed |= ui
+                    .add(
+                        egui::DragValue::new(&mut self.start_jitter_seed)
+                            .prefix(strings::get(self.language, Text::SeedPrefix)),
+                    )
+                    .changed();
+                reseed |= ui
+                    .button(self.tr(Text::Restart))
+                    .on_hover_text(self.tr(Text::RestartJitterHint))
+                    .clicked();
+            });
+            if reseed {
+                self.start_jitter_rng = StdRng::seed_from_u64(self.start_jitter_seed);
+            }
+            if changed
+                && self.start_jitter_ms > 0
+                && self.full_clip.is_none()
+                && self.full_load.is_none()
+            {
+                self.reload_full_clip();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.quantize,
+                strings::get(self.language, Text::Quantize),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.bpm)
+                    .range(30.0..=300.0)
+                    .suffix(" BPM"),
+            );
+            egui::ComboBox::from_label(self.tr(Text::Grid))
+                .selected_text(self.subdivision.label())
+                .show_ui(ui, |ui| {
+                    for subdivision in Subdivision::ALL {
+                        ui.selectable_value(
+                            &mut self.subdivision,
+                            subdivision,
+                            subdivision.label(),
+                        );
+                    }
+                });
+        });
+
+        ui.horizontal(|ui| {
+            if ui
+                .button(self.tr(Text::Tap))
+                .on_hover_text(strings::fill(self.tr(Text::TapHint), self.tap_key.name()))
+                .clicked()
+            {
+                self.tap_tempo();
+            }
+            egui::ComboBox::from_id_source("tap_key")
+                .selected_text(strings::fill(self.tr(Text::TapKey), self.tap_key.name()))
+                .show_ui(ui, |ui| {
+                    for key in TAP_KEYS {
+                        ui.selectable_value(&mut self.tap_key, key, key.name());
+                    }
+                });
+            let tapping = self
+                .tap_times
+                .back()
+                .is_some_and(|&last| self.time - last <= TAP_RESET_S);
+            if tapping && self.tap_times.len() >= 2 {
+                ui.label(strings::fill_all(
+                    self.tr(Text::TapBpm),
+                    &[&format!("{:.1}", self.bpm), &self.tap_times.len()],
+                ));
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_millis(250));
+            } else if tapping {
+                ui.weak(self.tr(Text::KeepTapping));
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::Layers)).show(ui, |ui| self.draw_layers(ui));
+
+        egui::CollapsingHeader::new(self.tr(Text::Sequencer))
+            .show(ui, |ui| self.draw_sequencer(ui));
+
+        egui::CollapsingHeader::new(self.tr(Text::ScrubPreview))
+            .show(ui, |ui| self.draw_scrub_preview(ui));
+
+        egui::CollapsingHeader::new(self.tr(Text::Freeze)).show(ui, |ui| {
+            ui.checkbox(
+                &mut self.freeze,
+                strings::get(self.language, Text::FreezeHeld),
+            )
+            .on_hover_text(self.tr(Text::FreezeHeldHint));
+            ui.add_enabled_ui(self.freeze, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.grain_ms, 10.0..=250.0)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::GrainSize)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.freeze_position, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::PositionInBite)),
+                );
+            });
+        });
+
+        ui.horizontal(|ui| {
+            ui.checkbox(
+                &mut self.bypass_effects,
+                strings::get(self.language, Text::BypassEffects),
+            )
+            .on_hover_text(self.tr(Text::BypassEffectsHint));
+            if self.bypass_effects && self.bypass_gain != 1.0 {
+                ui.weak(strings::fill(
+                    self.tr(Text::DryMatch),
+                    format!("{:+.1}", 20.0 * self.bypass_gain.log10()),
+                ));
+            }
+            if let Some(snapshot) = self
+                .active_snapshot
+                .and_then(|index| self.effect_snapshots.get(index))
+            {
+                ui.strong(strings::fill(self.tr(Text::ActiveSnapshot), &snapshot.name));
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::EffectSnapshots))
+            .show(ui, |ui| self.draw_effect_snapshots(ui));
+
+        egui::CollapsingHeader::new(self.tr(Text::BitCrusher)).show(ui, |ui| {
+            ui.checkbox(
+                &mut self.crush,
+                strings::get(self.language, Text::CrushNotes),
+            )
+            .on_hover_text(self.tr(Text::CrushNotesHint));
+            ui.add_enabled_ui(self.crush, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.crush_bits, 1..=16)
+                        .text(strings::get(self.language, Text::BitDepth)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.crush_downsample, 1..=32)
+                        .prefix("÷")
+                        .text(strings::get(self.language, Text::Downsample)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.crush_mix, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::DryWet)),
+                );
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::Overdrive)).show(ui, |ui| {
+            ui.checkbox(
+                &mut self.overdrive,
+                strings::get(self.language, Text::OverdriveNotes),
+            )
+            .on_hover_text(self.tr(Text::OverdriveNotesHint));
+            ui.add_enabled_ui(self.overdrive, |ui| {
+                egui::ComboBox::from_label(self.tr(Text::Curve))
+                    .selected_text(strings::get(self.language, self.overdrive_curve.label()))
+                    .show_ui(ui, |ui| {
+                        for curve in dsp::DriveCurve::ALL {
+                            ui.selectable_value(
+                                &mut self.overdrive_curve,
+                                curve,
+                                strings::get(self.language, curve.label()),
+                            );
+                        }
+                    });
+                ui.add(
+                    egui::Slider::new(&mut self.overdrive_drive_db, 0.0..=MAX_DRIVE_DB)
+                        .suffix(" dB")
+                        .text(strings::get(self.language, Text::Drive)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.overdrive_level_db, -24.0..=6.0)
+                        .suffix(" dB")
+                        .text(strings::get(self.language, Text::OutputLevel)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.overdrive_mix, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::DryWet)),
+                );
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::Reverb)).show(ui, |ui| {
+            ui.checkbox(&mut self.reverb, strings::get(self.language, Text::Reverb))
+                .on_hover_text(self.tr(Text::ReverbHint));
+            ui.add_enabled_ui(self.reverb, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.reverb_room, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::RoomSize)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.reverb_damping, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::Damping)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.reverb_mix, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::DryWet)),
+                );
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::WahFilter)).show(ui, |ui| {
+            ui.checkbox(&mut self.filter, strings::get(self.language, Text::Enable));
+            ui.add_enabled_ui(self.filter, |ui| {
+                let mut cutoff = self.filter_cutoff;
+                let slider = ui.add(
+                    egui::Slider::new(
+                        &mut cutoff,
+                        dsp::MIN_FILTER_CUTOFF_HZ..=dsp::MAX_FILTER_CUTOFF_HZ,
+                    )
+                    .logarithmic(true)
+                    .suffix(" Hz")
+                    .text(self.tr(Text::Cutoff)),
+                );
+                if slider.changed() {
+                    self.set_filter_cutoff(cutoff);
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.filter_resonance, 0.5..=10.0)
+                        .text(strings::get(self.language, Text::Resonance)),
+                );
+                ui.horizontal(|ui| {
+                    let label = format!(
+                        "⏺ {}",
+                        self.tr(if self.filter_recording.is_some() {
+                            Text::Recording
+                        } else if self.filter_record_armed {
+                            Text::Armed
+                        } else {
+                            Text::Record
+                        })
+                    );
+                    ui.toggle_value(&mut self.filter_record_armed, label)
+                        .on_hover_text(self.tr(Text::RecordHint));
+                    ui.add_enabled(
+                        !self.filter_automation.is_empty(),
+                        egui::Checkbox::new(
+                            &mut self.filter_play_automation,
+                            strings::get(self.language, Text::PlayAutomation),
+                        ),
+                    );
+                    if ui
+                        .add_enabled(
+                            !self.filter_automation.is_empty(),
+                            egui::Button::new(self.tr(Text::Clear)),
+                        )
+                        .clicked()
+                    {
+                        self.filter_automation = Arc::new(Vec::new());
+                        self.filter_play_automation = false;
+                    }
+                    if let Some(&(end, _)) = self.filter_automation.last() {
+                        ui.weak(strings::fill_all(
+                            self.tr(Text::AutomationPoints),
+                            &[
+                                &self.filter_automation.len(),
+                                &format!("{:.1}", end / 1_000.0),
+                            ],
+                        ));
+                    }
+                });
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::AutoWah)).show(ui, |ui| {
+            ui.checkbox(
+                &mut self.auto_wah,
+                strings::get(self.language, Text::Enable),
+            )
+            .on_hover_text(self.tr(Text::AutoWahHint));
+            ui.add_enabled_ui(self.auto_wah, |ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_sensitivity, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::Sensitivity)),
+                );
+                let range = dsp::MIN_FILTER_CUTOFF_HZ..=dsp::MAX_FILTER_CUTOFF_HZ;
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_low_hz, range.clone())
+                        .logarithmic(true)
+                        .suffix(" Hz")
+                        .text(strings::get(self.language, Text::Low)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_high_hz, range)
+                        .logarithmic(true)
+                        .suffix(" Hz")
+                        .text(strings::get(self.language, Text::High)),
+                );
+                self.auto_wah_high_hz = self.auto_wah_high_hz.max(self.auto_wah_low_hz);
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_attack_ms, 1.0..=100.0)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::Attack)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_release_ms, 10.0..=1_000.0)
+                        .logarithmic(true)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::Release)),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.auto_wah_resonance, 0.5..=10.0)
+                        .text(strings::get(self.language, Text::Resonance)),
+                );
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::Tremolo)).show(ui, |ui| {
+            ui.checkbox(
+                &mut self.tremolo,
+                strings::get(self.language, Text::Tremolo),
+            );
+            ui.add_enabled_ui(self.tremolo, |ui| {
+                ui.horizontal(|ui| {
+                    for shape in dsp::LfoShape::ALL {
+                        ui.selectable_value(
+                            &mut self.tremolo_shape,
+                            shape,
+                            strings::get(self.language, shape.label()),
+                        );
+                    }
+                    ui.checkbox(
+                        &mut self.tremolo_sync,
+                        strings::get(self.language, Text::SyncToBpm),
+                    );
+                });
+                if self.tremolo_sync {
+                    egui::ComboBox::from_label(self.tr(Text::Rate))
+                        .selected_text(self.tremolo_subdivision.label())
+                        .show_ui(ui, |ui| {
+                            for subdivision in Subdivision::ALL {
+                                ui.selectable_value(
+                                    &mut self.tremolo_subdivision,
+                                    subdivision,
+                                    subdivision.label(),
+                                );
+                            }
+                        });
+                } else {
+                    ui.add(
+                        egui::Slider::new(&mut self.tremolo_rate, 0.1..=20.0)
+                            .logarithmic(true)
+                            .suffix(" Hz")
+                            .text(strings::get(self.language, Text::Rate)),
+                    );
+                }
+                ui.add(
+                    egui::Slider::new(&mut self.tremolo_depth, 0.0..=1.0)
+                        .text(strings::get(self.language, Text::Depth)),
+                );
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::NoteTrims)).show(ui, |ui| {
+            if self.note_trims.is_empty() {
+                ui.weak(self.tr(Text::TrimHint));
+            }
+            let notes: Vec<i32> = self.note_trims.keys().copied().collect();
+            let flats = self.flats();
+            for midi in notes {
+                ui.horizontal(|ui| trim_menu(ui, &mut self.note_trims, midi, flats, self.language));
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::KeyZones)).show(ui, |ui| {
+            self.draw_zones(ui);
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::Release)).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                ui.add(
+                    egui::Slider::new(&mut self.attack_ms, 0..=MAX_ATTACK_MS)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::AttackFade)),
+                )
+                .on_hover_text(self.tr(Text::AttackFadeHint));
+                ui.add(
+                    egui::Slider::new(&mut self.release_ms, 5..=2_000)
+                        .logarithmic(true)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::ReleaseTime)),
+                )
+                .on_hover_text(self.tr(Text::ReleaseTimeHint));
+                ui.add(
+                    egui::Slider::new(&mut self.smoothing_ms, 1..=MAX_SMOOTHING_MS)
+                        .suffix(" ms")
+                        .text(strings::get(self.language, Text::Smoothing)),
+                )
+                .on_hover_text(self.tr(Text::SmoothingHint));
+                egui::ComboBox::from_label(self.tr(Text::Curve))
+                    .selected_text(strings::get(self.language, self.release_curve.label()))
+                    .show_ui(ui, |ui| {
+                        for curve in dsp::ReleaseCurve::ALL {
+                            ui.selectable_value(
+                                &mut self.release_curve,
+                                curve,
+                                strings::get(self.language, curve.label()),
+                            );
+                        }
+                    });
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::AudioOutput)).show(ui, |ui| {
+            let mut latency = self.latency;
+            ui.horizontal(|ui| {
+                for mode in [LatencyMode::Stable, LatencyMode::Low] {
+                    ui.radio_value(
+                        &mut latency,
+                        mode,
+                        strings::get(self.language, mode.label()),
+                    );
+                }
+            })
+            .response
+            .on_hover_text(self.tr(Text::LatencyHint));
+            if latency != self.latency {
+                self.set_latency(latency);
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::OutputLimiter)).show(ui, |ui| {
+            let threshold = ui.add(
+                egui::Slider::new(&mut self.limiter_threshold_db, -24.0..=0.0)
+                    .suffix(" dBFS")
+                    .text(strings::get(self.language, Text::Threshold)),
+            );
+            let makeup = ui.add(
+                egui::Slider::new(&mut self.limiter_makeup_db, 0.0..=24.0)
+                    .suffix(" dB")
+                    .text(strings::get(self.language, Text::MakeupGain)),
+            );
+            if threshold.changed() || makeup.changed() {
+                self.apply_limiter();
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::MidiOutput)).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let mut changed = ui
+                    .checkbox(
+                        &mut self.midi_out_enabled,
+                        strings::get(self.language, Text::SendNotesTo),
+                    )
+                    .changed();
+                let selected = self.midi_out_port.clone();
+                egui::ComboBox::from_id_source("midi_out_port")
+                    .selected_text(selected.as_deref().unwrap_or(self.tr(Text::NoPort)))
+                    .show_ui(ui, |ui| {
+                        for port in &self.midi_out_ports {
+                            let chosen = selected.as_ref() == Some(port);
+                            if ui.selectable_label(chosen, port).clicked() && !chosen {
+                                self.midi_out_port = Some(port.clone());
+                                changed = true;
+                            }
+                        }
+                    });
+                if ui.button(self.tr(Text::Rescan)).clicked() {
+                    self.refresh_midi_ports();
+                }
+                if changed {
+                    self.connect_midi_out();
+                }
+            });
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::MidiInput)).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let mut changed = ui
+                    .checkbox(
+                        &mut self.midi_in_enabled,
+                        strings::get(self.language, Text::SwitchPresetsFrom),
+                    )
+                    .on_hover_text(self.tr(Text::SwitchPresetsHint))
+                    .changed();
+                let selected = self.midi_in_port.clone();
+                egui::ComboBox::from_id_source("midi_in_port")
+                    .selected_text(selected.as_deref().unwrap_or(self.tr(Text::NoPort)))
+                    .show_ui(ui, |ui| {
+                        for port in &self.midi_in_ports {
+                            let chosen = selected.as_ref() == Some(port);
+                            if ui.selectable_label(chosen, port).clicked() && !chosen {
+                                self.midi_in_port = Some(port.clone());
+                                changed = true;
+                            }
+                        }
+                    });
+                if ui.button(self.tr(Text::Rescan)).clicked() {
+                    self.refresh_midi_ports();
+                }
+                if changed {
+                    self.connect_midi_in();
+                }
+            });
+            for (index, path) in self.presets.iter().enumerate() {
+                ui.label(strings::fill_all(
+                    self.tr(Text::ProgramPreset),
+                    &[&(index + 1), &preset_name(path)],
+                ));
+            }
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::OscControl)).show(ui, |ui| {
+            ui.horizontal(|ui| {
+                let toggled = ui
+                    .checkbox(
+                        &mut self.osc_enabled,
+                        strings::get(self.language, Text::ListenUdp),
+                    )
+                    .changed();
+                ui.add_enabled(
+                    self.osc.is_none(),
+                    egui::DragValue::new(&mut self.osc_port).range(1024..=65535),
+                );
+                let network = ui
+                    .checkbox(
+                        &mut self.osc_network,
+                        strings::get(self.language, Text::AllowOtherDevices),
+                    )
+                    .on_hover_text(self.tr(Text::AllowOtherDevicesHint))
+                    .changed();
+                if toggled || (network && self.osc_enabled) {
+                    self.start_osc();
+                }
+                match &self.osc {
+                    Some(server) => {
+                        ui.label(strings::fill(self.tr(Text::ListeningOn), server.address))
+                    }
+                    None => ui.weak(self.tr(Text::Stopped)),
+                };
+            });
+            ui.weak("/note <midi> [velocity 0–1] · /param/bite|pan|detune|base|bpm <value>");
+        });
+
+        egui::CollapsingHeader::new(self.tr(Text::TestTone)).show(ui, |ui| {
+            let before = self.tone;
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_label(self.tr(Text::Waveform))
+                    .selected_text(strings::get(self.language, self.tone.waveform.label()))
+                    .show_ui(ui, |ui| {
+                        for waveform in ToneWaveform::ALL {
+                            ui.selectable_value(
+                                &mut self.tone.waveform,
+                                waveform,
+                                strings::get(self.language, waveform.label()),
+                            );
+                        }
+                    });
+                if ui.button(self.tr(Text::DefaultPreset)).clicked() {
+                    self.tone = ToneSettings::default();
+                }
+            });
+            ui.add(
+                egui::Slider::new(&mut self.tone.frequency, 55.0..=1_760.0)
+                    .logarithmic(true)
+                    .suffix(" Hz")
+                    .text(strings::get(self.language, Text::BaseFrequency)),
+            );
+            ui.add_enabled(
+                matches!(
+                    self.tone.waveform,
+                    ToneWaveform::Square | ToneWaveform::Saw | ToneWaveform::Triangle
+                ),
+                egui::Slider::new(&mut self.tone.harmonics, 1..=64)
+                    .text(strings::get(self.language, Text::Harmonics)),
+            );
+            if !self.using_test_tone() {
+                ui.label(self.tr(Text::ToneChangesHint));
+            } else if self.tone != before {
+                self.refresh_clip_for_duration();
+            }
+        });
+    }
+
+    fn draw_rate_override(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let mut enabled = self.rate_override.is_some();
+            let toggled = ui
+                .checkbox(&mut enabled, self.tr(Text::OverrideRate))
+                .on_hover_text(self.tr(Text::OverrideRateHint))
+                .changed();
+            if toggled {
+                // Seed the entry with the detected rate so nothing changes until it is edited.
+                let detected = self.sample.as_ref().map_or(44_100, |s| s.sample_rate);
+                self.rate_override = enabled.then_some(detected);
+            }
+            let edited = match self.rate_override.as_mut() {
+                Some(rate) => {
+                    let response = ui.add(
+                        egui::DragValue::new(rate)
+                            .range(MIN_RATE_OVERRIDE_HZ..=MAX_RATE_OVERRIDE_HZ)
+                            .speed(100.0)
+                            .suffix(" Hz"),
+                    );
+                    response.drag_stopped() || (response.changed() && !response.dragged())
+                }
+                None => false,
+            };
+            toggled || edited
+        })
+        .inner
+    }
+
+    fn draw_waveform(&mut self, ui: &mut egui::Ui) {
+        let size = Vec2::new(ui.available_width(), 72.0);
+        let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, Color32::from_gray(24));
+
+        if let Some(sample) = &self.sample {
+            let channels = usize::from(sample.channels.max(1));
+            let frames = sample.frames();
+            let (first, visible) = self.waveform_view(frames);
+            let columns = rect.width().max(1.0) as usize;
+            let mid = rect.center().y;
+            let half_height = rect.height() * 0.5 - 2.0;
+            for column in 0..columns {
+                let start = first + column * visible / columns;
+                let end = (first + (column + 1) * visible / columns)
+                    .max(start + 1)
+                    .min(frames);
+                if start >= end {
+                    continue;
+                }
+                let (low, high) = sample.samples[start * channels..end * channels]
+                    .iter()
+                    .fold((0.0f32, 0.0f32), |(low, high), &s| {
+                        (low.min(s), high.max(s))
+                    });
+                let x = rect.left() + column as f32 + 0.5;
+                painter.line_segment(
+                    [
+                        Pos2::new(x, mid - high.clamp(-1.0, 1.0) * half_height),
+                        Pos2::new(x, mid - low.clamp(-1.0, 1.0) * half_height + 1.0),
+                    ],
+                    Stroke::new(1.0, Color32::from_rgb(120, 200, 255)),
+                );
+            }
+        }
+        self.draw_transient_markers(ui, rect);
+        self.draw_loop_region(ui, rect);
+        self.waveform_rect = Some(rect);
+        if let Some(capture) = self.waveform_capture.take() {
+            painter.text(
+                rect.left_top() + Vec2::new(6.0, 4.0),
+                egui::Align2::LEFT_TOP,
+                self.waveform_caption(),
+                FontId::proportional(12.0),
+                Color32::WHITE,
+            );
+            self.waveform_capture = Some(match capture {
+                WaveformCapture::Armed(path) => {
+                    ui.ctx()
+                        .send_viewport_cmd(egui::ViewportCommand::Screenshot);
+                    WaveformCapture::Requested(path, self.time)
+                }
+                requested => requested,
+            });
+        }
+        self.draw_waveform_scrollbar(ui);
+
+        if !response.hovered() {
+            return;
+        }
+        let (delta, fine) = ui.input(|i| {
+            (
+                i.raw_scroll_delta.x + i.raw_scroll_delta.y,
+                i.modifiers.shift,
+            )
+        });
+        let response =
+            response.on_hover_text(strings::fill(self.tr(Text::WaveformHint), self.bite_ms));
+        if delta != 0.0 {
+            let ms_per_point = if fine { 0.2 } else { 2.0 };
+            let step = (delta * ms_per_point).round() as i64;
+            let bite_ms = (i64::from(self.bite_ms) + step)
+                .clamp(i64::from(MIN_BITE_MS), i64::from(MAX_BITE_MS))
+                as u32;
+            if bite_ms != self.bite_ms {
+                self.bite_ms = bite_ms;
+                self.commit_bite();
+                self.refresh_clip_for_duration();
+            }
+            response.ctx.request_repaint();
+        }
+    }
+
+    fn waveform_caption(&self) -> String {
+        let name = self
+            .current_source()
+            .map_or(self.tr(Text::TestToneName).to_string(), |source| {
+                source.name()
+            });
+        let Some(sample) = &self.sample else {
+            return name;
+        };
+        let start_ms = self.bite_start as f64 * 1_000.0 / f64::from(sample.sample_rate.max(1));
+        let channels = self.tr(if sample.channels == 2 {
+            Text::Stereo
+        } else {
+            Text::Mono
+        });
+        strings::fill_all(
+            self.tr(Text::WaveformCaption),
+            &[
+                &name,
+                &self.bite_ms,
+                &format!("{start_ms:.1}"),
+                &midi_note_name(self.base_midi, self.flats()),
+                &sample.sample_rate,
+                &channels,
+            ],
+        )
+    }
+
+    fn export_waveform_png(&mut self, ctx: &egui::Context) {
+        let name = self
+            .current_source()
+            .map_or("test-tone".to_string(), |source| source.name());
+        let stem = Path::new(&name)
+            .file_stem()
+            .map_or(name.clone(), |stem| stem.to_string_lossy().into_owned());
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter(self.tr(Text::PngImage), &["png"])
+            .set_file_name(format!("{stem}-waveform.png"))
+            .save_file()
+        {
+            self.waveform_capture = Some(WaveformCapture::Armed(path));
+            ctx.request_repaint();
+        }
+    }
+
+    fn poll_waveform_capture(&mut self, ctx: &egui::Context) {
+        let Some(&WaveformCapture::Requested(_, requested)) = self.waveform_capture.as_ref() else {
+            return;
+        };
+        let Some(image) = ctx.input(|i| {
+            i.events.iter().find_map(|event| match event {
+                egui::Event::Screenshot { image, .. } => Some(Arc::clone(image)),
+                _ => None,
+            })
+        }) else {
+            if self.time - requested > SCREENSHOT_TIMEOUT_S {
+                self.waveform_capture = None;
+                self.set_error(strings::fill(
+                    self.tr(Text::WaveformImageFailed),
+                    self.tr(Text::NoScreenshot),
+                ));
+            } else {
+                ctx.request_repaint();
+            }
+            return;
+        };
+        let (Some(WaveformCapture::Requested(path, _)), Some(rect)) =
+            (self.waveform_capture.take(), self.waveform_rect)
+        else {
+            return;
+        };
+        match screenshot::save_region(&image, rect, ctx.pixels_per_point(), &path) {
+            Ok(()) => self.set_status(strings::fill(
+                self.tr(Text::WaveformImageSaved),
+                path.display(),
+            )),
+            Err(err) => self.set_error(strings::fill(
+                self.tr(Text::WaveformImageFailed),
+                format!("{err:#}"),
+            )),
+        }
+    }
+
+    // First frame and frame count of the zoomed waveform window.
+    fn waveform_view(&self, frames: usize) -> (usize, usize) {
+        let visible =
+            ((frames as f32 / self.waveform_zoom).ceil() as usize).clamp(1, frames.max(1));
+        let first = (self.waveform_scroll * frames.saturating_sub(visible) as f32).round() as usize;
+        (first, visible)
+    }
+
+    fn draw_waveform_scrollbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(
+                    self.waveform_capture.is_none(),
+                    egui::Button::new(self.tr(Text::SavePng)),
+                )
+                .on_hover_text(self.tr(Text::SavePngHint))
+                .clicked()
+            {
+                self.export_waveform_png(ui.ctx());
+            }
+            ui.label(self.tr(Text::Zoom));
+            ui.add(
+                egui::Slider::new(&mut self.waveform_zoom, 1.0..=MAX_WAVEFORM_ZOOM)
+                    .logarithmic(true)
+                    .suffix("×"),
+            )
+            .on_hover_text(self.tr(Text::ZoomHint));
+
+            let (track, response) = ui.allocate_exact_size(
+                Vec2::new(ui.available_width(), 10.0),
+                Sense::click_and_drag(),
+            );
+            let thumb_width = (track.width() / self.waveform_zoom).max(8.0);
+            let travel = (track.width() - thumb_width).max(0.0);
+            if let Some(pointer) = response.interact_pointer_pos() {
+                if travel > 0.0 {
+                    let left = pointer.x - track.left() - thumb_width * 0.5;
+                    self.waveform_scroll = (left / travel).clamp(0.0, 1.0);
+                }
+            }
+            let left = track.left() + self.waveform_scroll * travel;
+            let painter = ui.painter_at(track);
+            painter.rect_filled(track, 3.0, Color32::from_gray(40));
+            painter.rect_filled(
+                Rect::from_x_y_ranges(left..=left + thumb_width, track.y_range()),
+                3.0,
+                if response.hovered() || response.dragged() {
+                    Color32::from_gray(150)
+                } else {
+                    Color32::from_gray(110)
+                },
+            );
+        });
+    }
+
+    fn zoom_waveform(&mut self, factor: f32) {
+        self.waveform_zoom = (self.waveform_zoom * factor).clamp(1.0, MAX_WAVEFORM_ZOOM);
+    }
+
+    // Onsets are found in the full file, so shift them by the bite start onto the bite.
+    fn draw_transient_markers(&self, ui: &egui::Ui, rect: Rect) {
+        let (Some(onsets), Some(sample)) = (&self.transient_frames, &self.sample) else {
+            return;
+        };
+        let frames = sample.frames().max(1);
+        let (first, visible) = self.waveform_view(frames);
+        let painter = ui.painter_at(rect);
+        for &onset in onsets {
+            let Some(frame) = onset.checked_sub(self.bite_start) else {
+                continue;
+            };
+            if !(first..first + visible).contains(&frame) {
+                continue;
+            }
+            let x = rect.left() + (frame - first) as f32 / visible as f32 * rect.width();
+            painter.line_segment(
+                [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+                Stroke::new(1.0, Color32::from_rgb(240, 90, 90)),
+            );
+        }
+    }
+
+    fn draw_loop_region(&mut self, ui: &mut egui::Ui, rect: Rect) {
+        let Some(sample) = &self.sample else {
+            return;
+        };
+        let Some((start, end)) = sample.loop_frames else {
+            return;
+        };
+        let frames = sample.frames().max(1);
+        let (first, visible) = self.waveform_view(frames);
+        let frame_to_x = |frame: usize| {
+            rect.left() + (frame as f32 - first as f32) / visible as f32 * rect.width()
+        };
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(
+            Rect::from_x_y_ranges(frame_to_x(start)..=frame_to_x(end), rect.y_range()),
+            0.0,
+            Color32::from_rgba_unmultiplied(255, 200, 80, 40),
+        );
+
+        let mut dragged = None;
+        for (index, frame) in [start, end].into_iter().enumerate() {
+            let x = frame_to_x(frame);
+            if !rect.x_range().contains(x) {
+                continue;
+            }
+            let handle = Rect::from_x_y_ranges(x - 4.0..=x + 4.0, rect.y_range());
+            let response = ui
+                .interact(handle, egui::Id::new(("loop_handle", index)), Sense::drag())
+                .on_hover_cursor(egui::CursorIcon::ResizeHorizontal);
+            let color = if response.hovered() || response.dragged() {
+                Color32::from_rgb(255, 230, 120)
+            } else {
+                Color32::from_rgb(255, 180, 40)
+            };
+            painter.line_segment(
+                [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+                Stroke::new(2.0, color),
+            );
+            if let Some(pointer) = response
+                .interact_pointer_pos()
+                .filter(|_| response.dragged())
+            {
+                // Map through the zoomed window so the handle lands on the absolute frame.
+                let t = ((pointer.x - rect.left()) / rect.width()).clamp(0.0, 1.0);
+                dragged = Some((index, first + (t * visible as f32) as usize));
+            }
+        }
+
+        let Some((index, mut frame)) = dragged else {
+            return;
+        };
+        if self.snap_zero {
+            let radius = clip::frames_for_ms(sample.sample_rate, ZERO_SNAP_RADIUS_MS);
+            frame = sample.nearest_zero_crossing(frame, radius, true);
+        }
+        let updated = if index == 0 {
+            (frame.min(end.saturating_sub(MIN_LOOP_FRAMES)), end)
+        } else {
+            (start, frame.max(start + MIN_LOOP_FRAMES).min(frames))
+        };
+        if let Some(sample) = &mut self.sample {
+            sample.loop_frames = Some(updated);
+        }
+    }
+
+    fn draw_loop_controls(&mut self, ui: &mut egui::Ui) {
+        let Some(sample) = &mut self.sample else {
+            return;
+        };
+        let snap_changed = ui
+            .horizontal(|ui| {
+                match sample.loop_frames {
+                    Some((start, end)) => {
+                        let ms = (end - start) as f64 * 1_000.0 / f64::from(sample.sample_rate);
+                        ui.label(strings::fill_all(
+                            strings::get(self.language, Text::LoopLabel),
+                            &[&format!("{ms:.1}"), &start, &end],
+                        ));
+                        if ui
+                            .button(strings::get(self.language, Text::ClearLoop))
+                            .clicked()
+                        {
+                            sample.loop_frames = None;
+                        }
+                    }
+                    None => {
+                        if ui
+                            .button(strings::get(self.language, Text::AddLoop))
+                            .clicked()
+                        {
+                            let frames = sample.frames();
+                            if frames > MIN_LOOP_FRAMES * 2 {
+                                sample.loop_frames = Some((frames / 4, frames * 3 / 4));
+                            }
+                        }
+                    }
+                }
+                ui.checkbox(
+                    &mut self.snap_zero,
+                    strings::get(self.language, Text::SnapZero),
+                )
+                .on_hover_text(strings::get(self.language, Text::SnapZeroHint))
+                .changed()
+            })
+            .inner;
+        if snap_changed {
+            self.refresh_clip_for_duration();
+        }
+    }
+
+    fn update_meter(&mut self) {
+        let elapsed = (self.time - self.meter_time).clamp(0.0, 1.0) as f32;
+        self.meter_time = self.time;
+        let peaks = self.audio.meter.take_peaks();
+        for (channel, peak) in peaks.into_iter().enumerate() {
+            let db = (20.0 * peak.max(1e-6).log10()).max(METER_FLOOR_DB);
+            let fallen = self.meter_levels[channel] - METER_FALL_DB_PER_S * elapsed;
+            self.meter_levels[channel] = db.max(fallen).max(METER_FLOOR_DB);
+
+            let (held, since) = self.meter_peaks[channel];
+            if db >= held {
+                self.meter_peaks[channel] = (db, self.time);
+            } else if self.time - since > PEAK_HOLD_S {
+                let decayed = held - METER_FALL_DB_PER_S * elapsed;
+                self.meter_peaks[channel] = (decayed.max(db), since);
+            }
+        }
+    }
+
+    fn draw_scope(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            let label = self.tr(if self.scope_frozen.is_some() {
+                Text::Unfreeze
+            } else {
+                Text::FreezeScope
+            });
+            if ui.button(label).clicked() {
+                self.scope_frozen = match self.scope_frozen {
+                    Some(_) => None,
+                    None => Some(self.audio.scope.snapshot()),
+                };
+            }
+        });
+        let samples = match &self.scope_frozen {
+            Some(frozen) => frozen.clone(),
+            None => {
+                ui.ctx().request_repaint();
+                self.audio.scope.snapshot()
+            }
+        };
+
+        let size = Vec2::new(ui.available_width(), 96.0);
+        let (rect, _) = ui.allocate_exact_size(size, Sense::hover());
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 2.0, Color32::from_gray(16));
+        painter.line_segment(
+            [rect.left_center(), rect.right_center()],
+            Stroke::new(1.0, Color32::from_gray(48)),
+        );
+        // Start on a rising zero crossing so a steady tone holds still between frames.
+        let window = samples.len() / 2;
+        let start = (1..window)
+            .find(|&i| samples[i - 1] <= 0.0 && samples[i] > 0.0)
+            .unwrap_or(0);
+        let points: Vec<Pos2> = samples[start..start + window]
+            .iter()
+            .enumerate()
+            .map(|(i, &sample)| {
+                Pos2::new(
+                    rect.left() + i as f32 / window as f32 * rect.width(),
+                    rect.center().y - sample.clamp(-1.0, 1.0) * rect.height() * 0.5,
+                )
+            })
+            .collect();
+        painter.add(egui::Shape::line(
+            points,
+            Stroke::new(1.0, Color32::from_rgb(120, 220, 140)),
+        ));
+    }
+
+    fn voice_limit_warning(&self) -> Option<Text> {
+        let effects = !self.bypass_effects
+            && (self.crush
+                || self.overdrive
+                || self.reverb
+                || self.tremolo
+                || self.filter
+                || self.auto_wah);
+        if effects && self.max_voices > SAFE_VOICES_WITH_EFFECTS {
+            Some(Text::HighCpuEffects)
+        } else if self.max_voices > SAFE_VOICES {
+            Some(Text::HighCpu)
+        } else {
+            None
+        }
+    }
+
+    fn draw_voice_count(&mut self, ui: &mut egui::Ui) {
+        let mut held = std::mem::take(&mut self.held_voices);
+        if let Ok(releasing) = self.audio.reap_voices(&mut held) {
+            self.draw_voice_summary(ui, &held, releasing);
+        }
+        self.held_voices = held;
+    }
+
+    fn draw_voice_summary(
+        &mut self,
+        ui: &mut egui::Ui,
+        held: &[(i32, Duration)],
+        releasing: usize,
+    ) {
+        if self.audio.take_stolen() > 0 {
+            self.voice_steal_flash = self.time;
+        }
+        let stealing = self.time - self.voice_steal_flash < VOICE_STEAL_FLASH_S;
+        ui.horizontal(|ui| {
+            let count =
+                strings::fill_all(self.tr(Text::VoiceCount), &[&held.len(), &self.max_voices]);
+            let label = if stealing {
+                RichText::new(format!("{count}  {}", self.tr(Text::VoiceStolen)))
+                    .color(Color32::from_rgb(255, 170, 60))
+                    .strong()
+            } else {
+                RichText::new(count)
+            };
+            ui.label(label).on_hover_ui(|ui| {
+                if held.is_empty() {
+                    ui.label(self.tr(Text::NoHeldVoices));
+                }
+                for &(midi, age) in held {
+                    let name = midi_note_name(midi, self.flats());
+                    ui.label(format!("{name}  {:.1} s", age.as_secs_f32()));
+                }
+            });
+            if releasing > 0 {
+                ui.weak(strings::fill(self.tr(Text::Releasing), releasing));
+            }
+            ui.add(
+                egui::DragValue::new(&mut self.max_voices)
+                    .range(1..=MAX_VOICE_LIMIT)
+                    .prefix(strings::get(self.language, Text::MaxVoices)),
+            )
+            .on_hover_text(self.tr(Text::MaxVoicesHint));
+            if let Some(warning) = self.voice_limit_warning() {
+                ui.colored_label(Color32::from_rgb(255, 170, 60), self.tr(warning));
+            }
+            if self.transpose != 0 {
+                ui.strong(strings::fill(
+                    self.tr(Text::TransposeAmount),
+                    format!("{:+}", self.transpose),
+                ));
+            }
+        });
+        if stealing || releasing > 0 || !held.is_empty() {
+            ui.ctx().request_repaint_after(Duration::from_millis(100));
+        }
+    }
+
+    fn draw_meter(&mut self, ui: &mut egui::Ui) {
+        let rows: Vec<(&str, f32, f32)> = if self.meter_stereo {
+            vec![
+                ("L", self.meter_levels[0], self.meter_peaks[0].0),
+                ("R", self.meter_levels[1], self.meter_peaks[1].0),
+            ]
+        } else {
+            vec![(
+                "M",
+                self.meter_levels[0].max(self.meter_levels[1]),
+                self.meter_peaks[0].0.max(self.meter_peaks[1].0),
+            )]
+        };
+        let mut reset = false;
+        ui.horizontal(|ui| {
+            ui.vertical(|ui| {
+                for (label, level, peak) in rows {
+                    ui.horizontal(|ui| {
+                        ui.label(label);
+                        let (rect, response) =
+                            ui.allocate_exact_size(Vec2::new(240.0, 10.0), Sense::click());
+                        let to_x = |db: f32| {
+                            rect.left() + (1.0 - db / METER_FLOOR_DB).clamp(0.0, 1.0) * rect.width()
+                        };
+                        let painter = ui.painter_at(rect);
+                        painter.rect_filled(rect, 1.0, Color32::from_gray(30));
+                        let color = if level > -1.0 {
+                            Color32::RED
+                        } else if level > -12.0 {
+                            Color32::YELLOW
+                        } else {
+                            Color32::GREEN
+                        };
+                        painter.rect_filled(
+                            Rect::from_x_y_ranges(rect.left()..=to_x(level), rect.y_range()),
+                            1.0,
+                            color,
+                        );
+                        let x = to_x(peak);
+                        painter.line_segment(
+                            [Pos2::new(x, rect.top()), Pos2::new(x, rect.bottom())],
+                            Stroke::new(2.0, Color32::WHITE),
+                        );
+                        if response
+                            .on_hover_text(strings::fill(
+                                strings::get(self.language, Text::PeakHint),
+                                format!("{peak:.1}"),
+                            ))
+                            .clicked()
+                        {
+                            reset = true;
+                        }
+                    });
+                }
+            });
+            ui.checkbox(
+                &mut self.meter_stereo,
+                strings::get(self.language, Text::StereoMeter),
+            );
+        });
+        if reset {
+            self.meter_peaks = [(METER_FLOOR_DB, self.time); 2];
+        }
+        if self.meter_levels.iter().any(|&db| db > METER_FLOOR_DB)
+            || self.meter_peaks.iter().any(|&(db, _)| db > METER_FLOOR_DB)
+        {
+            ui.ctx()
+                .request_repaint_after(std::time::Duration::from_millis(33));
+        }
+    }
+
+    fn draw_piano(&mut self, ui: &mut egui::Ui) {
+        // Keys stretch to fill the panel, within bounds that keep them playable.
+        let white_count = (PIANO_START_MIDI..=PIANO_END_MIDI)
+            .filter(|&midi| !is_black_key(midi))
+            .count();
+        let white_width = (ui.available_width() / white_count as f32)
+            .clamp(MIN_WHITE_KEY_WIDTH, MAX_WHITE_KEY_WIDTH)
+            .floor();
+        let keys = Self::piano_keys(white_width);
+        let flats = self.flats();
+        let white_height = white_width * WHITE_KEY_ASPECT;
+        let black_height = white_height * BLACK_KEY_HEIGHT_RATIO;
+        // Labels shrink with the keys; once they would collide only the Cs keep theirs.
+        let label_size = (white_width * KEY_LABEL_FONT_RATIO)
+            .clamp(MIN_KEY_LABEL_FONT, MAX_KEY_LABEL_FONT)
+            .round();
+        let landmarks_only = white_width < LANDMARK_LABELS_BELOW_WIDTH;
+        let total_width = keys
+            .iter()
+            .filter(|k| !k.is_black)
+            .map(|k| k.width)
+            .sum::<f32>();
+
+        let (rect, _) =
+            ui.allocate_exact_size(Vec2::new(total_width, white_height), Sense::hover());
+        let painter = ui.painter_at(rect);
+        let primary_down = ui.input(|i| i.pointer.primary_down());
+        let mut pressed = None;
+        let mut hit_rects = Vec::with_capacity(keys.len());
 
         for key in keys.iter().filter(|k| !k.is_black) {
             let key_rect = Rect::from_min_size(
                 Pos2::new(rect.left() + key.x, rect.top()),
                 Vec2::new(key.width, white_height),
             );
+            hit_rects.push((key_rect, key.midi));
             let response =
                 ui.interact(key_rect, egui::Id::new(("white", key.midi)), Sense::click());
-            painter.rect_filled(key_rect, 0.0, Color32::WHITE);
+            if response.is_pointer_button_down_on() && primary_down {
+                pressed = Some(key.midi);
+            }
+            response.context_menu(|ui| {
+                trim_menu(ui, &mut self.note_trims, key.midi, flats, self.language)
+            });
+            painter.rect_filled(key_rect, 0.0, self.key_fill(key.midi, false));
             painter.rect_stroke(key_rect, 0.0, Stroke::new(1.0, Color32::BLACK));
-            painter.text(
-                key_rect.center_bottom() + Vec2::new(0.0, -8.0),
-                egui::Align2::CENTER_BOTTOM,
-                midi_note_name(key.midi),
-                FontId::proportional(12.0),
-                Color32::BLACK,
-            );
-            if response.clicked() {
-                self.try_play(key.midi);
+            if !landmarks_only || key.midi.rem_euclid(12) == 0 {
+                painter.text(
+                    key_rect.center_bottom() + Vec2::new(0.0, -8.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    self.note_labels.key_label(
+                        key.midi,
+                        false,
+                        self.note_naming,
+                        self.scale_root,
+                        flats,
+                    ),
+                    FontId::proportional(label_size),
+                    Color32::BLACK,
+                );
             }
+            draw_shortcut_badge(
+                &painter,
+                key_rect.center_bottom() + Vec2::new(0.0, -30.0),
+                key.midi,
+            );
         }
 
         for key in keys.iter().filter(|k| k.is_black) {
@@ -326,112 +5853,735 @@ impl SamplePianoApp {
                 Pos2::new(rect.left() + key.x, rect.top()),
                 Vec2::new(key.width, black_height),
             );
+            // The touch area grows around the drawn key, but never past a white key's
+            // width, so neighbouring black keys can't overlap each other.
+            let hit_width = (key.width * self.key_hit_scale).min(white_width);
+            let hit_rect = Rect::from_min_size(
+                Pos2::new(key_rect.center().x - hit_width * 0.5, rect.top()),
+                Vec2::new(
+                    hit_width,
+                    (black_height * self.key_hit_scale).min(white_height),
+                ),
+            );
+            // Black keys sit on top, so they win the hit test where they overlap white keys.
+            hit_rects.insert(0, (hit_rect, key.midi));
             let response =
-                ui.interact(key_rect, egui::Id::new(("black", key.midi)), Sense::click());
-            painter.rect_filled(key_rect, 2.0, Color32::from_rgb(20, 20, 20));
-            painter.text(
-                key_rect.center_bottom() + Vec2::new(0.0, -6.0),
-                egui::Align2::CENTER_BOTTOM,
-                midi_note_name(key.midi),
-                FontId::proportional(10.0),
-                Color32::WHITE,
+                ui.interact(hit_rect, egui::Id::new(("black", key.midi)), Sense::click());
+            if response.is_pointer_button_down_on() && primary_down {
+                pressed = Some(key.midi);
+            }
+            response.context_menu(|ui| {
+                trim_menu(ui, &mut self.note_trims, key.midi, flats, self.language)
+            });
+            painter.rect_filled(key_rect, 2.0, self.key_fill(key.midi, true));
+            if !landmarks_only {
+                painter.text(
+                    key_rect.center_bottom() + Vec2::new(0.0, -6.0),
+                    egui::Align2::CENTER_BOTTOM,
+                    self.note_labels.key_label(
+                        key.midi,
+                        true,
+                        self.note_naming,
+                        self.scale_root,
+                        flats,
+                    ),
+                    FontId::proportional((label_size - 2.0).max(MIN_KEY_LABEL_FONT)),
+                    Color32::WHITE,
+                );
+            }
+            draw_shortcut_badge(
+                &painter,
+                key_rect.center_bottom() + Vec2::new(0.0, -40.0),
+                key.midi,
             );
-            if response.clicked() {
-                self.try_play(key.midi);
+        }
+
+        let (origin, pointer) = ui.input(|i| (i.pointer.press_origin(), i.pointer.interact_pos()));
+        if self.glissando && primary_down && origin.is_some_and(|origin| rect.contains(origin)) {
+            // Each key fires once as the pointer crosses into it, like a strum.
+            pressed = pointer.and_then(|pointer| {
+                hit_rects
+                    .iter()
+                    .find(|(key_rect, _)| key_rect.contains(pointer))
+                    .map(|&(_, midi)| midi)
+            });
+        }
+
+        // Set before a new press triggers its note, so every press starts in tune.
+        if self.drag_bend && !self.glissando {
+            self.bend_semitones = match (pressed, origin, pointer) {
+                (Some(_), Some(origin), Some(pointer)) => {
+                    let rise = origin.y - pointer.y;
+                    let travel = (rise.abs() - BEND_DEAD_ZONE_POINTS).max(0.0) * rise.signum();
+                    (travel / BEND_DRAG_POINTS * self.bend_range)
+                        .clamp(-self.bend_range, self.bend_range)
+                }
+                _ => 0.0,
+            };
+            self.bend.set_semitones(self.bend_semitones);
+        } else {
+            self.bend_semitones = 0.0;
+        }
+
+        if pressed.is_some() {
+            if let Some(focused) = ui.memory(|m| m.focused()) {
+                ui.memory_mut(|m| m.surrender_focus(focused));
+            }
+        }
+
+        if pressed != self.mouse_note {
+            if let Some(previous) = self.mouse_note.take() {
+                self.release_note(previous);
             }
+            if let Some(midi) = pressed {
+                self.try_play(midi, None);
+            }
+            self.mouse_note = pressed;
         }
     }
 }
 
 impl eframe::App for SamplePianoApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.time = ctx.input(|i| i.time);
+        self.loop_control
+            .set(self.sample.as_ref().and_then(|sample| sample.loop_frames));
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
-            ui.heading("OpenWah – Soundbite Piano");
-            ui.label(
-                "1) Set bite duration  2) Load any clip  3) The chosen slice becomes base note (C4).",
-            );
+            ui.horizontal(|ui| {
+                ui.heading(self.tr(Text::Heading));
+                egui::ComboBox::from_label(self.tr(Text::Language))
+                    .selected_text(self.language.label())
+                    .show_ui(ui, |ui| {
+                        for language in Language::ALL {
+                            ui.selectable_value(&mut self.language, language, language.label());
+                        }
+                    });
+                ui.checkbox(
+                    &mut self.compact,
+                    strings::get(self.language, Text::Compact),
+                )
+                .on_hover_text(self.tr(Text::CompactHint));
+            });
+            if !self.compact {
+                ui.label(strings::fill(
+                    self.tr(Text::Intro),
+                    midi_note_name(self.base_midi, self.flats()),
+                ));
+            }
 
             ui.horizontal(|ui| {
-                if ui.button("Open Sound Clip...").clicked() {
+                let idle = self.pending_load.is_none();
+                if ui
+                    .add_enabled(idle, egui::Button::new(self.tr(Text::OpenClip)))
+                    .clicked()
+                {
                     if let Some(path) = rfd::FileDialog::new().pick_file() {
                         self.load_clip(path);
                     }
                 }
+                if ui.button(self.tr(Text::OpenFolder)).clicked() {
+                    if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                        self.open_folder(folder);
+                    }
+                }
+                if ui
+                    .add_enabled(idle, egui::Button::new(self.tr(Text::ImportRaw)))
+                    .clicked()
+                {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        let format = self.raw_format.unwrap_or_default();
+                        self.raw_import = Some((path, format));
+                    }
+                }
+                if ui
+                    .add_enabled(idle, egui::Button::new(self.tr(Text::OpenUrl)))
+                    .clicked()
+                {
+                    self.show_url_dialog = true;
+                }
+                ui.add_enabled_ui(idle, |ui| {
+                    ui.menu_button(self.tr(Text::SampleLibrary), |ui| {
+                        for sample in &library::BUNDLED {
+                            if ui.button(sample.name).clicked() {
+                                self.start_load(ClipSource::Memory(sample.clip()), true);
+                                ui.close_menu();
+                            }
+                        }
+                    })
+                    .response
+                    .on_hover_text(self.tr(Text::SampleLibraryHint));
+                });
+                if ui
+                    .add_enabled(
+                        !self.using_test_tone(),
+                        egui::Button::new(self.tr(Text::UseTestTone)),
+                    )
+                    .on_hover_text(self.tr(Text::UseTestToneHint))
+                    .clicked()
+                {
+                    self.use_test_tone();
+                }
+                if let Some(load) = &self.pending_load {
+                    ui.spinner();
+                    ui.label(strings::fill(self.tr(Text::Loading), &load.name));
+                    if ui.button(self.tr(Text::Cancel)).clicked() {
+                        self.pending_load = None;
+                        self.set_status(self.tr(Text::LoadCancelled));
+                    }
+                }
+                if ui
+                    .button(self.tr(Text::AllNotesOff))
+                    .on_hover_text(self.tr(Text::AllNotesOffHint))
+                    .clicked()
+                {
+                    self.all_notes_off();
+                }
                 if let Some(path) = &self.selected_path {
-                    ui.label(format!("Current: {}", path.display()));
+                    ui.label(strings::fill(self.tr(Text::Current), path.display()));
+                } else if let Some(source) = &self.memory_source {
+                    ui.label(strings::fill(self.tr(Text::Current), &source.name));
                 }
             });
 
-            let slider_changed = ui
-                .add(
-                    egui::Slider::new(&mut self.bite_ms, MIN_BITE_MS..=MAX_BITE_MS)
-                        .text("Sound bite (ms)"),
-                )
-                .changed();
+            if !self.compact {
+                self.draw_preset_row(ui);
+            }
+
+            let slider = ui.add(
+                egui::Slider::new(&mut self.bite_ms, MIN_BITE_MS..=MAX_BITE_MS)
+                    .text(strings::get(self.language, Text::SoundBite)),
+            );
+            let slider_changed = slider.changed();
+            if slider.drag_stopped() || (slider_changed && !slider.dragged()) {
+                self.commit_bite();
+            }
             if slider_changed {
                 self.refresh_clip_for_duration();
             }
+            if self.compact {
+                let max_height = ctx.screen_rect().height() * 0.7;
+                ui.menu_button(self.tr(Text::MoreControls), |ui| {
+                    egui::ScrollArea::vertical()
+                        .max_height(max_height)
+                        .show(ui, |ui| {
+                            self.draw_preset_row(ui);
+                            self.draw_sound_controls(ui);
+                        });
+                });
+            } else {
+                self.draw_sound_controls(ui);
+            }
 
-            ui.label(RichText::new(&self.status).color(Color32::LIGHT_BLUE));
+            self.update_meter();
+            self.draw_voice_count(ui);
+            self.draw_meter(ui);
+            self.draw_status(ui);
         });
 
+        let mut held_entry = None;
+        if let Some(folder) = self.browse_folder.clone() {
+            egui::SidePanel::left("sample_browser").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.strong(
+                        folder
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_else(|| folder.display().to_string()),
+                    )
+                    .on_hover_text(folder.display().to_string());
+                    if ui
+                        .small_button("✕")
+                        .on_hover_text(self.tr(Text::CloseFolder))
+                        .clicked()
+                    {
+                        self.browse_folder = None;
+                        self.folder_files.clear();
+                        self.folder_index = None;
+                    }
+                });
+                ui.horizontal(|ui| {
+                    if ui
+                        .button(format!("◀ {}", self.tr(Text::Previous)))
+                        .clicked()
+                    {
+                        self.step_folder(false);
+                    }
+                    if ui.button(format!("{} ▶", self.tr(Text::Next))).clicked() {
+                        self.step_folder(true);
+                    }
+                });
+                ui.separator();
+                let mut clicked = None;
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for (index, path) in self.folder_files.iter().enumerate() {
+                        let name = path
+                            .file_name()
+                            .map(|name| name.to_string_lossy().into_owned())
+                            .unwrap_or_default();
+                        let response = ui
+                            .selectable_label(self.folder_index == Some(index), name)
+                            .on_hover_text(self.tr(Text::BrowserEntryHint));
+                        if response.is_pointer_button_down_on() {
+                            held_entry = Some(index);
+                        }
+                        if response.clicked() {
+                            clicked = Some(index);
+                        }
+                    }
+                });
+                if let Some(index) = clicked {
+                    self.load_folder_file(index);
+                }
+            });
+        }
+        self.update_audition(held_entry);
+
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.separator();
-            ui.label("Piano (C3 → C6)");
+            ui.horizontal(|ui| {
+                ui.label(self.tr(Text::Piano));
+                egui::ComboBox::from_label(self.tr(Text::KeyLabels))
+                    .selected_text(strings::get(self.language, self.note_labels.label()))
+                    .show_ui(ui, |ui| {
+                        for mode in NoteLabelMode::ALL {
+                            ui.selectable_value(
+                                &mut self.note_labels,
+                                mode,
+                                strings::get(self.language, mode.label()),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_id_source("note_naming")
+                    .selected_text(strings::get(self.language, self.note_naming.label()))
+                    .show_ui(ui, |ui| {
+                        for naming in NoteNaming::ALL {
+                            ui.selectable_value(
+                                &mut self.note_naming,
+                                naming,
+                                strings::get(self.language, naming.label()),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(self.tr(Text::MovableDoHint));
+                let flats = self.flats();
+                egui::ComboBox::from_id_source("scale_root")
+                    .selected_text(pitch_class_name(self.scale_root, flats))
+                    .show_ui(ui, |ui| {
+                        for root in 0..12 {
+                            ui.selectable_value(
+                                &mut self.scale_root,
+                                root,
+                                pitch_class_name(root, flats),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_label(self.tr(Text::HighlightScale))
+                    .selected_text(strings::get(self.language, self.scale.label()))
+                    .show_ui(ui, |ui| {
+                        for scale in ScaleHighlight::ALL {
+                            ui.selectable_value(
+                                &mut self.scale,
+                                scale,
+                                strings::get(self.language, scale.label()),
+                            );
+                        }
+                    });
+                egui::ComboBox::from_id_source("accidentals")
+                    .selected_text(strings::get(self.language, self.accidentals.label()))
+                    .show_ui(ui, |ui| {
+                        for accidentals in Accidentals::ALL {
+                            ui.selectable_value(
+                                &mut self.accidentals,
+                                accidentals,
+                                strings::get(self.language, accidentals.label()),
+                            );
+                        }
+                    })
+                    .response
+                    .on_hover_text(self.tr(Text::FollowKeyHint));
+            });
+            self.draw_waveform(ui);
+            egui::CollapsingHeader::new(self.tr(Text::Oscilloscope))
+                .show(ui, |ui| self.draw_scope(ui));
+            self.draw_loop_controls(ui);
+            self.draw_bite_start(ui);
+            self.draw_chord_buttons(ui);
             self.draw_piano(ui);
 
-            if self.selected_path.is_none() {
-                ui.colored_label(
-                    Color32::YELLOW,
-                    "Using generated test tone. Load a clip to replace it.",
-                );
+            if self.using_test_tone() {
+                ui.colored_label(Color32::YELLOW, self.tr(Text::UsingTestTone));
             }
 
-            ui.add_space(8.0);
-            ui.label("Keyboard shortcuts: A W S E D F T G Y H U J K");
+            if !self.compact {
+                ui.add_space(8.0);
+                ui.label(self.tr(Text::Shortcuts));
+            }
         });
 
-        for (key, midi) in [
-            (egui::Key::A, 60),
-            (egui::Key::W, 61),
-            (egui::Key::S, 62),
-            (egui::Key::E, 63),
-            (egui::Key::D, 64),
-            (egui::Key::F, 65),
-            (egui::Key::T, 66),
-            (egui::Key::G, 67),
-            (egui::Key::Y, 68),
-            (egui::Key::H, 69),
-            (egui::Key::U, 70),
-            (egui::Key::J, 71),
-            (egui::Key::K, 72),
-        ] {
-            if ctx.input(|i| i.key_pressed(key)) {
-                self.try_play(midi);
+        if self.show_url_dialog {
+            let mut open = true;
+            egui::Window::new(self.tr(Text::OpenFromUrl))
+                .open(&mut open)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    let response = ui.add(
+                        egui::TextEdit::singleline(&mut self.url_input)
+                            .hint_text("https://example.com/sample.wav")
+                            .desired_width(360.0),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if ui.button(self.tr(Text::Load)).clicked() || submitted {
+                        self.start_url_load();
+                    }
+                });
+            if !open {
+                self.show_url_dialog = false;
+            }
+        }
+        self.draw_raw_import_dialog(ctx);
+        self.handle_close_request(ctx);
+        self.update_voice_cache(ctx);
+        self.poll_pending_load(ctx);
+        self.poll_round_robin(ctx);
+        self.poll_audition(ctx);
+        self.poll_full_load(ctx);
+        self.poll_osc(ctx);
+        self.poll_midi_in(ctx);
+        self.poll_waveform_capture(ctx);
+        self.update_scratch_wav(ctx);
+        self.update_bypass_gain(ctx);
+        self.audio.set_max_voices(self.max_voices);
+        self.update_filter_recording(ctx);
+        self.advance_sequencer(ctx);
+
+        if !ctx.wants_keyboard_input() {
+            let pasted = ctx.input(|i| {
+                i.events.iter().find_map(|event| match event {
+                    egui::Event::Paste(text) => Some(text.clone()),
+                    _ => None,
+                })
+            });
+            if let Some(text) = pasted {
+                self.paste_clip(&text);
+            }
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::Escape)) {
+            self.all_notes_off();
+        }
+
+        if self.scrub_mode
+            && !ctx.wants_keyboard_input()
+            && ctx.input(|i| i.key_pressed(egui::Key::Space))
+        {
+            self.toggle_scrub_player();
+        }
+
+        if !ctx.wants_keyboard_input() && self.browse_folder.is_some() {
+            if ctx.input(|i| i.key_pressed(egui::Key::ArrowDown)) {
+                self.step_folder(true);
+            }
+            if ctx.input(|i| i.key_pressed(egui::Key::ArrowUp)) {
+                self.step_folder(false);
+            }
+        }
+
+        if !ctx.wants_keyboard_input() {
+            let (zoom_in, zoom_out) = ctx.input(|i| {
+                (
+                    i.key_pressed(egui::Key::Plus) || i.key_pressed(egui::Key::Equals),
+                    i.key_pressed(egui::Key::Minus),
+                )
+            });
+            if zoom_in {
+                self.zoom_waveform(2.0);
+            } else if zoom_out {
+                self.zoom_waveform(0.5);
+            }
+        }
+
+        if !ctx.wants_keyboard_input() {
+            if ctx.input_mut(|i| i.consume_key(egui::Modifiers::COMMAND, egui::Key::T)) {
+                self.use_test_tone();
+            }
+            let (earlier, later) = ctx.input_mut(|i| {
+                (
+                    i.consume_key(egui::Modifiers::SHIFT, egui::Key::ArrowLeft),
+                    i.consume_key(egui::Modifiers::SHIFT, egui::Key::ArrowRight),
+                )
+            });
+            if earlier || later {
+                self.nudge_bite_start(later);
+            }
+            let (redo, undo) = ctx.input_mut(|i| {
+                let shift_redo = i.consume_key(
+                    egui::Modifiers::COMMAND | egui::Modifiers::SHIFT,
+                    egui::Key::Z,
+                );
+                let redo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Y);
+                let undo = i.consume_key(egui::Modifiers::COMMAND, egui::Key::Z);
+                (shift_redo || redo, undo)
+            });
+            if undo {
+                self.undo_bite();
+            } else if redo {
+                self.redo_bite();
+            }
+        }
+
+        // Any focused widget (text field, slider, drag value) owns the keyboard,
+        // so letters edit it instead of playing notes. Releases still go through.
+        let note_keys = ctx.memory(|m| m.focused()).is_none();
+        self.held_keys.retain(|&key| ctx.input(|i| i.key_down(key)));
+
+        // Number keys pick chords in chord mode and recall effect snapshots otherwise.
+        if !self.chord_mode && note_keys {
+            let number_keys = [
+                egui::Key::Num1,
+                egui::Key::Num2,
+                egui::Key::Num3,
+                egui::Key::Num4,
+                egui::Key::Num5,
+            ];
+            for (index, key) in number_keys.into_iter().enumerate() {
+                if self.key_struck(ctx, key) {
+                    self.recall_snapshot(index);
+                }
             }
         }
+
+        if self.chord_mode && note_keys {
+            let number_keys = [
+                egui::Key::Num1,
+                egui::Key::Num2,
+                egui::Key::Num3,
+                egui::Key::Num4,
+                egui::Key::Num5,
+            ];
+            for (key, chord) in number_keys.into_iter().zip(ChordType::ALL) {
+                if self.key_struck(ctx, key) {
+                    self.chord_type = chord;
+                    self.try_play(self.chord_root, None);
+                }
+                if ctx.input(|i| i.key_released(key)) {
+                    self.release_note(self.chord_root);
+                }
+            }
+        }
+
+        if note_keys && self.key_struck(ctx, self.tap_key) {
+            self.tap_tempo();
+        }
+
+        for (key, midi) in NOTE_SHORTCUTS {
+            if note_keys && self.key_struck(ctx, key) {
+                self.try_play(midi, None);
+            }
+            if ctx.input(|i| i.key_released(key)) {
+                self.release_note(midi);
+            }
+        }
+
+        self.fire_pending_notes(ctx);
+    }
+
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, BROWSE_FOLDER_KEY, &self.browse_folder);
+        eframe::set_value(storage, LATENCY_MODE_KEY, &self.latency);
+        eframe::set_value(storage, LANGUAGE_KEY, &self.language);
+        eframe::set_value(storage, COMPACT_KEY, &self.compact);
+        eframe::set_value(storage, FULL_DECODE_KEY, &self.full_decode_s);
+        eframe::set_value(storage, SCRATCH_WAV_KEY, &self.scratch_wav);
+        eframe::set_value(storage, KEY_HIT_SCALE_KEY, &self.key_hit_scale);
+        eframe::set_value(storage, MAX_VOICES_KEY, &self.max_voices);
+    }
+}
+
+fn draw_shortcut_badge(painter: &egui::Painter, center: Pos2, midi: i32) {
+    let Some((key, _)) = NOTE_SHORTCUTS.iter().find(|(_, note)| *note == midi) else {
+        return;
+    };
+    let badge = Rect::from_center_size(center, Vec2::splat(16.0));
+    painter.rect_filled(badge, 3.0, Color32::from_rgb(70, 110, 170));
+    painter.text(
+        badge.center(),
+        egui::Align2::CENTER_CENTER,
+        key.name(),
+        FontId::monospace(11.0),
+        Color32::WHITE,
+    );
+}
+
+fn trim_menu(
+    ui: &mut egui::Ui,
+    trims: &mut BTreeMap<i32, f32>,
+    midi: i32,
+    flats: bool,
+    language: Language,
+) {
+    ui.label(strings::fill(
+        strings::get(language, Text::TrimNote),
+        midi_note_name(midi, flats),
+    ));
+    let mut db = trims.get(&midi).copied().unwrap_or(0.0);
+    ui.add(egui::Slider::new(&mut db, -MAX_NOTE_TRIM_DB..=MAX_NOTE_TRIM_DB).suffix(" dB"));
+    if ui.button(strings::get(language, Text::Reset)).clicked() {
+        db = 0.0;
+        ui.close_menu();
+    }
+    if db == 0.0 {
+        trims.remove(&midi);
+    } else {
+        trims.insert(midi, db);
     }
 }
 
+fn preset_name(path: &Path) -> String {
+    path.file_stem()
+        .and_then(|n| n.to_str())
+        .unwrap_or("preset")
+        .to_string()
+}
+
+fn hold_velocity(held_seconds: f64) -> f32 {
+    let t = ((held_seconds - HOLD_VELOCITY_SHORT_S)
+        / (HOLD_VELOCITY_LONG_S - HOLD_VELOCITY_SHORT_S))
+        .clamp(0.0, 1.0) as f32;
+    1.0 - t * (1.0 - HOLD_VELOCITY_MIN)
+}
+
 fn is_black_key(midi: i32) -> bool {
     matches!(midi.rem_euclid(12), 1 | 3 | 6 | 8 | 10)
 }
 
-fn midi_note_name(midi: i32) -> String {
-    let note = match midi.rem_euclid(12) {
-        0 => "C",
-        1 => "C#",
-        2 => "D",
-        3 => "D#",
-        4 => "E",
-        5 => "F",
-        6 => "F#",
-        7 => "G",
-        8 => "G#",
-        9 => "A",
-        10 => "A#",
-        _ => "B",
+fn midi_note_name(midi: i32, flats: bool) -> String {
+    note_name(midi, NoteNaming::Letters, 0, flats)
+}
+
+// Movable Do counts from `key`, so the key's tonic is always Do; octave numbers stay absolute.
+fn note_name(midi: i32, naming: NoteNaming, key: i32, flats: bool) -> String {
+    let note = match naming {
+        NoteNaming::Letters => pitch_class_name(midi, flats),
+        NoteNaming::FixedDo => fixed_do_name(midi, flats),
+        NoteNaming::MovableDo => movable_do_name(midi - key, flats),
     };
-    let octave = midi / 12 - 1;
+    let octave = midi.div_euclid(12) - 1;
     format!("{note}{octave}")
 }
+
+fn fixed_do_name(midi: i32, flats: bool) -> &'static str {
+    match (midi.rem_euclid(12), flats) {
+        (0, _) => "Do",
+        (1, false) => "Do#",
+        (1, true) => "Reb",
+        (2, _) => "Re",
+        (3, false) => "Re#",
+        (3, true) => "Mib",
+        (4, _) => "Mi",
+        (5, _) => "Fa",
+        (6, false) => "Fa#",
+        (6, true) => "Solb",
+        (7, _) => "Sol",
+        (8, false) => "Sol#",
+        (8, true) => "Lab",
+        (9, _) => "La",
+        (10, false) => "La#",
+        (10, true) => "Sib",
+        _ => "Si",
+    }
+}
+
+// Chromatic syllables raise the vowel to "i" for sharps and lower it to "e" (Ra for the
+// flat second) for flats, as taught with movable Do.
+fn movable_do_name(degree: i32, flats: bool) -> &'static str {
+    match (degree.rem_euclid(12), flats) {
+        (0, _) => "Do",
+        (1, false) => "Di",
+        (1, true) => "Ra",
+        (2, _) => "Re",
+        (3, false) => "Ri",
+        (3, true) => "Me",
+        (4, _) => "Mi",
+        (5, _) => "Fa",
+        (6, false) => "Fi",
+        (6, true) => "Se",
+        (7, _) => "Sol",
+        (8, false) => "Si",
+        (8, true) => "Le",
+        (9, _) => "La",
+        (10, false) => "Li",
+        (10, true) => "Te",
+        _ => "Ti",
+    }
+}
+
+fn pitch_class_name(midi: i32, flats: bool) -> &'static str {
+    match (midi.rem_euclid(12), flats) {
+        (0, _) => "C",
+        (1, false) => "C#",
+        (1, true) => "Db",
+        (2, _) => "D",
+        (3, false) => "D#",
+        (3, true) => "Eb",
+        (4, _) => "E",
+        (5, _) => "F",
+        (6, false) => "F#",
+        (6, true) => "Gb",
+        (7, _) => "G",
+        (8, false) => "G#",
+        (8, true) => "Ab",
+        (9, _) => "A",
+        (10, false) => "A#",
+        (10, true) => "Bb",
+        _ => "B",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clip::ToneSettings;
+
+    #[test]
+    fn rapid_notes_stay_within_the_voice_limit() {
+        let (mixer, mut output) = dynamic_mixer::mixer(2, MIX_SAMPLE_RATE);
+        let engine = AudioEngine {
+            mixer: Some(mixer),
+            ..AudioEngine::silent_fallback()
+        };
+        engine.set_max_voices(8);
+        let clip = SampleClip::generated_test_tone(500, &ToneSettings::default());
+        let slots = engine.lock_voices().unwrap().capacity();
+        let mut held = Vec::with_capacity(VOICE_SLOTS);
+
+        // The tuning reference keeps its own slot: it is neither counted nor stolen.
+        engine.play_reference(440.0).unwrap();
+        assert_eq!(engine.reap_voices(&mut held).unwrap(), 0);
+        assert!(held.is_empty());
+
+        for i in 0..2_000 {
+            let midi = PIANO_START_MIDI + i % (PIANO_END_MIDI - PIANO_START_MIDI);
+            engine
+                .play_note(&clip, midi, NoteParams::default())
+                .unwrap();
+            // A few samples between notes, so some voices fade out and finish meanwhile.
+            output.by_ref().take(32).for_each(drop);
+            let releasing = engine.reap_voices(&mut held).unwrap();
+            assert!(held.len() <= 8);
+            assert!(held.len() + releasing <= 8 * VOICE_TAIL_FACTOR);
+        }
+        assert_eq!(engine.lock_voices().unwrap().capacity(), slots);
+        assert!(engine.take_stolen() > 0);
+
+        // Once everything has played out the mixer has dropped every voice.
+        output
+            .by_ref()
+            .take(MIX_SAMPLE_RATE as usize * 2)
+            .for_each(drop);
+        assert_eq!(engine.reap_voices(&mut held).unwrap(), 0);
+        assert!(held.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+use std::sync::mpsc;
+
+use anyhow::{anyhow, Result};
+use midir::{Ignore, MidiInput, MidiInputConnection};
+
+const CLIENT_NAME: &str = "OpenWah";
+const PROGRAM_CHANGE: u8 = 0xC0;
+
+pub fn port_names() -> Result<Vec<String>> {
+    let input = MidiInput::new(CLIENT_NAME)?;
+    Ok(input
+        .ports()
+        .iter()
+        .filter_map(|port| input.port_name(port).ok())
+        .collect())
+}
+
+pub struct MidiIn {
+    _connection: MidiInputConnection<()>,
+    receiver: mpsc::Receiver<u8>,
+    pub port_name: String,
+}
+
+impl MidiIn {
+    pub fn connect(port_name: &str) -> Result<Self> {
+        let mut input = MidiInput::new(CLIENT_NAME)?;
+        input.ignore(Ignore::All);
+        let port = input
+            .ports()
+            .into_iter()
+            .find(|port| input.port_name(port).is_ok_and(|name| name == port_name))
+            .ok_or_else(|| anyhow!("MIDI input port \"{port_name}\" is not available"))?;
+        let (sender, receiver) = mpsc::channel();
+        // Only Program Change is forwarded, on any channel.
+        let connection = input
+            .connect(
+                &port,
+                "OpenWah in",
+                move |_, message, _| {
+                    if let [status, program, ..] = *message {
+                        if status & 0xF0 == PROGRAM_CHANGE {
+                            let _ = sender.send(program & 0x7F);
+                        }
+                    }
+                },
+                (),
+            )
+            .map_err(|err| anyhow!("failed to open {port_name}: {err}"))?;
+        Ok(Self {
+            _connection: connection,
+            receiver,
+            port_name: port_name.to_string(),
+        })
+    }
+
+    pub fn poll_programs(&self) -> Vec<u8> {
+        self.receiver.try_iter().collect()
+    }
+}
@@ -0,0 +1,64 @@
+use anyhow::{anyhow, Result};
+use midir::{MidiOutput, MidiOutputConnection};
+
+const CLIENT_NAME: &str = "OpenWah";
+const NOTE_ON: u8 = 0x90;
+const NOTE_OFF: u8 = 0x80;
+const CONTROL_CHANGE: u8 = 0xB0;
+const ALL_NOTES_OFF: u8 = 123;
+
+pub fn port_names() -> Result<Vec<String>> {
+    let output = MidiOutput::new(CLIENT_NAME)?;
+    Ok(output
+        .ports()
+        .iter()
+        .filter_map(|port| output.port_name(port).ok())
+        .collect())
+}
+
+pub struct MidiOut {
+    connection: MidiOutputConnection,
+    pub port_name: String,
+}
+
+impl MidiOut {
+    pub fn connect(port_name: &str) -> Result<Self> {
+        let output = MidiOutput::new(CLIENT_NAME)?;
+        let port = output
+            .ports()
+            .into_iter()
+            .find(|port| output.port_name(port).is_ok_and(|name| name == port_name))
+            .ok_or_else(|| anyhow!("MIDI output port \"{port_name}\" is not available"))?;
+        let connection = output
+            .connect(&port, "OpenWah out")
+            .map_err(|err| anyhow!("failed to open {port_name}: {err}"))?;
+        Ok(Self {
+            connection,
+            port_name: port_name.to_string(),
+        })
+    }
+
+    pub fn note_on(&mut self, midi: i32, velocity: u8) -> Result<()> {
+        self.send(NOTE_ON, midi, velocity.clamp(1, 127))
+    }
+
+    pub fn note_off(&mut self, midi: i32) -> Result<()> {
+        self.send(NOTE_OFF, midi, 0)
+    }
+
+    pub fn all_notes_off(&mut self) -> Result<()> {
+        self.connection.send(&[CONTROL_CHANGE, ALL_NOTES_OFF, 0])?;
+        Ok(())
+    }
+
+    fn send(&mut self, status: u8, midi: i32, velocity: u8) -> Result<()> {
+        let Ok(note) = u8::try_from(midi) else {
+            return Ok(());
+        };
+        if note > 127 {
+            return Ok(());
+        }
+        self.connection.send(&[status, note, velocity])?;
+        Ok(())
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    io::ErrorKind,
+    net::{Ipv4Addr, SocketAddr, UdpSocket},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use anyhow::{Context, Result};
+use rosc::{OscMessage, OscPacket, OscType};
+
+pub const DEFAULT_OSC_PORT: u16 = 9000;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscCommand {
+    Note { midi: i32, velocity: Option<f32> },
+    Param { name: String, value: f32 },
+}
+
+pub struct OscServer {
+    pub address: SocketAddr,
+    receiver: mpsc::Receiver<OscCommand>,
+    stopped: Arc<AtomicBool>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl OscServer {
+    // Only this computer can send notes and parameters unless `network` opens the port
+    // to the whole LAN.
+    pub fn bind(port: u16, network: bool) -> Result<Self> {
+        let host = if network {
+            Ipv4Addr::UNSPECIFIED
+        } else {
+            Ipv4Addr::LOCALHOST
+        };
+        let socket = UdpSocket::bind((host, port))
+            .with_context(|| format!("failed to listen on UDP port {port}"))?;
+        let address = socket.local_addr()?;
+        socket.set_read_timeout(Some(POLL_INTERVAL))?;
+        let (sender, receiver) = mpsc::channel();
+        let stopped = Arc::new(AtomicBool::new(false));
+        let worker_stopped = Arc::clone(&stopped);
+
+        let worker = thread::spawn(move || {
+            let mut buffer = [0u8; rosc::decoder::MTU];
+            while !worker_stopped.load(Ordering::Relaxed) {
+                let size = match socket.recv(&mut buffer) {
+                    Ok(size) => size,
+                    Err(err)
+                        if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                    {
+                        continue
+                    }
+                    Err(_) => break,
+                };
+                let Ok((_, packet)) = rosc::decoder::decode_udp(&buffer[..size]) else {
+                    continue;
+                };
+                for command in commands(packet) {
+                    if sender.send(command).is_err() {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Ok(Self {
+            address,
+            receiver,
+            stopped,
+            worker: Some(worker),
+        })
+    }
+
+    pub fn poll(&self) -> Vec<OscCommand> {
+        self.receiver.try_iter().collect()
+    }
+}
+
+impl Drop for OscServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        // Waits at most one poll interval, so the port is free again for an immediate rebind.
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+    }
+}
+
+fn commands(packet: OscPacket) -> Vec<OscCommand> {
+    match packet {
+        OscPacket::Message(message) => parse_message(message).into_iter().collect(),
+        OscPacket::Bundle(bundle) => bundle.content.into_iter().flat_map(commands).collect(),
+    }
+}
+
+fn parse_message(message: OscMessage) -> Option<OscCommand> {
+    let mut args = message.args.iter().map(number);
+    if message.addr == "/note" {
+        let midi = args.next()??.round() as i32;
+        let velocity = args.next().flatten().map(|v| v.clamp(0.0, 1.0));
+        return Some(OscCommand::Note { midi, velocity });
+    }
+    let name = message.addr.strip_prefix("/param/")?;
+    Some(OscCommand::Param {
+        name: name.to_string(),
+        value: args.next()??,
+    })
+}
+
+fn number(arg: &OscType) -> Option<f32> {
+    match *arg {
+        OscType::Int(value) => Some(value as f32),
+        OscType::Long(value) => Some(value as f32),
+        OscType::Float(value) => Some(value),
+        OscType::Double(value) => Some(value as f32),
+        _ => None,
+    }
+}
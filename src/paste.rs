@@ -0,0 +1,156 @@
+use std::{path::PathBuf, sync::Arc};
+
+use base64::Engine;
+
+use crate::{
+    clip::{self, ClipSource, MemoryClip},
+    remote,
+};
+
+// egui only hands over clipboard text, so audio arrives either as file references (one
+// path or a `text/uri-list`, as file managers copy them) or as a base64 `data:` URI.
+pub enum PasteError {
+    NoFile,
+    NotAudio(PathBuf),
+    BadData(String),
+}
+
+pub fn clip_from_clipboard(text: &str) -> Result<ClipSource, PasteError> {
+    let text = text.trim();
+    if let Some(data) = text.strip_prefix("data:") {
+        return clip_from_data_uri(data).map(ClipSource::Memory);
+    }
+
+    let mut not_audio = None;
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some(path) = path_from_line(line) else {
+            continue;
+        };
+        if clip::is_supported_audio(&path) {
+            return Ok(ClipSource::File(path));
+        }
+        not_audio.get_or_insert(path);
+    }
+    Err(not_audio.map_or(PasteError::NoFile, PasteError::NotAudio))
+}
+
+fn path_from_line(line: &str) -> Option<PathBuf> {
+    let unquoted = line
+        .strip_prefix(['"', '\''])
+        .and_then(|rest| rest.strip_suffix(['"', '\'']))
+        .unwrap_or(line);
+    let path = match unquoted.strip_prefix("file://") {
+        Some(rest) => percent_decode(rest.strip_prefix("localhost").unwrap_or(rest)),
+        None => unquoted.to_string(),
+    };
+    let path = PathBuf::from(path);
+    path.is_file().then_some(path)
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+fn clip_from_data_uri(data: &str) -> Result<MemoryClip, PasteError> {
+    let (header, payload) = data
+        .split_once(',')
+        .ok_or_else(|| PasteError::BadData("the data URI has no payload".to_string()))?;
+    let mut parts = header.split(';');
+    let mime = parts.next().unwrap_or_default();
+    if !parts.any(|part| part.eq_ignore_ascii_case("base64")) {
+        return Err(PasteError::BadData(
+            "only base64-encoded data URIs are supported".to_string(),
+        ));
+    }
+    let extension = remote::extension_from_content_type(mime)
+        .ok_or_else(|| PasteError::BadData(format!("{mime:?} is not a supported audio type")))?;
+    let payload: String = payload.split_whitespace().collect();
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(payload)
+        .map_err(|err| PasteError::BadData(err.to_string()))?;
+    Ok(MemoryClip {
+        name: format!("clipboard.{extension}"),
+        bytes: Arc::new(bytes),
+        extension: Some(extension.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_the_first_audio_file_from_a_uri_list() {
+        let dir = std::env::temp_dir().join("openwah paste test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let notes = dir.join("notes.txt");
+        let audio = dir.join("hit #1.wav");
+        std::fs::write(&notes, b"").unwrap();
+        std::fs::write(&audio, b"").unwrap();
+
+        let list = format!(
+            "# copied\r\nfile://{}\r\nfile://{}\r\n",
+            notes.display().to_string().replace(' ', "%20"),
+            audio
+                .display()
+                .to_string()
+                .replace(' ', "%20")
+                .replace('#', "%23"),
+        );
+        assert!(matches!(
+            clip_from_clipboard(&list),
+            Ok(ClipSource::File(path)) if path == audio
+        ));
+        assert!(matches!(
+            clip_from_clipboard(&notes.display().to_string()),
+            Err(PasteError::NotAudio(path)) if path == notes
+        ));
+        assert!(matches!(
+            clip_from_clipboard("just some text"),
+            Err(PasteError::NoFile)
+        ));
+    }
+
+    #[test]
+    fn decodes_base64_data_uris() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(b"RIFF");
+        let Ok(ClipSource::Memory(memory)) =
+            clip_from_clipboard(&format!("data:audio/x-wav;base64,{encoded}"))
+        else {
+            panic!("data URI was not accepted");
+        };
+        assert_eq!(memory.bytes.as_slice(), b"RIFF");
+        assert_eq!(memory.extension.as_deref(), Some("wav"));
+
+        assert!(matches!(
+            clip_from_clipboard("data:audio/wav,RIFF"),
+            Err(PasteError::BadData(_))
+        ));
+        assert!(matches!(
+            clip_from_clipboard("data:image/png;base64,AAAA"),
+            Err(PasteError::BadData(_))
+        ));
+    }
+}
@@ -0,0 +1,86 @@
+const MIN_FREQUENCY: f32 = 40.0;
+const MAX_FREQUENCY: f32 = 2_000.0;
+const WINDOW: usize = 2_048;
+const YIN_THRESHOLD: f32 = 0.15;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PitchEstimate {
+    pub frequency: f32,
+    pub confidence: f32,
+}
+
+impl PitchEstimate {
+    pub fn midi_note(&self) -> i32 {
+        (69.0 + 12.0 * (self.frequency / 440.0).log2()).round() as i32
+    }
+}
+
+pub fn detect(samples: &[f32], sample_rate: u32) -> Option<PitchEstimate> {
+    let rate = sample_rate as f32;
+    let tau_min = (rate / MAX_FREQUENCY).floor().max(2.0) as usize;
+    let tau_max = (rate / MIN_FREQUENCY).ceil() as usize;
+    if samples.len() < WINDOW + tau_max {
+        return None;
+    }
+
+    let start = loudest_window_start(samples, samples.len() - WINDOW - tau_max);
+    let frame = &samples[start..start + WINDOW + tau_max];
+
+    let mut cmnd = vec![1.0f32; tau_max + 1];
+    let mut running_sum = 0.0f32;
+    for tau in 1..=tau_max {
+        let diff: f32 = (0..WINDOW)
+            .map(|j| {
+                let d = frame[j] - frame[j + tau];
+                d * d
+            })
+            .sum();
+        running_sum += diff;
+        cmnd[tau] = if running_sum > 0.0 {
+            diff * tau as f32 / running_sum
+        } else {
+            1.0
+        };
+    }
+
+    let mut best = (tau_min..=tau_max).find(|&tau| cmnd[tau] < YIN_THRESHOLD);
+    if let Some(mut tau) = best {
+        while tau < tau_max && cmnd[tau + 1] < cmnd[tau] {
+            tau += 1;
+        }
+        best = Some(tau);
+    }
+    let tau = best.or_else(|| (tau_min..=tau_max).min_by(|&a, &b| cmnd[a].total_cmp(&cmnd[b])))?;
+
+    let refined = if tau > 1 && tau < tau_max {
+        let (prev, here, next) = (cmnd[tau - 1], cmnd[tau], cmnd[tau + 1]);
+        let denom = prev - 2.0 * here + next;
+        if denom.abs() > f32::EPSILON {
+            tau as f32 + 0.5 * (prev - next) / denom
+        } else {
+            tau as f32
+        }
+    } else {
+        tau as f32
+    };
+
+    Some(PitchEstimate {
+        frequency: rate / refined,
+        confidence: (1.0 - cmnd[tau]).clamp(0.0, 1.0),
+    })
+}
+
+fn loudest_window_start(samples: &[f32], last_start: usize) -> usize {
+    (0..=last_start)
+        .step_by(WINDOW / 2)
+        .max_by(|&a, &b| {
+            let energy = |start: usize| {
+                samples[start..start + WINDOW]
+                    .iter()
+                    .map(|s| s * s)
+                    .sum::<f32>()
+            };
+            energy(a).total_cmp(&energy(b))
+        })
+        .unwrap_or(0)
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    clip::{ClipLayer, LayerMode, ToneSettings},
+    dsp, KeyZone, BASE_MIDI_NOTE, DEFAULT_ATTACK_MS, DEFAULT_BITE_MS, DEFAULT_SMOOTHING_MS,
+    NOTE_OFF_FADE_MS,
+};
+
+pub const PRESET_EXTENSION: &str = "owah";
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preset {
+    pub source_path: Option<PathBuf>,
+    pub layers: Vec<ClipLayer>,
+    pub layer_mode: LayerMode,
+    pub use_layers: bool,
+    pub bite_ms: u32,
+    // Frames into the source where the bite is cut.
+    pub bite_start: usize,
+    pub preserve_stereo: bool,
+    pub stereo_width: f32,
+    pub pan: f32,
+    pub random_pan: bool,
+    pub tone: ToneSettings,
+    pub base_midi: i32,
+    pub detune_cents: f32,
+    pub transpose: i32,
+    pub freeze: bool,
+    pub grain_ms: f32,
+    pub freeze_position: f32,
+    pub auto_level: bool,
+    pub bite_gain_comp: bool,
+    pub crush: bool,
+    pub crush_bits: u32,
+    pub crush_downsample: u32,
+    pub crush_mix: f32,
+    pub overdrive: bool,
+    pub overdrive_curve: dsp::DriveCurve,
+    pub overdrive_drive_db: f32,
+    pub overdrive_level_db: f32,
+    pub overdrive_mix: f32,
+    pub reverb: bool,
+    pub reverb_room: f32,
+    pub reverb_damping: f32,
+    pub reverb_mix: f32,
+    pub limiter_threshold_db: f32,
+    pub limiter_makeup_db: f32,
+    pub tremolo: bool,
+    pub tremolo_rate: f32,
+    pub tremolo_depth: f32,
+    pub tremolo_shape: dsp::LfoShape,
+    pub tremolo_sync: bool,
+    pub reverse_attack: bool,
+    pub reverse_attack_ms: u32,
+    pub interp: dsp::InterpQuality,
+    pub remove_dc: bool,
+    pub fill_loop: bool,
+    pub release_ms: u32,
+    pub release_curve: dsp::ReleaseCurve,
+    pub smoothing_ms: u32,
+    pub attack_ms: u32,
+    pub filter: bool,
+    pub filter_cutoff: f32,
+    pub filter_resonance: f32,
+    pub filter_automation: Vec<(f32, f32)>,
+    pub filter_play_automation: bool,
+    pub auto_wah: bool,
+    pub auto_wah_sensitivity: f32,
+    pub auto_wah_low_hz: f32,
+    pub auto_wah_high_hz: f32,
+    pub auto_wah_attack_ms: f32,
+    pub auto_wah_release_ms: f32,
+    pub auto_wah_resonance: f32,
+    pub note_trims: BTreeMap<i32, f32>,
+    pub zones: Vec<KeyZone>,
+    pub effect_snapshots: Vec<EffectSnapshot>,
+}
+
+impl Default for Preset {
+    fn default() -> Self {
+        Self {
+            source_path: None,
+            layers: Vec::new(),
+            layer_mode: LayerMode::Sum,
+            use_layers: false,
+            bite_ms: DEFAULT_BITE_MS,
+            bite_start: 0,
+            preserve_stereo: false,
+            stereo_width: 100.0,
+            pan: 0.0,
+            random_pan: false,
+            tone: ToneSettings::default(),
+            base_midi: BASE_MIDI_NOTE,
+            detune_cents: 0.0,
+            transpose: 0,
+            freeze: false,
+            grain_ms: 80.0,
+            freeze_position: 0.5,
+            auto_level: true,
+            bite_gain_comp: false,
+            crush: false,
+            crush_bits: 8,
+            crush_downsample: 4,
+            crush_mix: 1.0,
+            overdrive: false,
+            overdrive_curve: dsp::DriveCurve::SoftClip,
+            overdrive_drive_db: 12.0,
+            overdrive_level_db: 0.0,
+            overdrive_mix: 1.0,
+            reverb: false,
+            reverb_room: 0.6,
+            reverb_damping: 0.5,
+            reverb_mix: 0.3,
+            limiter_threshold_db: dsp::DEFAULT_LIMITER_THRESHOLD_DB,
+            limiter_makeup_db: 0.0,
+            tremolo: false,
+            tremolo_rate: 5.0,
+            tremolo_depth: 0.5,
+            tremolo_shape: dsp::LfoShape::Sine,
+            tremolo_sync: false,
+            reverse_attack: false,
+            reverse_attack_ms: 200,
+            interp: dsp::InterpQuality::Linear,
+            remove_dc: false,
+            fill_loop: false,
+            release_ms: NOTE_OFF_FADE_MS,
+            release_curve: dsp::ReleaseCurve::Linear,
+            smoothing_ms: DEFAULT_SMOOTHING_MS,
+            attack_ms: DEFAULT_ATTACK_MS,
+            filter: false,
+            filter_cutoff: 2_000.0,
+            filter_resonance: 4.0,
+            filter_automation: Vec::new(),
+            filter_play_automation: false,
+            auto_wah: false,
+            auto_wah_sensitivity: 0.5,
+            auto_wah_low_hz: 300.0,
+            auto_wah_high_hz: 3_000.0,
+            auto_wah_attack_ms: 10.0,
+            auto_wah_release_ms: 150.0,
+            auto_wah_resonance: 4.0,
+            note_trims: BTreeMap::new(),
+            zones: Vec::new(),
+            effect_snapshots: Vec::new(),
+        }
+    }
+}
+
+// The effect-chain part of a preset, recalled on its own during performance.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectSnapshot {
+    pub name: String,
+    pub crush: bool,
+    pub crush_bits: u32,
+    pub crush_downsample: u32,
+    pub crush_mix: f32,
+    pub overdrive: bool,
+    pub overdrive_curve: dsp::DriveCurve,
+    pub overdrive_drive_db: f32,
+    pub overdrive_level_db: f32,
+    pub overdrive_mix: f32,
+    pub reverb: bool,
+    pub reverb_room: f32,
+    pub reverb_damping: f32,
+    pub reverb_mix: f32,
+    pub tremolo: bool,
+    pub tremolo_rate: f32,
+    pub tremolo_depth: f32,
+    pub tremolo_shape: dsp::LfoShape,
+    pub tremolo_sync: bool,
+    pub filter: bool,
+    pub filter_cutoff: f32,
+    pub filter_resonance: f32,
+    pub auto_wah: bool,
+    pub auto_wah_sensitivity: f32,
+    pub auto_wah_low_hz: f32,
+    pub auto_wah_high_hz: f32,
+    pub auto_wah_attack_ms: f32,
+    pub auto_wah_release_ms: f32,
+    pub auto_wah_resonance: f32,
+}
+
+impl EffectSnapshot {
+    pub fn from_preset(name: String, preset: &Preset) -> Self {
+        Self {
+            name,
+            crush: preset.crush,
+            crush_bits: preset.crush_bits,
+            crush_downsample: preset.crush_downsample,
+            crush_mix: preset.crush_mix,
+            overdrive: preset.overdrive,
+            overdrive_curve: preset.overdrive_curve,
+            overdrive_drive_db: preset.overdrive_drive_db,
+            overdrive_level_db: preset.overdrive_level_db,
+            overdrive_mix: preset.overdrive_mix,
+            reverb: preset.reverb,
+            reverb_room: preset.reverb_room,
+            reverb_damping: preset.reverb_damping,
+            reverb_mix: preset.reverb_mix,
+            tremolo: preset.tremolo,
+            tremolo_rate: preset.tremolo_rate,
+            tremolo_depth: preset.tremolo_depth,
+            tremolo_shape: preset.tremolo_shape,
+            tremolo_sync: preset.tremolo_sync,
+            filter: preset.filter,
+            filter_cutoff: preset.filter_cutoff,
+            filter_resonance: preset.filter_resonance,
+            auto_wah: preset.auto_wah,
+            auto_wah_sensitivity: preset.auto_wah_sensitivity,
+            auto_wah_low_hz: preset.auto_wah_low_hz,
+            auto_wah_high_hz: preset.auto_wah_high_hz,
+            auto_wah_attack_ms: preset.auto_wah_attack_ms,
+            auto_wah_release_ms: preset.auto_wah_release_ms,
+            auto_wah_resonance: preset.auto_wah_resonance,
+        }
+    }
+}
+
+impl Default for EffectSnapshot {
+    fn default() -> Self {
+        Self::from_preset(String::new(), &Preset::default())
+    }
+}
+
+impl Preset {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("failed to read preset: {}", path.display()))?;
+        serde_json::from_str(&text)
+            .with_context(|| format!("invalid preset file: {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(self)?;
+        fs::write(path, text).with_context(|| format!("failed to write preset: {}", path.display()))
+    }
+}
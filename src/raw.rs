@@ -0,0 +1,104 @@
+use std::{fs, path::Path};
+
+use anyhow::{anyhow, Context, Result};
+
+use crate::strings::Text;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RawEncoding {
+    U8,
+    S16,
+    S24,
+    S32,
+    F32,
+}
+
+impl RawEncoding {
+    pub const ALL: [Self; 5] = [Self::U8, Self::S16, Self::S24, Self::S32, Self::F32];
+
+    pub fn label(self) -> Text {
+        match self {
+            Self::U8 => Text::RawU8,
+            Self::S16 => Text::RawS16,
+            Self::S24 => Text::RawS24,
+            Self::S32 => Text::RawS32,
+            Self::F32 => Text::RawF32,
+        }
+    }
+
+    fn bytes(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::S16 => 2,
+            Self::S24 => 3,
+            Self::S32 | Self::F32 => 4,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RawPcmFormat {
+    pub sample_rate: u32,
+    pub encoding: RawEncoding,
+    pub channels: u16,
+    pub big_endian: bool,
+}
+
+impl Default for RawPcmFormat {
+    fn default() -> Self {
+        Self {
+            sample_rate: 44_100,
+            encoding: RawEncoding::S16,
+            channels: 1,
+            big_endian: false,
+        }
+    }
+}
+
+pub fn read(path: &Path, format: &RawPcmFormat) -> Result<Vec<f32>> {
+    let bytes =
+        fs::read(path).with_context(|| format!("failed to read raw file: {}", path.display()))?;
+    parse(&bytes, format)
+}
+
+fn parse(bytes: &[u8], format: &RawPcmFormat) -> Result<Vec<f32>> {
+    if format.sample_rate == 0 || format.channels == 0 {
+        return Err(anyhow!("sample rate and channel count must be non-zero"));
+    }
+    let width = format.encoding.bytes();
+    let frame_bytes = width * usize::from(format.channels);
+    if bytes.is_empty() {
+        return Err(anyhow!("raw file is empty"));
+    }
+    if !bytes.len().is_multiple_of(frame_bytes) {
+        return Err(anyhow!(
+            "file length {} bytes is not a whole number of {}-byte frames; check bit depth and channel count",
+            bytes.len(),
+            frame_bytes
+        ));
+    }
+
+    Ok(bytes
+        .chunks_exact(width)
+        .map(|chunk| {
+            let mut word = [0u8; 4];
+            if format.big_endian {
+                word[4 - width..].copy_from_slice(chunk);
+            } else {
+                word[..width].copy_from_slice(chunk);
+            }
+            let value = if format.big_endian {
+                u32::from_be_bytes(word)
+            } else {
+                u32::from_le_bytes(word)
+            };
+            match format.encoding {
+                RawEncoding::U8 => (value as f32 - 128.0) / 128.0,
+                RawEncoding::S16 => value as u16 as i16 as f32 / 32_768.0,
+                RawEncoding::S24 => ((value << 8) as i32 >> 8) as f32 / 8_388_608.0,
+                RawEncoding::S32 => value as i32 as f32 / 2_147_483_648.0,
+                RawEncoding::F32 => f32::from_bits(value),
+            }
+        })
+        .collect())
+}
@@ -0,0 +1,71 @@
+use std::io::Read;
+
+use anyhow::{anyhow, Context, Result};
+
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+pub struct Download {
+    pub bytes: Vec<u8>,
+    pub extension: Option<String>,
+}
+
+pub fn download(url: &str) -> Result<Download> {
+    let response = ureq::get(url)
+        .call()
+        .with_context(|| format!("failed to download {url}"))?;
+
+    let extension = extension_from_url(url).or_else(|| {
+        response
+            .header("Content-Type")
+            .and_then(extension_from_content_type)
+            .map(str::to_string)
+    });
+
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .context("connection dropped while downloading")?;
+    if bytes.len() as u64 > MAX_DOWNLOAD_BYTES {
+        return Err(anyhow!(
+            "download exceeds {} MB limit",
+            MAX_DOWNLOAD_BYTES / (1024 * 1024)
+        ));
+    }
+    if bytes.is_empty() {
+        return Err(anyhow!("server returned an empty response"));
+    }
+
+    Ok(Download { bytes, extension })
+}
+
+pub fn file_name_from_url(url: &str) -> &str {
+    url_path(url)
+        .rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(url)
+}
+
+fn url_path(url: &str) -> &str {
+    url.split(['?', '#']).next().unwrap_or(url)
+}
+
+fn extension_from_url(url: &str) -> Option<String> {
+    let (_, ext) = file_name_from_url(url).rsplit_once('.')?;
+    (!ext.is_empty() && ext.len() <= 5 && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+        .then(|| ext.to_ascii_lowercase())
+}
+
+pub fn extension_from_content_type(content_type: &str) -> Option<&'static str> {
+    let mime = content_type.split(';').next()?.trim().to_ascii_lowercase();
+    match mime.as_str() {
+        "audio/wav" | "audio/wave" | "audio/x-wav" | "audio/vnd.wave" => Some("wav"),
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        "audio/flac" | "audio/x-flac" => Some("flac"),
+        "audio/ogg" | "application/ogg" | "audio/vorbis" => Some("ogg"),
+        "audio/mp4" | "audio/x-m4a" => Some("m4a"),
+        "audio/aac" => Some("aac"),
+        _ => None,
+    }
+}
@@ -0,0 +1,508 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{anyhow, Context, Result};
+use rodio::{source::UniformSourceIterator, Source};
+
+use crate::{
+    clip::{self, SampleClip, ToneSettings},
+    dsp, midi_note_name, NoteParams, DEFAULT_BITE_MS, LOOP_CROSSFADE_MS, MAX_BITE_MS, MIN_BITE_MS,
+};
+
+pub type VoiceSource = Box<dyn Source<Item = f32> + Send>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoteEvent {
+    pub midi: i32,
+    pub start_ms: u32,
+    pub duration_ms: u32,
+}
+
+pub fn pitch_ratio(midi_note: i32, params: &NoteParams) -> f32 {
+    let semitones =
+        (midi_note + params.transpose - params.base_midi) as f32 + params.detune_cents / 100.0;
+    2.0f32.powf(semitones / 12.0)
+}
+
+pub fn voice_source(clip: &SampleClip, midi_note: i32, params: &NoteParams) -> VoiceSource {
+    let ratio = pitch_ratio(midi_note, params);
+    let loop_control = params
+        .loop_control
+        .as_ref()
+        .filter(|_| clip.loop_frames.is_some());
+    let source: VoiceSource = match (params.freeze, loop_control, &params.prerendered) {
+        (Some(freeze), _, _) => Box::new(
+            dsp::GranularFreeze::new(
+                Arc::clone(&clip.samples),
+                clip.channels,
+                clip.sample_rate,
+                freeze.grain_ms,
+                freeze.position,
+                ratio,
+            )
+            .amplify(0.75 * params.gain),
+        ),
+        // Loops read the clip at its own rate, so pitch comes from the playback speed.
+        (None, Some(control), _) => Box::new(
+            dsp::LoopedClip::new(
+                Arc::clone(&clip.samples),
+                clip.channels,
+                clip.sample_rate,
+                control.clone(),
+                clip::frames_for_ms(clip.sample_rate, LOOP_CROSSFADE_MS),
+            )
+            .speed(ratio)
+            .amplify(0.75 * params.gain),
+        ),
+        // Performance mode already pitched this key, so the buffer plays back as-is.
+        (None, None, Some(buffer)) => Box::new(
+            dsp::Resampler::new(
+                Arc::clone(buffer),
+                clip.channels,
+                clip.sample_rate,
+                1.0,
+                dsp::InterpQuality::Nearest,
+            )
+            .amplify(0.75 * params.gain),
+        ),
+        (None, None, None) => Box::new(
+            dsp::Resampler::new(
+                Arc::clone(&clip.samples),
+                clip.channels,
+                clip.sample_rate,
+                ratio,
+                params.interp,
+            )
+            .amplify(0.75 * params.gain),
+        ),
+    };
+    let source: VoiceSource = match &params.bend {
+        Some(control) => Box::new(dsp::Bend::new(source, control.clone())),
+        None => source,
+    };
+    let source: VoiceSource = match params.crush {
+        Some(crush) => Box::new(dsp::BitCrush::new(
+            source,
+            crush.bits,
+            crush.downsample,
+            crush.mix,
+        )),
+        None => source,
+    };
+    let source: VoiceSource = match params.overdrive {
+        Some(drive) => Box::new(dsp::Overdrive::new(
+            source,
+            drive.curve,
+            drive.drive_db,
+            drive.level_db,
+            drive.mix,
+        )),
+        None => source,
+    };
+    let source: VoiceSource = match params.tremolo {
+        Some(tremolo) => Box::new(dsp::Tremolo::new(
+            source,
+            tremolo.rate_hz,
+            tremolo.depth,
+            tremolo.shape,
+        )),
+        None => source,
+    };
+    let source: VoiceSource = match &params.filter {
+        Some(filter) => Box::new(dsp::WahFilter::new(
+            source,
+            filter.control.clone(),
+            filter.resonance,
+            filter.automation.clone(),
+        )),
+        None => source,
+    };
+    let source: VoiceSource = match params.auto_wah {
+        Some(wah) => Box::new(dsp::AutoWah::new(
+            source,
+            wah.sensitivity,
+            wah.low_hz,
+            wah.high_hz,
+            wah.attack_ms,
+            wah.release_ms,
+            wah.resonance,
+        )),
+        None => source,
+    };
+    let source: VoiceSource = if params.attack_ms > 0 {
+        Box::new(source.fade_in(Duration::from_millis(u64::from(params.attack_ms))))
+    } else {
+        source
+    };
+    let source: VoiceSource = if params.delay_ms > 0.0 {
+        Box::new(source.delay(Duration::from_secs_f32(params.delay_ms / 1_000.0)))
+    } else {
+        source
+    };
+    let source: VoiceSource = if clip.channels == 2 && params.width != 1.0 {
+        Box::new(dsp::StereoWidth::new(source, params.width))
+    } else {
+        source
+    };
+    Box::new(dsp::Panned::new(source, params.pan))
+}
+
+pub fn releasable_voice(
+    clip: &SampleClip,
+    midi_note: i32,
+    params: &NoteParams,
+    release: dsp::ReleaseHandle,
+) -> VoiceSource {
+    // The reverb sits after the release fade so a released voice keeps its tail.
+    let voice = dsp::Releasable::new(voice_source(clip, midi_note, params), release);
+    match params.reverb {
+        Some(reverb) => Box::new(dsp::Reverb::new(
+            voice,
+            reverb.room_size,
+            reverb.damping,
+            reverb.mix,
+        )),
+        None => Box::new(voice),
+    }
+}
+
+// RMS over the start of a voice, long enough to cover typical effect modulation.
+pub fn voice_rms(clip: &SampleClip, midi_note: i32, params: &NoteParams, duration_ms: u32) -> f32 {
+    let voice = releasable_voice(clip, midi_note, params, dsp::ReleaseHandle::new());
+    let len = clip::frames_for_ms(voice.sample_rate(), duration_ms) * usize::from(voice.channels());
+    let (sum, count) = voice
+        .take(len)
+        .fold((0.0f64, 0usize), |(sum, count), sample| {
+            (sum + f64::from(sample * sample), count + 1)
+        });
+    if count == 0 {
+        0.0
+    } else {
+        (sum / count as f64).sqrt() as f32
+    }
+}
+
+pub fn render_notes(
+    clip: &SampleClip,
+    notes: &[NoteEvent],
+    params: &NoteParams,
+    sample_rate: u32,
+) -> Vec<f32> {
+    let ms_to_frames = |ms: u32| clip::frames_for_ms(sample_rate, ms);
+    let total_frames = notes
+        .iter()
+        .map(|note| ms_to_frames(note.start_ms + note.duration_ms + params.release_ms))
+        .max()
+        .unwrap_or(0);
+    let mut mix = vec![0.0f32; total_frames * 2];
+
+    for note in notes {
+        let release = dsp::ReleaseHandle::new();
+        let voice = releasable_voice(clip, note.midi, params, release.clone());
+        let mut samples = UniformSourceIterator::<_, f32>::new(voice, 2, sample_rate);
+
+        let start = ms_to_frames(note.start_ms);
+        let release_at = start + ms_to_frames(note.duration_ms);
+        for frame in start..total_frames {
+            if frame == release_at {
+                release.release(params.release_ms, params.release_curve);
+            }
+            let (Some(left), Some(right)) = (samples.next(), samples.next()) else {
+                break;
+            };
+            mix[frame * 2] += left;
+            mix[frame * 2 + 1] += right;
+        }
+    }
+
+    let limiter = dsp::LimiterControl::default();
+    mix.iter_mut()
+        .for_each(|sample| *sample = limiter.process(*sample));
+    mix
+}
+
+pub fn parse_notes(text: &str) -> Result<Vec<NoteEvent>> {
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(line_number, line)| {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [midi, start_ms, duration_ms] = fields[..] else {
+                return Err(anyhow!(
+                    "line {line_number}: expected \"note,start_ms,duration_ms\""
+                ));
+            };
+            let parse = |value: &str, what: &str| {
+                value
+                    .parse::<u32>()
+                    .with_context(|| format!("line {line_number}: invalid {what} \"{value}\""))
+            };
+            let midi = parse(midi, "note")?;
+            if midi > 127 {
+                return Err(anyhow!("line {line_number}: note {midi} is outside 0–127"));
+            }
+            Ok(NoteEvent {
+                midi: midi as i32,
+                start_ms: parse(start_ms, "start_ms")?,
+                duration_ms: parse(duration_ms, "duration_ms")?,
+            })
+        })
+        .collect()
+}
+
+pub fn write_wav(path: &Path, samples: &[f32], channels: u16, sample_rate: u32) -> Result<()> {
+    let spec = hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    };
+    let mut writer = hound::WavWriter::create(path, spec)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    for &sample in samples {
+        writer.write_sample((sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)?;
+    }
+    writer.finalize()?;
+    Ok(())
+}
+
+// The base note played for the length of the bite through the whole voice chain, as a
+// 32-bit float stereo WAV, so it can be loaded back in as a new clip.
+pub fn bounce_base_note(clip: &SampleClip, params: &NoteParams) -> Result<Vec<u8>> {
+    let clip_ms = clip.frames() as f32 * 1_000.0 / clip.sample_rate as f32;
+    let note = NoteEvent {
+        midi: params.base_midi,
+        start_ms: 0,
+        duration_ms: (clip_ms / pitch_ratio(params.base_midi, params)).ceil() as u32,
+    };
+    let rendered = render_notes(clip, &[note], params, clip.sample_rate);
+
+    let spec = hound::WavSpec {
+        channels: 2,
+        sample_rate: clip.sample_rate,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    let mut writer = hound::WavWriter::new(&mut bytes, spec)?;
+    for sample in rendered {
+        writer.write_sample(sample)?;
+    }
+    writer.finalize()?;
+    Ok(bytes.into_inner())
+}
+
+// Renders one WAV per key into `dir` plus an `.sfz` mapping each to its own key, so the
+// instrument plays the same in other samplers. Returns the path of the `.sfz` file.
+pub fn export_instrument(
+    clip: &SampleClip,
+    params: &NoteParams,
+    keys: std::ops::RangeInclusive<i32>,
+    dir: &Path,
+    name: &str,
+) -> Result<PathBuf> {
+    let samples_dir = dir.join("samples");
+    fs::create_dir_all(&samples_dir)
+        .with_context(|| format!("failed to create folder {}", samples_dir.display()))?;
+    let clip_ms = clip.frames() as f32 * 1_000.0 / clip.sample_rate as f32;
+    let mut sfz = format!("// {name}, exported from OpenWah\n<control>\ndefault_path=samples/\n\n");
+
+    for midi in keys {
+        // Higher notes play the bite faster, so they finish sooner.
+        let duration_ms = (clip_ms / pitch_ratio(midi, params)).ceil() as u32;
+        let note = NoteEvent {
+            midi,
+            start_ms: 0,
+            duration_ms,
+        };
+        let rendered = render_notes(clip, &[note], params, clip.sample_rate);
+        let file_name = format!(
+            "{name}_{midi:03}_{}.wav",
+            midi_note_name(midi, false).replace('#', "s")
+        );
+        write_wav(
+            &samples_dir.join(&file_name),
+            &rendered,
+            2,
+            clip.sample_rate,
+        )?;
+        sfz.push_str(&format!(
+            "<region> sample={file_name} lokey={midi} hikey={midi} pitch_keycenter={midi}\n"
+        ));
+    }
+
+    let sfz_path = dir.join(format!("{name}.sfz"));
+    fs::write(&sfz_path, sfz).with_context(|| format!("failed to write {}", sfz_path.display()))?;
+    Ok(sfz_path)
+}
+
+pub fn run_cli(args: &[String]) -> Result<()> {
+    let usage = "usage: OpenWah --render <notes.txt> <out.wav> [--clip <file>] [--bite-ms <ms>] [--base-note <midi>]";
+    let [notes_path, out_path, options @ ..] = args else {
+        return Err(anyhow!(usage));
+    };
+
+    let mut clip_path: Option<PathBuf> = None;
+    let mut bite_ms = DEFAULT_BITE_MS;
+    let mut params = NoteParams::default();
+
+    let mut options = options.iter();
+    while let Some(flag) = options.next() {
+        let value = options
+            .next()
+            .ok_or_else(|| anyhow!("{flag} needs a value\n{usage}"))?;
+        match flag.as_str() {
+            "--clip" => clip_path = Some(PathBuf::from(value)),
+            "--bite-ms" => {
+                bite_ms = value
+                    .parse::<u32>()
+                    .context("invalid --bite-ms")?
+                    .clamp(MIN_BITE_MS, MAX_BITE_MS)
+            }
+            "--base-note" => params.base_midi = value.parse().context("invalid --base-note")?,
+            other => return Err(anyhow!("unknown option {other}\n{usage}")),
+        }
+    }
+
+    let clip = match &clip_path {
+        Some(path) => SampleClip::from_file(path, bite_ms, false, None, None)?,
+        None => SampleClip::generated_test_tone(bite_ms, &ToneSettings::default()),
+    };
+    let text = fs::read_to_string(notes_path)
+        .with_context(|| format!("failed to read notes file: {notes_path}"))?;
+    let notes = parse_notes(&text)?;
+    if notes.is_empty() {
+        return Err(anyhow!("notes file contains no notes"));
+    }
+
+    let rendered = render_notes(&clip, &notes, &params, clip.sample_rate);
+    write_wav(Path::new(out_path), &rendered, 2, clip.sample_rate)?;
+    println!(
+        "Rendered {} notes ({:.2} s) to {out_path}",
+        notes.len(),
+        rendered.len() as f32 / 2.0 / clip.sample_rate as f32
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_two_note_sequence_to_expected_length() {
+        let clip = SampleClip::generated_test_tone(500, &ToneSettings::default());
+        let params = NoteParams::default();
+        let notes = parse_notes("60,0,250\n64,500,250\n").unwrap();
+
+        let rendered = render_notes(&clip, &notes, &params, 44_100);
+
+        let expected_frames = 44_100 * (500 + 250 + params.release_ms as usize) / 1_000;
+        assert_eq!(rendered.len(), expected_frames * 2);
+        let second_note = 44_100 * 550 / 1_000 * 2;
+        assert!(rendered[second_note..].iter().any(|s| s.abs() > 0.1));
+    }
+
+    #[test]
+    fn bounced_base_note_loads_back_as_the_bite() {
+        let clip = SampleClip::generated_test_tone(500, &ToneSettings::default());
+        let bytes = bounce_base_note(&clip, &NoteParams::default()).unwrap();
+        let memory = clip::MemoryClip {
+            name: "bounce.wav".to_string(),
+            bytes: Arc::new(bytes),
+            extension: Some("wav".to_string()),
+        };
+
+        let bounced = SampleClip::from_memory(&memory, 500, true, None, None).unwrap();
+
+        assert_eq!(bounced.channels, 2);
+        assert_eq!(bounced.frames(), clip.frames());
+        assert!(bounced.samples.iter().any(|s| s.abs() > 0.1));
+    }
+
+    #[test]
+    fn attack_fade_starts_voices_from_silence() {
+        // A clip that starts at full level, as a bite cut mid-waveform would.
+        let clip = SampleClip {
+            sample_rate: 44_100,
+            channels: 1,
+            samples: Arc::new(vec![0.8; 4_410]),
+            root_midi: None,
+            loop_frames: None,
+            loudness_db: None,
+            decoded_frames: 4_410,
+            truncated: false,
+            skipped_packets: 0,
+            track_id: None,
+            tracks: Vec::new(),
+            cue_frames: Vec::new(),
+        };
+        let first_sample = |attack_ms| {
+            let params = NoteParams {
+                attack_ms,
+                ..NoteParams::default()
+            };
+            releasable_voice(&clip, params.base_midi, &params, dsp::ReleaseHandle::new())
+                .next()
+                .unwrap()
+                .abs()
+        };
+
+        assert!(first_sample(0) > 0.1);
+        assert!(first_sample(crate::DEFAULT_ATTACK_MS) < 1e-3);
+    }
+
+    #[test]
+    fn retrigger_crossfades_without_discontinuity() {
+        let clip = SampleClip::generated_test_tone(500, &ToneSettings::default());
+        let params = NoteParams::default();
+        // Largest step between neighbouring frames of one channel.
+        let max_step = |samples: &[f32]| {
+            samples
+                .iter()
+                .zip(samples.iter().skip(2))
+                .map(|(a, b)| (b - a).abs())
+                .fold(0.0f32, f32::max)
+        };
+
+        let lead: Vec<f32> =
+            releasable_voice(&clip, params.base_midi, &params, dsp::ReleaseHandle::new())
+                .take(8_000)
+                .collect();
+        // Retrigger on the loudest frame, where an abrupt stop would jump the furthest.
+        let peak = lead
+            .iter()
+            .enumerate()
+            .step_by(2)
+            .max_by(|a, b| a.1.abs().total_cmp(&b.1.abs()))
+            .map(|(index, _)| index)
+            .unwrap();
+        let natural_step = max_step(&lead);
+        assert!(lead[peak].abs() > 10.0 * natural_step);
+
+        let first_release = dsp::ReleaseHandle::new();
+        let mut first = releasable_voice(&clip, params.base_midi, &params, first_release.clone());
+        let mut output: Vec<f32> = first.by_ref().take(peak).collect();
+        first_release.release(params.smoothing_ms, params.release_curve);
+        let tail: Vec<f32> = first.collect();
+        let second = releasable_voice(&clip, params.base_midi, &params, dsp::ReleaseHandle::new());
+        output.extend(
+            second
+                .take(8_000)
+                .enumerate()
+                .map(|(index, sample)| sample + tail.get(index).copied().unwrap_or(0.0)),
+        );
+
+        // Two overlapping voices can at most double the slope; a cut would be far larger.
+        let step = max_step(&output[peak - 2..]);
+        assert!(
+            step <= 2.0 * natural_step + 0.01,
+            "step {step} vs natural {natural_step}"
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use anyhow::{anyhow, Result};
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SamplerInfo {
+    pub sample_rate: Option<u32>,
+    pub unity_note: Option<i32>,
+    pub loop_frames: Option<(usize, usize)>,
+    pub cue_frames: Vec<usize>,
+}
+
+pub fn read_sampler_info(path: &Path) -> Result<Option<SamplerInfo>> {
+    scan_sampler_info(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn sampler_info_from_bytes(bytes: &[u8]) -> Result<Option<SamplerInfo>> {
+    scan_sampler_info(&mut Cursor::new(bytes))
+}
+
+fn scan_sampler_info(reader: &mut (impl Read + Seek)) -> Result<Option<SamplerInfo>> {
+    let mut header = [0u8; 12];
+    if reader.read_exact(&mut header).is_err() {
+        return Ok(None);
+    }
+    match (&header[0..4], &header[8..12]) {
+        (b"RIFF", b"WAVE") => scan_wave(reader),
+        (b"FORM", b"AIFF" | b"AIFC") => scan_aiff(reader),
+        (b"fLaC", _) => {
+            reader.seek(SeekFrom::Start(4))?;
+            scan_flac(reader)
+        }
+        _ => Ok(None),
+    }
+}
+
+fn scan_wave(reader: &mut (impl Read + Seek)) -> Result<Option<SamplerInfo>> {
+    let mut collector = WaveChunks::default();
+    while let Some((id, size)) = next_chunk(reader, u32::from_le_bytes)? {
+        if !WaveChunks::wants(&id) {
+            reader.seek(SeekFrom::Current(i64::from(size) + i64::from(size & 1)))?;
+            continue;
+        }
+        let body = read_body(reader, size)?;
+        reader.seek(SeekFrom::Current(i64::from(size & 1)))?;
+        collector.add(&id, &body)?;
+    }
+    Ok(collector.finish())
+}
+
+// FLAC keeps foreign WAVE chunks (flac --keep-foreign-metadata) in APPLICATION blocks tagged "riff".
+fn scan_flac(reader: &mut (impl Read + Seek)) -> Result<Option<SamplerInfo>> {
+    const APPLICATION: u8 = 2;
+    let mut collector = WaveChunks::default();
+    loop {
+        let mut block_header = [0u8; 4];
+        if reader.read_exact(&mut block_header).is_err() {
+            break;
+        }
+        let is_last = block_header[0] & 0x80 != 0;
+        let size = u32::from_be_bytes([0, block_header[1], block_header[2], block_header[3]]);
+        if block_header[0] & 0x7f == APPLICATION && size >= 12 {
+            let body = read_body(reader, size)?;
+            let chunk_id = [body[4], body[5], body[6], body[7]];
+            if &body[0..4] == b"riff" && WaveChunks::wants(&chunk_id) {
+                let chunk_size = le_u32(&body[8..12]) as usize;
+                let end = (12 + chunk_size).min(body.len());
+                collector.add(&chunk_id, &body[12..end])?;
+            }
+        } else {
+            reader.seek(SeekFrom::Current(i64::from(size)))?;
+        }
+        if is_last {
+            break;
+        }
+    }
+    Ok(collector.finish())
+}
+
+// AIFF stores the loop in INST as marker ids, which MARK resolves to frame positions.
+fn scan_aiff(reader: &mut (impl Read + Seek)) -> Result<Option<SamplerInfo>> {
+    let mut markers: Vec<(u16, usize)> = Vec::new();
+    let mut instrument: Option<(i32, Option<(u16, u16)>)> = None;
+    while let Some((id, size)) = next_chunk(reader, u32::from_be_bytes)? {
+        if &id != b"MARK" && &id != b"INST" {
+            reader.seek(SeekFrom::Current(i64::from(size) + i64::from(size & 1)))?;
+            continue;
+        }
+        let body = read_body(reader, size)?;
+        reader.seek(SeekFrom::Current(i64::from(size & 1)))?;
+        if &id == b"MARK" {
+            markers = parse_mark(&body)?;
+        } else {
+            instrument = Some(parse_inst(&body)?);
+        }
+    }
+
+    let marker_frame = |id: u16| {
+        markers
+            .iter()
+            .find(|&&(marker, _)| marker == id)
+            .map(|&(_, frame)| frame)
+    };
+    let sustain_loop = instrument.and_then(|(_, sustain_loop)| sustain_loop);
+    let loop_frames = sustain_loop
+        .and_then(|(begin, end)| Some((marker_frame(begin)?, marker_frame(end)?)))
+        .filter(|&(start, end)| end > start);
+    // Markers that only bound the loop are not slice points.
+    let mut cue_frames: Vec<usize> = markers
+        .iter()
+        .filter(|&&(id, _)| sustain_loop.is_none_or(|(begin, end)| id != begin && id != end))
+        .map(|&(_, frame)| frame)
+        .collect();
+    cue_frames.sort_unstable();
+    cue_frames.dedup();
+    if instrument.is_none() && cue_frames.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(SamplerInfo {
+        sample_rate: None,
+        unity_note: instrument.map(|(note, _)| note),
+        loop_frames,
+        cue_frames,
+    }))
+}
+
+#[derive(Default)]
+struct WaveChunks {
+    info: SamplerInfo,
+    found: bool,
+}
+
+impl WaveChunks {
+    fn wants(id: &[u8; 4]) -> bool {
+        id == b"smpl" || id == b"cue "
+    }
+
+    fn add(&mut self, id: &[u8; 4], body: &[u8]) -> Result<()> {
+        if id == b"smpl" {
+            self.info = SamplerInfo {
+                cue_frames: std::mem::take(&mut self.info.cue_frames),
+                ..parse_smpl(body)?
+            };
+        } else {
+            self.info.cue_frames = parse_cue(body)?;
+        }
+        self.found = true;
+        Ok(())
+    }
+
+    fn finish(self) -> Option<SamplerInfo> {
+        self.found.then_some(self.info)
+    }
+}
+
+fn next_chunk(
+    reader: &mut impl Read,
+    read_size: fn([u8; 4]) -> u32,
+) -> Result<Option<([u8; 4], u32)>> {
+    let mut chunk_header = [0u8; 8];
+    match reader.read_exact(&mut chunk_header) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.into()),
+    }
+    let id = [
+        chunk_header[0],
+        chunk_header[1],
+        chunk_header[2],
+        chunk_header[3],
+    ];
+    let size = [
+        chunk_header[4],
+        chunk_header[5],
+        chunk_header[6],
+        chunk_header[7],
+    ];
+    Ok(Some((id, read_size(size))))
+}
+
+// Chunk sizes come from untrusted files, so the body grows with what is actually read
+// rather than being allocated up front from the header.
+fn read_body(reader: &mut impl Read, size: u32) -> Result<Vec<u8>> {
+    let mut body = Vec::new();
+    reader.take(u64::from(size)).read_to_end(&mut body)?;
+    if body.len() < size as usize {
+        return Err(anyhow!("chunk is truncated"));
+    }
+    Ok(body)
+}
+
+// Each marker is an id, a frame position, and a Pascal string padded to an even length.
+fn parse_mark(body: &[u8]) -> Result<Vec<(u16, usize)>> {
+    if body.len() < 2 {
+        return Err(anyhow!("MARK chunk is truncated"));
+    }
+    let count = u16::from_be_bytes([body[0], body[1]]);
+    let mut markers = Vec::with_capacity(usize::from(count));
+    let mut offset = 2;
+    for _ in 0..count {
+        let Some(marker) = body.get(offset..offset + 7) else {
+            return Err(anyhow!("MARK chunk is truncated"));
+        };
+        let id = u16::from_be_bytes([marker[0], marker[1]]);
+        let position = u32::from_be_bytes([marker[2], marker[3], marker[4], marker[5]]);
+        let name_len = usize::from(marker[6]);
+        markers.push((id, position as usize));
+        offset += 6 + (1 + name_len).next_multiple_of(2);
+    }
+    Ok(markers)
+}
+
+// Returns the base note and, when its play mode is not "no looping", the sustain loop's markers.
+fn parse_inst(body: &[u8]) -> Result<(i32, Option<(u16, u16)>)> {
+    if body.len() < 20 {
+        return Err(anyhow!("INST chunk is truncated"));
+    }
+    let base_note = i32::from(body[0].min(127));
+    let play_mode = u16::from_be_bytes([body[8], body[9]]);
+    let begin = u16::from_be_bytes([body[10], body[11]]);
+    let end = u16::from_be_bytes([body[12], body[13]]);
+    Ok((base_note, (play_mode != 0).then_some((begin, end))))
+}
+
+fn parse_smpl(body: &[u8]) -> Result<SamplerInfo> {
+    if body.len() < 36 {
+        return Err(anyhow!("smpl chunk is truncated"));
+    }
+
+    let sample_period_ns = le_u32(&body[8..12]);
+    let unity_note = le_u32(&body[12..16]);
+    let loop_count = le_u32(&body[28..32]) as usize;
+
+    let loop_frames = if loop_count > 0 && body.len() >= 36 + 24 {
+        let first_loop = &body[36..60];
+        let start = le_u32(&first_loop[8..12]) as usize;
+        let end = le_u32(&first_loop[12..16]) as usize;
+        (end >= start).then_some((start, end + 1))
+    } else {
+        None
+    };
+
+    Ok(SamplerInfo {
+        sample_rate: (sample_period_ns > 0)
+            .then(|| (1_000_000_000.0 / sample_period_ns as f64).round() as u32),
+        unity_note: (unity_note <= 127).then_some(unity_note as i32),
+        loop_frames,
+        cue_frames: Vec::new(),
+    })
+}
+
+// Each cue point is 24 bytes; its last field is the frame offset into the data chunk.
+fn parse_cue(body: &[u8]) -> Result<Vec<usize>> {
+    if body.len() < 4 {
+        return Err(anyhow!("cue chunk is truncated"));
+    }
+    let count = le_u32(&body[0..4]) as usize;
+    let mut frames: Vec<usize> = body[4..]
+        .chunks_exact(24)
+        .take(count)
+        .map(|point| le_u32(&point[20..24]) as usize)
+        .collect();
+    frames.sort_unstable();
+    frames.dedup();
+    Ok(frames)
+}
+
+fn le_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
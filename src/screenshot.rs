@@ -0,0 +1,30 @@
+use std::{fs::File, io::BufWriter, path::Path};
+
+use anyhow::{Context, Result};
+use eframe::egui::{ColorImage, Pos2, Rect, Vec2};
+
+// Crops a viewport screenshot to `region` (in points) and writes it as an RGBA PNG.
+pub fn save_region(
+    image: &ColorImage,
+    region: Rect,
+    pixels_per_point: f32,
+    path: &Path,
+) -> Result<()> {
+    let [width, height] = image.size;
+    let bounds = Rect::from_min_size(
+        Pos2::ZERO,
+        Vec2::new(width as f32, height as f32) / pixels_per_point,
+    );
+    let cropped = image.region(&region.intersect(bounds), Some(pixels_per_point));
+
+    let file = File::create(path)
+        .with_context(|| format!("failed to create image: {}", path.display()))?;
+    let [width, height] = cropped.size;
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width as u32, height as u32);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(cropped.as_raw())?;
+    writer.finish()?;
+    Ok(())
+}
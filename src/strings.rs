@@ -0,0 +1,1271 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Self; 2] = [Self::English, Self::German];
+
+    // Each language names itself so the picker stays readable whatever is selected.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::English => "English",
+            Self::German => "Deutsch",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Text {
+    Heading,
+    Intro,
+    OpenClip,
+    OpenFolder,
+    ImportRaw,
+    OpenUrl,
+    SampleLibrary,
+    SampleLibraryHint,
+    UseTestTone,
+    UseTestToneHint,
+    Loading,
+    Cancel,
+    LoadCancelled,
+    AllNotesOff,
+    AllNotesOffHint,
+    AllNotesOffDone,
+    Current,
+    SavePreset,
+    LoadPreset,
+    ExportInstrument,
+    Preset,
+    Language,
+    Layers,
+    Sequencer,
+    ScrubPreview,
+    Oscilloscope,
+    Freeze,
+    EffectSnapshots,
+    BitCrusher,
+    Overdrive,
+    Reverb,
+    WahFilter,
+    AutoWah,
+    Tremolo,
+    NoteTrims,
+    KeyZones,
+    Release,
+    AudioOutput,
+    OutputLimiter,
+    MidiOutput,
+    MidiInput,
+    OscControl,
+    TestTone,
+    StatusLog,
+    LoadBusy,
+    ClipboardNoFile,
+    ClipboardNotAudio,
+    ClipboardBadData,
+    OscStopped,
+    FilterRecording,
+    PitchTooShort,
+    TestToneLoaded,
+    NothingToExport,
+    ExportedInstrument,
+    ExportFailed,
+    ResampleFailed,
+    SavedPreset,
+    PresetSaveFailed,
+    PresetLoadFailed,
+    PresetMissingFile,
+    LoadedPreset,
+    PresetFileFilter,
+    UnsavedChanges,
+    UnsavedChangesBody,
+    Save,
+    Discard,
+    FoundAudioFiles,
+    OpenFolderFailed,
+    ClipLoadFailed,
+    ClipLoaded,
+    Stereo,
+    Mono,
+    BaseNoteFromFile,
+    LoopFramesStatus,
+    LevelStatus,
+    DecodedPartly,
+    SkippedPackets,
+    PreviewFailed,
+    PlaybackError,
+    MidiOutError,
+    RoundRobinRemoved,
+    PerformanceModeOff,
+    ResampledBase,
+    OscListening,
+    OscError,
+    OscUnknownParam,
+    MidiInError,
+    MidiInListening,
+    ProgramChangeIgnored,
+    MidiOutSending,
+    FullFileFailed,
+    NudgeNeedsFile,
+    NoTransients,
+    FoundTransients,
+    FilterRecorded,
+    LatencySwitched,
+    LatencySwitchFailed,
+    PitchUnclear,
+    PitchDetected,
+    ScratchWavOff,
+    WaveformImageFailed,
+    NoScreenshot,
+    WaveformImageSaved,
+    LatencyStable,
+    LatencyLow,
+    PlayOneShot,
+    PlayGate,
+    ChordMajor,
+    ChordMinor,
+    ChordDominant7,
+    ChordMajor7,
+    ChordMinor7,
+    RoundRobinSequential,
+    RoundRobinRandom,
+    CueOff,
+    CueSingle,
+    CueKit,
+    ScaleOff,
+    ScaleMajor,
+    ScaleMinor,
+    ScaleMajorPentatonic,
+    ScaleMinorPentatonic,
+    AccidentalsSharps,
+    AccidentalsFlats,
+    AccidentalsFollowKey,
+    NoteLabelsNames,
+    NoteLabelsNumbers,
+    NoteLabelsBoth,
+    NamingLetters,
+    NamingFixedDo,
+    NamingMovableDo,
+    LayerSum,
+    LayerConcatenate,
+    WaveformClassic,
+    WaveformSine,
+    WaveformSquare,
+    WaveformSaw,
+    WaveformTriangle,
+    CurveLinear,
+    CurveExponential,
+    LfoSine,
+    LfoSquare,
+    DriveSoftClip,
+    DriveHardClip,
+    DriveAsymmetric,
+    InterpNearest,
+    InterpLinear,
+    InterpCubic,
+    RawU8,
+    RawS16,
+    RawS24,
+    RawS32,
+    RawF32,
+    TrackLabel,
+    DefaultTrack,
+    AudioTrack,
+    FallbackSynth,
+    BiteStart,
+    NudgeEarlier,
+    NudgeLater,
+    NudgeStep,
+    NudgeSamples,
+    Transients,
+    CueMarkers,
+    CueLabel,
+    CueModeHint,
+    DecodeUpTo,
+    DecodeUpToHint,
+    FileLonger,
+    ScrubPosition,
+    StopSpace,
+    PlaySpace,
+    Sensitivity,
+    SensitivityHint,
+    AutoSlice,
+    AutoSliceHint,
+    UseCueMarkers,
+    PlayFromScrub,
+    PlayFromScrubHint,
+    SnapshotName,
+    StoreEffects,
+    SnapshotDefaultName,
+    RecallStopsNotes,
+    RecallStopsNotesHint,
+    RecallKey,
+    Update,
+    UpdateHint,
+    Remove,
+    RoundRobin,
+    AddClip,
+    AddClipHint,
+    LoadingShort,
+    AddLayer,
+    DragToReorder,
+    RemoveLayer,
+    MuteLayer,
+    SoloLayer,
+    ChordMode,
+    ChordModeHint,
+    ChordRoot,
+    PlayOnRoot,
+    Stop,
+    Play,
+    AddRow,
+    Clear,
+    SixteenthsAt,
+    RemoveRow,
+    ImportRawTitle,
+    SampleRate,
+    SampleFormat,
+    Channels,
+    ByteOrder,
+    LittleEndian,
+    BigEndian,
+    Import,
+    ExportInstrumentHint,
+    ResampleToBite,
+    ResampleToBiteHint,
+    RestoreOriginal,
+    RestoreOriginalHint,
+    ScratchWav,
+    ScratchWavHint,
+    CopyPath,
+    NoZones,
+    ZoneTo,
+    AddZone,
+    AddZoneHint,
+    PreserveStereo,
+    RemoveDc,
+    RemoveDcHint,
+    FillLoop,
+    FillLoopHint,
+    AutoLevel,
+    AutoLevelHint,
+    BiteGainComp,
+    BiteGainCompHint,
+    AutoPreview,
+    AutoPreviewHint,
+    ReverseAttack,
+    ReverseAttackHint,
+    ReverseLength,
+    BaseNote,
+    BaseNoteHint,
+    DetectPitch,
+    DetectPitchHint,
+    StopReference,
+    PlayReference,
+    ReferenceHint,
+    Detune,
+    Transpose,
+    TransposeHint,
+    Interpolation,
+    InterpolationHint,
+    PerformanceMode,
+    PerformanceModeHint,
+    Caching,
+    AllKeysCached,
+    Pan,
+    RandomPan,
+    StereoWidth,
+    StereoWidthHint,
+    StereoWidthDisabled,
+    HoldVelocity,
+    HoldVelocityHint,
+    NextVelocity,
+    Glissando,
+    GlissandoHint,
+    DragBend,
+    DragBendHint,
+    BendRange,
+    BendAmount,
+    BlackKeyArea,
+    BlackKeyAreaHint,
+    Humanize,
+    HumanizeHint,
+    Amount,
+    Seed,
+    Restart,
+    RestartHumanizeHint,
+    StartJitter,
+    StartJitterHint,
+    SeedPrefix,
+    RestartJitterHint,
+    Quantize,
+    Grid,
+    Tap,
+    TapHint,
+    TapKey,
+    TapBpm,
+    KeepTapping,
+    FreezeHeld,
+    FreezeHeldHint,
+    GrainSize,
+    PositionInBite,
+    BypassEffects,
+    BypassEffectsHint,
+    DryMatch,
+    ActiveSnapshot,
+    CrushNotes,
+    CrushNotesHint,
+    BitDepth,
+    Downsample,
+    DryWet,
+    OverdriveNotes,
+    OverdriveNotesHint,
+    Curve,
+    Drive,
+    OutputLevel,
+    ReverbHint,
+    RoomSize,
+    Damping,
+    Enable,
+    Cutoff,
+    Resonance,
+    Recording,
+    Armed,
+    Record,
+    RecordHint,
+    PlayAutomation,
+    AutomationPoints,
+    AutoWahHint,
+    Low,
+    High,
+    Attack,
+    SyncToBpm,
+    Rate,
+    Depth,
+    TrimHint,
+    AttackFade,
+    AttackFadeHint,
+    ReleaseTime,
+    ReleaseTimeHint,
+    Smoothing,
+    SmoothingHint,
+    LatencyHint,
+    Threshold,
+    MakeupGain,
+    SendNotesTo,
+    NoPort,
+    Rescan,
+    SwitchPresetsFrom,
+    SwitchPresetsHint,
+    ProgramPreset,
+    ListenUdp,
+    AllowOtherDevices,
+    AllowOtherDevicesHint,
+    ListeningOn,
+    Stopped,
+    Waveform,
+    DefaultPreset,
+    BaseFrequency,
+    Harmonics,
+    ToneChangesHint,
+    OverrideRate,
+    OverrideRateHint,
+    WaveformHint,
+    TestToneName,
+    WaveformCaption,
+    PngImage,
+    SavePng,
+    SavePngHint,
+    Zoom,
+    ZoomHint,
+    LoopLabel,
+    ClearLoop,
+    AddLoop,
+    SnapZero,
+    SnapZeroHint,
+    Unfreeze,
+    FreezeScope,
+    HighCpuEffects,
+    HighCpu,
+    VoiceCount,
+    VoiceStolen,
+    NoHeldVoices,
+    Releasing,
+    MaxVoices,
+    MaxVoicesHint,
+    TransposeAmount,
+    PeakHint,
+    StereoMeter,
+    Compact,
+    CompactHint,
+    SoundBite,
+    MoreControls,
+    CloseFolder,
+    Previous,
+    Next,
+    BrowserEntryHint,
+    Piano,
+    KeyLabels,
+    MovableDoHint,
+    HighlightScale,
+    FollowKeyHint,
+    UsingTestTone,
+    Shortcuts,
+    OpenFromUrl,
+    Load,
+    TrimNote,
+    Reset,
+}
+
+// Strings containing `{}` are templates; callers fill them with `fill`.
+pub fn get(language: Language, text: Text) -> &'static str {
+    match language {
+        Language::English => english(text),
+        Language::German => german(text),
+    }
+}
+
+pub fn fill(template: &str, value: impl std::fmt::Display) -> String {
+    template.replacen("{}", &value.to_string(), 1)
+}
+
+// Fills every `{}` in order; values are never themselves searched for placeholders.
+pub fn fill_all(template: &str, values: &[&dyn std::fmt::Display]) -> String {
+    let mut values = values.iter();
+    let mut text = String::new();
+    for (index, part) in template.split("{}").enumerate() {
+        if index > 0 {
+            if let Some(value) = values.next() {
+                text.push_str(&value.to_string());
+            }
+        }
+        text.push_str(part);
+    }
+    text
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::Heading => "OpenWah – Soundbite Piano",
+        Text::Intro => {
+            "1) Set bite duration  2) Load any clip  3) The chosen slice becomes base note ({})."
+        }
+        Text::OpenClip => "Open Sound Clip...",
+        Text::OpenFolder => "Open Folder...",
+        Text::ImportRaw => "Import Raw PCM...",
+        Text::OpenUrl => "Open from URL...",
+        Text::SampleLibrary => "Sample Library",
+        Text::SampleLibraryHint => "Play one of the built-in one-shots",
+        Text::UseTestTone => "Use Test Tone",
+        Text::UseTestToneHint => "Go back to the generated reference tone (Ctrl+T)",
+        Text::Loading => "Loading {}...",
+        Text::Cancel => "Cancel",
+        Text::LoadCancelled => "Load cancelled.",
+        Text::AllNotesOff => "All Notes Off",
+        Text::AllNotesOffHint => "Silence every playing note (Esc)",
+        Text::AllNotesOffDone => "All notes off.",
+        Text::Current => "Current: {}",
+        Text::SavePreset => "Save Preset...",
+        Text::LoadPreset => "Load Preset...",
+        Text::ExportInstrument => "Export Instrument...",
+        Text::Preset => "Preset",
+        Text::Language => "Language",
+        Text::Layers => "Layers",
+        Text::Sequencer => "Sequencer",
+        Text::ScrubPreview => "Scrub preview",
+        Text::Oscilloscope => "Oscilloscope",
+        Text::Freeze => "Freeze (granular sustain)",
+        Text::EffectSnapshots => "Effect snapshots",
+        Text::BitCrusher => "Bit-crusher",
+        Text::Overdrive => "Overdrive",
+        Text::Reverb => "Reverb",
+        Text::WahFilter => "Wah filter",
+        Text::AutoWah => "Auto-wah",
+        Text::Tremolo => "Tremolo",
+        Text::NoteTrims => "Note trims",
+        Text::KeyZones => "Key zones",
+        Text::Release => "Release",
+        Text::AudioOutput => "Audio output",
+        Text::OutputLimiter => "Output limiter",
+        Text::MidiOutput => "MIDI output",
+        Text::MidiInput => "MIDI input",
+        Text::OscControl => "OSC control",
+        Text::TestTone => "Test tone generator",
+        Text::StatusLog => "Status log",
+        Text::LoadBusy => "A clip is already loading; paste again when it finishes.",
+        Text::ClipboardNoFile => "Clipboard does not contain an audio file or audio data.",
+        Text::ClipboardNotAudio => "Pasted file is not a supported audio format: {}",
+        Text::ClipboardBadData => "Could not read the pasted audio data: {}",
+        Text::OscStopped => "OSC server stopped.",
+        Text::FilterRecording => "Recording filter sweep: drag Cutoff now.",
+        Text::PitchTooShort => "Clip is too short to detect a pitch.",
+        Text::TestToneLoaded => "Loaded generated {} ms test tone. Open a file to replace it.",
+        Text::NothingToExport => "Nothing to export yet.",
+        Text::ExportedInstrument => "Exported instrument to {}",
+        Text::ExportFailed => "Export failed: {}",
+        Text::ResampleFailed => "Resample failed: {}",
+        Text::SavedPreset => "Saved preset {}.",
+        Text::PresetSaveFailed => "Could not save preset: {}",
+        Text::PresetLoadFailed => "Could not load preset: {}",
+        Text::PresetMissingFile => {
+            "Preset {} references a missing file ({}); keeping current state."
+        }
+        Text::LoadedPreset => "Loaded preset {}. {}",
+        Text::PresetFileFilter => "OpenWah preset",
+        Text::UnsavedChanges => "Unsaved changes",
+        Text::UnsavedChangesBody => "You have unsaved changes to this preset.",
+        Text::Save => "Save",
+        Text::Discard => "Discard",
+        Text::FoundAudioFiles => "Found {} audio file(s) in {}.",
+        Text::OpenFolderFailed => "Failed to open folder: {}",
+        Text::ClipLoadFailed => "Could not load clip: {}",
+        Text::ClipLoaded => "Loaded {} ({} Hz, {}). First {} ms is now mapped across C3–C6.",
+        Text::Stereo => "stereo",
+        Text::Mono => "mono",
+        Text::BaseNoteFromFile => " Base note set to {} from the file's sampler metadata.",
+        Text::LoopFramesStatus => " Loop frames {}–{}.",
+        Text::LevelStatus => " Level {} dBFS RMS.",
+        Text::DecodedPartly => " Decoded {} of {} ms; rest is silence.",
+        Text::SkippedPackets => " Skipped {} corrupt packet(s).",
+        Text::PreviewFailed => "Preview failed: {}",
+        Text::PlaybackError => "Playback error: {}",
+        Text::MidiOutError => "MIDI out error: {}",
+        Text::RoundRobinRemoved => "Removed round-robin clip {}: {}",
+        Text::PerformanceModeOff => "Performance mode off: {}",
+        Text::ResampledBase => "Resampled the base note into the bite. Effects, detune and transpose were reset so they aren't applied twice; Restore original brings everything back.",
+        Text::OscListening => "Listening for OSC on {}",
+        Text::OscError => "OSC error: {}",
+        Text::OscUnknownParam => "Unknown OSC parameter /param/{}",
+        Text::MidiInError => "MIDI in error: {}",
+        Text::MidiInListening => "Listening for program changes on {}",
+        Text::ProgramChangeIgnored => "Ignored program change {}: only {} preset(s) loaded.",
+        Text::MidiOutSending => "Sending MIDI to {}",
+        Text::FullFileFailed => "Could not load the full file: {}",
+        Text::NudgeNeedsFile => "Nudging the bite start needs a loaded file.",
+        Text::NoTransients => "No transients found; raise the sensitivity and try again.",
+        Text::FoundTransients => "Found {} transient(s); each slice is on its own key.",
+        Text::FilterRecorded => "Recorded {} filter points over {} s.",
+        Text::LatencySwitched => "Audio output switched to {} mode.",
+        Text::LatencySwitchFailed => "Could not switch to {}: {}",
+        Text::PitchUnclear => "Pitch unclear (~{} Hz, confidence {}%); base note unchanged.",
+        Text::PitchDetected => "Detected {} Hz (confidence {}%). Base note set to {}.",
+        Text::ScratchWavOff => "Scratch WAV turned off: {}",
+        Text::WaveformImageFailed => "Could not save waveform image: {}",
+        Text::NoScreenshot => "the display did not return a screenshot",
+        Text::WaveformImageSaved => "Saved waveform image {}",
+        Text::LatencyStable => "Stable",
+        Text::LatencyLow => "Low latency",
+        Text::PlayOneShot => "One-shot",
+        Text::PlayGate => "Gate",
+        Text::ChordMajor => "Major",
+        Text::ChordMinor => "Minor",
+        Text::ChordDominant7 => "7",
+        Text::ChordMajor7 => "Maj7",
+        Text::ChordMinor7 => "m7",
+        Text::RoundRobinSequential => "Sequential",
+        Text::RoundRobinRandom => "Random",
+        Text::CueOff => "Off",
+        Text::CueSingle => "Single cue",
+        Text::CueKit => "Kit",
+        Text::ScaleOff => "Off",
+        Text::ScaleMajor => "Major",
+        Text::ScaleMinor => "Natural minor",
+        Text::ScaleMajorPentatonic => "Major pentatonic",
+        Text::ScaleMinorPentatonic => "Minor pentatonic",
+        Text::AccidentalsSharps => "Sharps (C#)",
+        Text::AccidentalsFlats => "Flats (Db)",
+        Text::AccidentalsFollowKey => "Follow key",
+        Text::NoteLabelsNames => "Names",
+        Text::NoteLabelsNumbers => "MIDI numbers",
+        Text::NoteLabelsBoth => "Both",
+        Text::NamingLetters => "Letters",
+        Text::NamingFixedDo => "Fixed Do",
+        Text::NamingMovableDo => "Movable Do",
+        Text::LayerSum => "Layer (sum)",
+        Text::LayerConcatenate => "Concatenate",
+        Text::WaveformClassic => "Classic (default)",
+        Text::WaveformSine => "Sine",
+        Text::WaveformSquare => "Square",
+        Text::WaveformSaw => "Saw",
+        Text::WaveformTriangle => "Triangle",
+        Text::CurveLinear => "Linear",
+        Text::CurveExponential => "Exponential",
+        Text::LfoSine => "Sine",
+        Text::LfoSquare => "Square",
+        Text::DriveSoftClip => "Soft clip",
+        Text::DriveHardClip => "Hard clip",
+        Text::DriveAsymmetric => "Asymmetric",
+        Text::InterpNearest => "Nearest",
+        Text::InterpLinear => "Linear",
+        Text::InterpCubic => "Cubic",
+        Text::RawU8 => "8-bit unsigned",
+        Text::RawS16 => "16-bit signed",
+        Text::RawS24 => "24-bit signed",
+        Text::RawS32 => "32-bit signed",
+        Text::RawF32 => "32-bit float",
+        Text::TrackLabel => "Track {}: {}",
+        Text::DefaultTrack => "Default",
+        Text::AudioTrack => "Audio track",
+        Text::FallbackSynth => "No clip loaded; keys play a built-in sine until one is.",
+        Text::BiteStart => "Bite start: {} ms (frame {})",
+        Text::NudgeEarlier => "Nudge the start earlier (Shift+Left)",
+        Text::NudgeLater => "Nudge the start later (Shift+Right)",
+        Text::NudgeStep => "step ",
+        Text::NudgeSamples => " samples",
+        Text::Transients => "transients",
+        Text::CueMarkers => "cue markers",
+        Text::CueLabel => "Cue {} ({} s)",
+        Text::CueModeHint => "Off plays the start of the file. Single cue maps one cue's slice across the keyboard; Kit puts one slice on each key from the base note up.",
+        Text::DecodeUpTo => "Decode up to ",
+        Text::DecodeUpToHint => "How much of the file scrubbing, cues, nudging and transient slicing keep in memory",
+        Text::FileLonger => "File is longer; only the first {} s are cached.",
+        Text::ScrubPosition => "{} s of {} s",
+        Text::StopSpace => "Stop (Space)",
+        Text::PlaySpace => "Play (Space)",
+        Text::Sensitivity => "Sensitivity",
+        Text::SensitivityHint => "Higher values find quieter hits; lower values only the loudest",
+        Text::AutoSlice => "Auto-slice by transients",
+        Text::AutoSliceHint => "Cut the file at each detected hit and map the slices to keys",
+        Text::UseCueMarkers => "Use cue markers",
+        Text::PlayFromScrub => "Play from scrub position",
+        Text::PlayFromScrubHint => "Keys play the file from the position below instead of the bite",
+        Text::SnapshotName => "Name",
+        Text::StoreEffects => "Store current effects",
+        Text::SnapshotDefaultName => "Snapshot {}",
+        Text::RecallStopsNotes => "Recall stops notes",
+        Text::RecallStopsNotesHint => "Silence playing notes when a snapshot is recalled",
+        Text::RecallKey => "Recall (key {})",
+        Text::Update => "Update",
+        Text::UpdateHint => "Overwrite with the current effects",
+        Text::Remove => "Remove",
+        Text::RoundRobin => "Round robin",
+        Text::AddClip => "Add clip...",
+        Text::AddClipHint => "Alternate this clip with the main one on repeated notes",
+        Text::LoadingShort => "loading",
+        Text::AddLayer => "Add layer...",
+        Text::DragToReorder => "Drag to reorder",
+        Text::RemoveLayer => "Remove layer",
+        Text::MuteLayer => "Mute this layer",
+        Text::SoloLayer => "Solo: only soloed layers are heard",
+        Text::ChordMode => "Chord mode",
+        Text::ChordModeHint => "Every key plays a whole chord rooted on it",
+        Text::ChordRoot => "Root",
+        Text::PlayOnRoot => "Play on the root (key {})",
+        Text::Stop => "Stop",
+        Text::Play => "Play",
+        Text::AddRow => "Add row",
+        Text::Clear => "Clear",
+        Text::SixteenthsAt => "16ths at {} BPM",
+        Text::RemoveRow => "Remove row",
+        Text::ImportRawTitle => "Import Raw PCM",
+        Text::SampleRate => "Sample rate",
+        Text::SampleFormat => "Sample format",
+        Text::Channels => "Channels",
+        Text::ByteOrder => "Byte order",
+        Text::LittleEndian => "Little-endian",
+        Text::BigEndian => "Big-endian",
+        Text::Import => "Import",
+        Text::ExportInstrumentHint => "Render every key to WAV files with an .sfz map for other samplers",
+        Text::ResampleToBite => "Resample to bite",
+        Text::ResampleToBiteHint => "Replace the bite with the base note as it sounds now, effects and pitch included. Destructive: the original can only be restored until another clip is loaded.",
+        Text::RestoreOriginal => "Restore original",
+        Text::RestoreOriginalHint => "Go back to the clip and settings from before resampling",
+        Text::ScratchWav => "Mirror bite to scratch WAV",
+        Text::ScratchWavHint => "Rewrite a fixed WAV file whenever the bite changes, for tools that watch it",
+        Text::CopyPath => "Copy path",
+        Text::NoZones => "Without zones every key plays one-shot.",
+        Text::ZoneTo => "to",
+        Text::AddZone => "Add zone",
+        Text::AddZoneHint => "Gate zones stop on key release; one-shot zones play to the end",
+        Text::PreserveStereo => "Preserve stereo",
+        Text::RemoveDc => "Remove DC offset",
+        Text::RemoveDcHint => "Subtract the clip's average so a biased recording is centred on zero",
+        Text::FillLoop => "Fill by looping",
+        Text::FillLoopHint => "When the clip is shorter than the bite, repeat it with crossfades instead of padding with silence",
+        Text::AutoLevel => "Auto-level clips",
+        Text::AutoLevelHint => "Match playback loudness across clips using the measured RMS level",
+        Text::BiteGainComp => "Hold level across bite lengths",
+        Text::BiteGainCompHint => "Re-measure loudness on each re-slice and keep it at the level the clip loaded with (auto-level already does this)",
+        Text::AutoPreview => "Auto-preview",
+        Text::AutoPreviewHint => "Play the bite once whenever a clip is loaded or re-sliced",
+        Text::ReverseAttack => "Reverse attack",
+        Text::ReverseAttackHint => "Play the start of the bite backwards into the hit",
+        Text::ReverseLength => "Reverse length",
+        Text::BaseNote => "Base note",
+        Text::BaseNoteHint => "The key that plays the bite at its natural pitch",
+        Text::DetectPitch => "Detect pitch",
+        Text::DetectPitchHint => "Estimate the clip's fundamental and set the base note",
+        Text::StopReference => "Stop reference",
+        Text::PlayReference => "Play reference",
+        Text::ReferenceHint => "Sustain a pure sine at A4 to tune against (Esc stops it)",
+        Text::Detune => "Detune",
+        Text::Transpose => "Transpose",
+        Text::TransposeHint => "Shift every note without changing which keys you press",
+        Text::Interpolation => "Interpolation",
+        Text::InterpolationHint => "How pitched notes are resampled. Nearest is cheapest but aliases, Linear is a good default, Cubic is smoothest and costs the most CPU per voice.",
+        Text::PerformanceMode => "Performance mode",
+        Text::PerformanceModeHint => "Pre-pitch the bite for every key so notes start without resampling. Uses memory for 37 copies and re-renders after every clip or tuning change.",
+        Text::Caching => "Caching {}/{}",
+        Text::AllKeysCached => "All keys cached",
+        Text::Pan => "Pan (L/R)",
+        Text::RandomPan => "Random pan per note",
+        Text::StereoWidth => "Stereo width",
+        Text::StereoWidthHint => "0% is mono, 100% the original image, above 100% widens via mid/side",
+        Text::StereoWidthDisabled => "Load a stereo clip with Preserve stereo ticked",
+        Text::HoldVelocity => "Velocity from hold time",
+        Text::HoldVelocityHint => "Quick taps set the next note loud, long holds set it soft",
+        Text::NextVelocity => "Next velocity: {}%",
+        Text::Glissando => "Glissando",
+        Text::GlissandoHint => "Drag across the piano to play every key you pass over",
+        Text::DragBend => "Drag to bend",
+        Text::DragBendHint => "Drag up or down on a key you are holding to bend its pitch; ignored while Glissando is on",
+        Text::BendRange => "Bend range",
+        Text::BendAmount => "Bend {} st",
+        Text::BlackKeyArea => "Black key touch area",
+        Text::BlackKeyAreaHint => "Make black keys easier to tap on touch screens; they reach further into the white keys than they are drawn",
+        Text::Humanize => "Humanize",
+        Text::HumanizeHint => "Vary each note's pitch, timing, level and pan slightly",
+        Text::Amount => "Amount",
+        Text::Seed => "Seed",
+        Text::Restart => "Restart",
+        Text::RestartHumanizeHint => "Replay the same variations from the start",
+        Text::StartJitter => "Start jitter",
+        Text::StartJitterHint => "Start each note up to this much later in the file so repeated hits differ",
+        Text::SeedPrefix => "seed ",
+        Text::RestartJitterHint => "Replay the same start offsets from the beginning",
+        Text::Quantize => "Quantize triggers",
+        Text::Grid => "Grid",
+        Text::Tap => "Tap",
+        Text::TapHint => "Tap the beat to set the BPM (or press {})",
+        Text::TapKey => "Key {}",
+        Text::TapBpm => "{} BPM from {} taps",
+        Text::KeepTapping => "Keep tapping...",
+        Text::FreezeHeld => "Freeze held notes",
+        Text::FreezeHeldHint => "Held keys loop tiny grains from the bite until released",
+        Text::GrainSize => "Grain size",
+        Text::PositionInBite => "Position in bite",
+        Text::BypassEffects => "Bypass all effects",
+        Text::BypassEffectsHint => "Play dry without losing any effect settings; each effect's own checkbox bypasses just that one",
+        Text::DryMatch => "dry {} dB to match",
+        Text::ActiveSnapshot => "Snapshot: {}",
+        Text::CrushNotes => "Crush notes",
+        Text::CrushNotesHint => "Quantize amplitude and hold samples for a lo-fi sound",
+        Text::BitDepth => "Bit depth",
+        Text::Downsample => "Downsample",
+        Text::DryWet => "Dry/wet",
+        Text::OverdriveNotes => "Overdrive notes",
+        Text::OverdriveNotesHint => "Waveshaping distortion; the level stays put as drive goes up",
+        Text::Curve => "Curve",
+        Text::Drive => "Drive",
+        Text::OutputLevel => "Output level",
+        Text::ReverbHint => "Freeverb-style room; tails ring out after notes are released",
+        Text::RoomSize => "Room size",
+        Text::Damping => "Damping",
+        Text::Enable => "Enable",
+        Text::Cutoff => "Cutoff",
+        Text::Resonance => "Resonance",
+        Text::Recording => "Recording",
+        Text::Armed => "Armed",
+        Text::Record => "Record",
+        Text::RecordHint => "Arm, play a note, then drag Cutoff; click again to stop",
+        Text::PlayAutomation => "Play automation",
+        Text::AutomationPoints => "{} points, {} s",
+        Text::AutoWahHint => "Louder playing opens the filter, quieter playing closes it",
+        Text::Low => "Low",
+        Text::High => "High",
+        Text::Attack => "Attack",
+        Text::SyncToBpm => "Sync to BPM",
+        Text::Rate => "Rate",
+        Text::Depth => "Depth",
+        Text::TrimHint => "Right-click a piano key to trim its level.",
+        Text::AttackFade => "Attack fade",
+        Text::AttackFadeHint => "Ramp every note in from silence so clips that don't start at zero don't click; 0 turns it off",
+        Text::ReleaseTime => "Release time",
+        Text::ReleaseTimeHint => "Fade length when a held note is let go",
+        Text::Smoothing => "Smoothing",
+        Text::SmoothingHint => "Shortest fade used anywhere a voice is cut: retriggers, voice stealing, note-off and All Notes Off",
+        Text::LatencyHint => "Stable uses the device's default buffer and rarely glitches. Low latency asks for a 256-frame buffer so keys respond faster, but busy systems may crackle or drop out.",
+        Text::Threshold => "Threshold",
+        Text::MakeupGain => "Makeup gain",
+        Text::SendNotesTo => "Send notes to",
+        Text::NoPort => "(no port)",
+        Text::Rescan => "Rescan",
+        Text::SwitchPresetsFrom => "Switch presets from",
+        Text::SwitchPresetsHint => "Program Change 1 loads the first preset in the Preset list, 2 the second, and so on",
+        Text::ProgramPreset => "Program {}: {}",
+        Text::ListenUdp => "Listen on UDP port",
+        Text::AllowOtherDevices => "Allow other devices",
+        Text::AllowOtherDevicesHint => "Accept OSC from the whole local network instead of only this computer",
+        Text::ListeningOn => "Listening on {}",
+        Text::Stopped => "Stopped",
+        Text::Waveform => "Waveform",
+        Text::DefaultPreset => "Default preset",
+        Text::BaseFrequency => "Base frequency",
+        Text::Harmonics => "Harmonics",
+        Text::ToneChangesHint => "Changes apply when the test tone is in use.",
+        Text::OverrideRate => "Override sample rate",
+        Text::OverrideRateHint => "Treat the clip as if it were recorded at this rate, for files with a wrong header",
+        Text::WaveformHint => "Sound bite: {} ms (scroll to change, Shift for fine steps)",
+        Text::TestToneName => "Test tone",
+        Text::WaveformCaption => "{}  ·  bite {} ms from {} ms  ·  base {}  ·  {} Hz {}",
+        Text::PngImage => "PNG image",
+        Text::SavePng => "Save PNG...",
+        Text::SavePngHint => "Save the waveform view as an image, captioned with the bite settings",
+        Text::Zoom => "Zoom",
+        Text::ZoomHint => "Zoom the waveform (+ / - keys)",
+        Text::LoopLabel => "Loop: {} ms ({}–{})",
+        Text::ClearLoop => "Clear loop",
+        Text::AddLoop => "Add loop",
+        Text::SnapZero => "Snap to zero crossings",
+        Text::SnapZeroHint => "Start the bite and place loop handles on the nearest rising zero crossing",
+        Text::Unfreeze => "Unfreeze",
+        Text::FreezeScope => "Freeze",
+        Text::HighCpuEffects => "High CPU: each voice runs its own effect chain",
+        Text::HighCpu => "High CPU: may cause dropouts on slower machines",
+        Text::VoiceCount => "Voices: {}/{}",
+        Text::VoiceStolen => "voice stolen",
+        Text::NoHeldVoices => "No held voices",
+        Text::Releasing => "+{} releasing",
+        Text::MaxVoices => "Max voices: ",
+        Text::MaxVoicesHint => "How many notes can hold at once before the oldest is stolen; their release tails may add up to as many again",
+        Text::TransposeAmount => "Transpose {} st",
+        Text::PeakHint => "Peak {} dBFS (click to reset)",
+        Text::StereoMeter => "Stereo meter",
+        Text::Compact => "Compact",
+        Text::CompactHint => "Hide the help text and move secondary controls into a menu",
+        Text::SoundBite => "Sound bite (ms)",
+        Text::MoreControls => "More controls",
+        Text::CloseFolder => "Close folder",
+        Text::Previous => "Previous",
+        Text::Next => "Next",
+        Text::BrowserEntryHint => "Hold to preview, click to load",
+        Text::Piano => "Piano (C3 → C6)",
+        Text::KeyLabels => "Key labels",
+        Text::MovableDoHint => "Movable Do names notes relative to the key chosen for the scale highlight",
+        Text::HighlightScale => "Highlight scale",
+        Text::FollowKeyHint => "Follow key spells notes with flats in F, Bb, Eb, Ab and Db major and their relative minors",
+        Text::UsingTestTone => "Using generated test tone. Load a clip to replace it.",
+        Text::Shortcuts => "Keyboard shortcuts: A W S E D F T G Y H U J K · Ctrl+Z / Ctrl+Y undo/redo bite length · Ctrl+T test tone",
+        Text::OpenFromUrl => "Open from URL",
+        Text::Load => "Load",
+        Text::TrimNote => "Trim {}",
+        Text::Reset => "Reset",
+    }
+}
+
+fn german(text: Text) -> &'static str {
+    match text {
+        Text::Heading => "OpenWah – Soundschnipsel-Klavier",
+        Text::Intro => {
+            "1) Schnipsellänge wählen  2) Beliebigen Clip laden  3) Der Ausschnitt wird zum Grundton ({})."
+        }
+        Text::OpenClip => "Soundclip öffnen...",
+        Text::OpenFolder => "Ordner öffnen...",
+        Text::ImportRaw => "Roh-PCM importieren...",
+        Text::OpenUrl => "Von URL öffnen...",
+        Text::SampleLibrary => "Klangbibliothek",
+        Text::SampleLibraryHint => "Einen der eingebauten Klänge spielen",
+        Text::UseTestTone => "Testton verwenden",
+        Text::UseTestToneHint => "Zurück zum erzeugten Referenzton (Strg+T)",
+        Text::Loading => "Lade {}...",
+        Text::Cancel => "Abbrechen",
+        Text::LoadCancelled => "Laden abgebrochen.",
+        Text::AllNotesOff => "Alle Noten aus",
+        Text::AllNotesOffHint => "Alle klingenden Noten stoppen (Esc)",
+        Text::AllNotesOffDone => "Alle Noten aus.",
+        Text::Current => "Aktuell: {}",
+        Text::SavePreset => "Preset speichern...",
+        Text::LoadPreset => "Preset laden...",
+        Text::ExportInstrument => "Instrument exportieren...",
+        Text::Preset => "Preset",
+        Text::Language => "Sprache",
+        Text::Layers => "Ebenen",
+        Text::Sequencer => "Sequenzer",
+        Text::ScrubPreview => "Vorhören ab Position",
+        Text::Oscilloscope => "Oszilloskop",
+        Text::Freeze => "Freeze (granulares Halten)",
+        Text::EffectSnapshots => "Effekt-Schnappschüsse",
+        Text::BitCrusher => "Bitcrusher",
+        Text::Overdrive => "Verzerrer",
+        Text::Reverb => "Hall",
+        Text::WahFilter => "Wah-Filter",
+        Text::AutoWah => "Auto-Wah",
+        Text::Tremolo => "Tremolo",
+        Text::NoteTrims => "Notenpegel",
+        Text::KeyZones => "Tastenzonen",
+        Text::Release => "Ausklang",
+        Text::AudioOutput => "Audioausgabe",
+        Text::OutputLimiter => "Ausgangslimiter",
+        Text::MidiOutput => "MIDI-Ausgang",
+        Text::MidiInput => "MIDI-Eingang",
+        Text::OscControl => "OSC-Steuerung",
+        Text::TestTone => "Testtongenerator",
+        Text::StatusLog => "Statusprotokoll",
+        Text::LoadBusy => {
+            "Es wird bereits ein Clip geladen; bitte danach erneut einfügen."
+        }
+        Text::ClipboardNoFile => {
+            "Die Zwischenablage enthält keine Audiodatei und keine Audiodaten."
+        }
+        Text::ClipboardNotAudio => "Die eingefügte Datei ist kein unterstütztes Audioformat: {}",
+        Text::ClipboardBadData => "Die eingefügten Audiodaten sind unlesbar: {}",
+        Text::OscStopped => "OSC-Server gestoppt.",
+        Text::FilterRecording => "Filterfahrt wird aufgenommen: jetzt Cutoff ziehen.",
+        Text::PitchTooShort => "Der Clip ist zu kurz für eine Tonhöhenerkennung.",
+        Text::TestToneLoaded => "Erzeugter Testton mit {} ms geladen. Öffne eine Datei, um ihn zu ersetzen.",
+        Text::NothingToExport => "Noch nichts zum Exportieren.",
+        Text::ExportedInstrument => "Instrument exportiert nach {}",
+        Text::ExportFailed => "Export fehlgeschlagen: {}",
+        Text::ResampleFailed => "Resampling fehlgeschlagen: {}",
+        Text::SavedPreset => "Preset {} gespeichert.",
+        Text::PresetSaveFailed => "Preset konnte nicht gespeichert werden: {}",
+        Text::PresetLoadFailed => "Preset konnte nicht geladen werden: {}",
+        Text::PresetMissingFile => "Preset {} verweist auf eine fehlende Datei ({}); der aktuelle Zustand bleibt erhalten.",
+        Text::LoadedPreset => "Preset {} geladen. {}",
+        Text::PresetFileFilter => "OpenWah-Preset",
+        Text::UnsavedChanges => "Ungespeicherte Änderungen",
+        Text::UnsavedChangesBody => "Dieses Preset hat ungespeicherte Änderungen.",
+        Text::Save => "Speichern",
+        Text::Discard => "Verwerfen",
+        Text::FoundAudioFiles => "{} Audiodatei(en) in {} gefunden.",
+        Text::OpenFolderFailed => "Ordner konnte nicht geöffnet werden: {}",
+        Text::ClipLoadFailed => "Clip konnte nicht geladen werden: {}",
+        Text::ClipLoaded => "{} geladen ({} Hz, {}). Die ersten {} ms liegen jetzt auf C3–C6.",
+        Text::Stereo => "stereo",
+        Text::Mono => "mono",
+        Text::BaseNoteFromFile => " Grundton aus den Sampler-Metadaten der Datei auf {} gesetzt.",
+        Text::LoopFramesStatus => " Schleifen-Frames {}–{}.",
+        Text::LevelStatus => " Pegel {} dBFS RMS.",
+        Text::DecodedPartly => " {} von {} ms dekodiert; der Rest ist Stille.",
+        Text::SkippedPackets => " {} beschädigte(s) Paket(e) übersprungen.",
+        Text::PreviewFailed => "Vorhören fehlgeschlagen: {}",
+        Text::PlaybackError => "Wiedergabefehler: {}",
+        Text::MidiOutError => "MIDI-Ausgabefehler: {}",
+        Text::RoundRobinRemoved => "Round-Robin-Clip {} entfernt: {}",
+        Text::PerformanceModeOff => "Performance-Modus aus: {}",
+        Text::ResampledBase => "Grundton in den Ausschnitt gerendert. Effekte, Verstimmung und Transponierung wurden zurückgesetzt, damit sie nicht doppelt wirken; „Original wiederherstellen“ holt alles zurück.",
+        Text::OscListening => "Empfange OSC auf {}",
+        Text::OscError => "OSC-Fehler: {}",
+        Text::OscUnknownParam => "Unbekannter OSC-Parameter /param/{}",
+        Text::MidiInError => "MIDI-Eingangsfehler: {}",
+        Text::MidiInListening => "Empfange Programmwechsel auf {}",
+        Text::ProgramChangeIgnored => "Programmwechsel {} ignoriert: nur {} Preset(s) geladen.",
+        Text::MidiOutSending => "Sende MIDI an {}",
+        Text::FullFileFailed => "Die vollständige Datei konnte nicht geladen werden: {}",
+        Text::NudgeNeedsFile => "Zum Verschieben des Ausschnittstarts muss eine Datei geladen sein.",
+        Text::NoTransients => "Keine Transienten gefunden; erhöhe die Empfindlichkeit und versuche es erneut.",
+        Text::FoundTransients => "{} Transient(en) gefunden; jeder Abschnitt liegt auf einer eigenen Taste.",
+        Text::FilterRecorded => "{} Filterpunkte über {} s aufgenommen.",
+        Text::LatencySwitched => "Audioausgabe in den Modus {} geschaltet.",
+        Text::LatencySwitchFailed => "Umschalten auf {} fehlgeschlagen: {}",
+        Text::PitchUnclear => "Tonhöhe unklar (~{} Hz, Sicherheit {} %); Grundton unverändert.",
+        Text::PitchDetected => "{} Hz erkannt (Sicherheit {} %). Grundton auf {} gesetzt.",
+        Text::ScratchWavOff => "Scratch-WAV ausgeschaltet: {}",
+        Text::WaveformImageFailed => "Wellenformbild konnte nicht gespeichert werden: {}",
+        Text::NoScreenshot => "die Anzeige hat keinen Screenshot geliefert",
+        Text::WaveformImageSaved => "Wellenformbild {} gespeichert",
+        Text::LatencyStable => "Stabil",
+        Text::LatencyLow => "Niedrige Latenz",
+        Text::PlayOneShot => "One-Shot",
+        Text::PlayGate => "Gate",
+        Text::ChordMajor => "Dur",
+        Text::ChordMinor => "Moll",
+        Text::ChordDominant7 => "7",
+        Text::ChordMajor7 => "Maj7",
+        Text::ChordMinor7 => "m7",
+        Text::RoundRobinSequential => "Der Reihe nach",
+        Text::RoundRobinRandom => "Zufällig",
+        Text::CueOff => "Aus",
+        Text::CueSingle => "Einzelner Cue",
+        Text::CueKit => "Kit",
+        Text::ScaleOff => "Aus",
+        Text::ScaleMajor => "Dur",
+        Text::ScaleMinor => "Natürliches Moll",
+        Text::ScaleMajorPentatonic => "Dur-Pentatonik",
+        Text::ScaleMinorPentatonic => "Moll-Pentatonik",
+        Text::AccidentalsSharps => "Kreuze (Cis)",
+        Text::AccidentalsFlats => "Bs (Des)",
+        Text::AccidentalsFollowKey => "Nach Tonart",
+        Text::NoteLabelsNames => "Namen",
+        Text::NoteLabelsNumbers => "MIDI-Nummern",
+        Text::NoteLabelsBoth => "Beides",
+        Text::NamingLetters => "Buchstaben",
+        Text::NamingFixedDo => "Feste Do",
+        Text::NamingMovableDo => "Bewegliche Do",
+        Text::LayerSum => "Schichten (Summe)",
+        Text::LayerConcatenate => "Aneinanderhängen",
+        Text::WaveformClassic => "Klassisch (Standard)",
+        Text::WaveformSine => "Sinus",
+        Text::WaveformSquare => "Rechteck",
+        Text::WaveformSaw => "Sägezahn",
+        Text::WaveformTriangle => "Dreieck",
+        Text::CurveLinear => "Linear",
+        Text::CurveExponential => "Exponentiell",
+        Text::LfoSine => "Sinus",
+        Text::LfoSquare => "Rechteck",
+        Text::DriveSoftClip => "Weiches Clipping",
+        Text::DriveHardClip => "Hartes Clipping",
+        Text::DriveAsymmetric => "Asymmetrisch",
+        Text::InterpNearest => "Nächster Wert",
+        Text::InterpLinear => "Linear",
+        Text::InterpCubic => "Kubisch",
+        Text::RawU8 => "8 Bit ohne Vorzeichen",
+        Text::RawS16 => "16 Bit mit Vorzeichen",
+        Text::RawS24 => "24 Bit mit Vorzeichen",
+        Text::RawS32 => "32 Bit mit Vorzeichen",
+        Text::RawF32 => "32 Bit Gleitkomma",
+        Text::TrackLabel => "Spur {}: {}",
+        Text::DefaultTrack => "Standard",
+        Text::AudioTrack => "Audiospur",
+        Text::FallbackSynth => "Kein Clip geladen; die Tasten spielen einen eingebauten Sinus, bis einer geladen ist.",
+        Text::BiteStart => "Ausschnittstart: {} ms (Frame {})",
+        Text::NudgeEarlier => "Start früher setzen (Umschalt+Links)",
+        Text::NudgeLater => "Start später setzen (Umschalt+Rechts)",
+        Text::NudgeStep => "Schritt ",
+        Text::NudgeSamples => " Samples",
+        Text::Transients => "Transienten",
+        Text::CueMarkers => "Cue-Marker",
+        Text::CueLabel => "Cue {} ({} s)",
+        Text::CueModeHint => "Aus spielt den Anfang der Datei. Einzelner Cue legt den Abschnitt eines Cues über die ganze Tastatur; Kit legt ab dem Grundton je einen Abschnitt auf jede Taste.",
+        Text::DecodeUpTo => "Dekodiere bis zu ",
+        Text::DecodeUpToHint => "Wie viel der Datei Scrubbing, Cues, Verschieben und Transienten-Schnitt im Speicher halten",
+        Text::FileLonger => "Die Datei ist länger; nur die ersten {} s sind zwischengespeichert.",
+        Text::ScrubPosition => "{} s von {} s",
+        Text::StopSpace => "Stopp (Leertaste)",
+        Text::PlaySpace => "Abspielen (Leertaste)",
+        Text::Sensitivity => "Empfindlichkeit",
+        Text::SensitivityHint => "Höhere Werte finden leisere Anschläge, niedrigere nur die lautesten",
+        Text::AutoSlice => "Automatisch an Transienten schneiden",
+        Text::AutoSliceHint => "Schneidet die Datei an jedem erkannten Anschlag und legt die Abschnitte auf Tasten",
+        Text::UseCueMarkers => "Cue-Marker verwenden",
+        Text::PlayFromScrub => "Ab Scrub-Position spielen",
+        Text::PlayFromScrubHint => "Die Tasten spielen die Datei ab der Position unten statt des Ausschnitts",
+        Text::SnapshotName => "Name",
+        Text::StoreEffects => "Aktuelle Effekte speichern",
+        Text::SnapshotDefaultName => "Snapshot {}",
+        Text::RecallStopsNotes => "Abruf stoppt Noten",
+        Text::RecallStopsNotesHint => "Klingende Noten verstummen, wenn ein Snapshot abgerufen wird",
+        Text::RecallKey => "Abrufen (Taste {})",
+        Text::Update => "Aktualisieren",
+        Text::UpdateHint => "Mit den aktuellen Effekten überschreiben",
+        Text::Remove => "Entfernen",
+        Text::RoundRobin => "Round Robin",
+        Text::AddClip => "Clip hinzufügen ...",
+        Text::AddClipHint => "Wechselt bei wiederholten Noten zwischen diesem Clip und dem Hauptclip",
+        Text::LoadingShort => "lädt",
+        Text::AddLayer => "Schicht hinzufügen ...",
+        Text::DragToReorder => "Zum Umsortieren ziehen",
+        Text::RemoveLayer => "Schicht entfernen",
+        Text::MuteLayer => "Diese Schicht stummschalten",
+        Text::SoloLayer => "Solo: nur Solo-Schichten sind zu hören",
+        Text::ChordMode => "Akkordmodus",
+        Text::ChordModeHint => "Jede Taste spielt einen ganzen Akkord mit ihr als Grundton",
+        Text::ChordRoot => "Grundton",
+        Text::PlayOnRoot => "Auf dem Grundton spielen (Taste {})",
+        Text::Stop => "Stopp",
+        Text::Play => "Abspielen",
+        Text::AddRow => "Zeile hinzufügen",
+        Text::Clear => "Leeren",
+        Text::SixteenthsAt => "16tel bei {} BPM",
+        Text::RemoveRow => "Zeile entfernen",
+        Text::ImportRawTitle => "Roh-PCM importieren",
+        Text::SampleRate => "Abtastrate",
+        Text::SampleFormat => "Sampleformat",
+        Text::Channels => "Kanäle",
+        Text::ByteOrder => "Bytereihenfolge",
+        Text::LittleEndian => "Little-Endian",
+        Text::BigEndian => "Big-Endian",
+        Text::Import => "Importieren",
+        Text::ExportInstrumentHint => "Rendert jede Taste als WAV-Datei mit einer .sfz-Zuordnung für andere Sampler",
+        Text::ResampleToBite => "In den Ausschnitt rendern",
+        Text::ResampleToBiteHint => "Ersetzt den Ausschnitt durch den Grundton, wie er jetzt klingt, samt Effekten und Tonhöhe. Destruktiv: Das Original lässt sich nur wiederherstellen, bis ein anderer Clip geladen wird.",
+        Text::RestoreOriginal => "Original wiederherstellen",
+        Text::RestoreOriginalHint => "Zurück zum Clip und den Einstellungen von vor dem Rendern",
+        Text::ScratchWav => "Ausschnitt in Scratch-WAV spiegeln",
+        Text::ScratchWavHint => "Schreibt bei jeder Änderung des Ausschnitts eine feste WAV-Datei neu, für Werkzeuge, die sie beobachten",
+        Text::CopyPath => "Pfad kopieren",
+        Text::NoZones => "Ohne Zonen spielt jede Taste als One-Shot.",
+        Text::ZoneTo => "bis",
+        Text::AddZone => "Zone hinzufügen",
+        Text::AddZoneHint => "Gate-Zonen stoppen beim Loslassen; One-Shot-Zonen spielen bis zum Ende",
+        Text::PreserveStereo => "Stereo beibehalten",
+        Text::RemoveDc => "DC-Versatz entfernen",
+        Text::RemoveDcHint => "Zieht den Mittelwert des Clips ab, damit eine verschobene Aufnahme um null zentriert ist",
+        Text::FillLoop => "Durch Schleifen auffüllen",
+        Text::FillLoopHint => "Ist der Clip kürzer als der Ausschnitt, wird er mit Überblendungen wiederholt statt mit Stille aufgefüllt",
+        Text::AutoLevel => "Clips automatisch angleichen",
+        Text::AutoLevelHint => "Gleicht die Wiedergabelautstärke der Clips anhand des gemessenen RMS-Pegels an",
+        Text::BiteGainComp => "Pegel über Ausschnittlängen halten",
+        Text::BiteGainCompHint => "Misst die Lautheit bei jedem Neuschnitt neu und hält sie auf dem Pegel beim Laden (automatisches Angleichen tut das bereits)",
+        Text::AutoPreview => "Automatisch vorhören",
+        Text::AutoPreviewHint => "Spielt den Ausschnitt einmal, sobald ein Clip geladen oder neu geschnitten wird",
+        Text::ReverseAttack => "Rückwärts-Anschlag",
+        Text::ReverseAttackHint => "Spielt den Anfang des Ausschnitts rückwärts in den Anschlag hinein",
+        Text::ReverseLength => "Rückwärtslänge",
+        Text::BaseNote => "Grundton",
+        Text::BaseNoteHint => "Die Taste, die den Ausschnitt in seiner natürlichen Tonhöhe spielt",
+        Text::DetectPitch => "Tonhöhe erkennen",
+        Text::DetectPitchHint => "Schätzt den Grundton des Clips und setzt ihn",
+        Text::StopReference => "Referenz stoppen",
+        Text::PlayReference => "Referenz spielen",
+        Text::ReferenceHint => "Hält einen reinen Sinus auf A4 zum Stimmen (Esc stoppt ihn)",
+        Text::Detune => "Verstimmung",
+        Text::Transpose => "Transponieren",
+        Text::TransposeHint => "Verschiebt jede Note, ohne die gedrückten Tasten zu ändern",
+        Text::Interpolation => "Interpolation",
+        Text::InterpolationHint => "Wie transponierte Noten umgerechnet werden. Nächster Wert ist am günstigsten, erzeugt aber Aliasing, Linear ist ein guter Standard, Kubisch klingt am saubersten und kostet pro Stimme die meiste CPU.",
+        Text::PerformanceMode => "Performance-Modus",
+        Text::PerformanceModeHint => "Berechnet den Ausschnitt für jede Taste vorab, damit Noten ohne Umrechnung starten. Braucht Speicher für 37 Kopien und rendert nach jeder Clip- oder Stimmungsänderung neu.",
+        Text::Caching => "Zwischenspeichern {}/{}",
+        Text::AllKeysCached => "Alle Tasten zwischengespeichert",
+        Text::Pan => "Panorama (L/R)",
+        Text::RandomPan => "Zufälliges Panorama pro Note",
+        Text::StereoWidth => "Stereobreite",
+        Text::StereoWidthHint => "0 % ist mono, 100 % das Originalbild, über 100 % wird per Mitte/Seite verbreitert",
+        Text::StereoWidthDisabled => "Lade einen Stereo-Clip mit aktiviertem „Stereo beibehalten“",
+        Text::HoldVelocity => "Anschlagstärke aus Haltedauer",
+        Text::HoldVelocityHint => "Kurzes Tippen macht die nächste Note laut, langes Halten leise",
+        Text::NextVelocity => "Nächste Anschlagstärke: {} %",
+        Text::Glissando => "Glissando",
+        Text::GlissandoHint => "Über das Klavier ziehen spielt jede überstrichene Taste",
+        Text::DragBend => "Ziehen zum Biegen",
+        Text::DragBendHint => "Auf einer gehaltenen Taste nach oben oder unten ziehen biegt ihre Tonhöhe; bei aktivem Glissando ignoriert",
+        Text::BendRange => "Biegebereich",
+        Text::BendAmount => "Biegung {} HT",
+        Text::BlackKeyArea => "Tastfläche schwarzer Tasten",
+        Text::BlackKeyAreaHint => "Macht schwarze Tasten auf Touchscreens leichter treffbar; sie reichen weiter in die weißen Tasten, als sie gezeichnet sind",
+        Text::Humanize => "Humanisieren",
+        Text::HumanizeHint => "Variiert Tonhöhe, Timing, Pegel und Panorama jeder Note leicht",
+        Text::Amount => "Stärke",
+        Text::Seed => "Startwert",
+        Text::Restart => "Neu starten",
+        Text::RestartHumanizeHint => "Spielt dieselben Variationen von vorn",
+        Text::StartJitter => "Startversatz",
+        Text::StartJitterHint => "Startet jede Note bis zu so viel später in der Datei, damit wiederholte Anschläge verschieden klingen",
+        Text::SeedPrefix => "Startwert ",
+        Text::RestartJitterHint => "Spielt dieselben Startversätze von vorn",
+        Text::Quantize => "Auslöser quantisieren",
+        Text::Grid => "Raster",
+        Text::Tap => "Tippen",
+        Text::TapHint => "Den Takt tippen, um die BPM zu setzen (oder {} drücken)",
+        Text::TapKey => "Taste {}",
+        Text::TapBpm => "{} BPM aus {} Anschlägen",
+        Text::KeepTapping => "Weiter tippen ...",
+        Text::FreezeHeld => "Gehaltene Noten einfrieren",
+        Text::FreezeHeldHint => "Gehaltene Tasten wiederholen winzige Körner aus dem Ausschnitt bis zum Loslassen",
+        Text::GrainSize => "Korngröße",
+        Text::PositionInBite => "Position im Ausschnitt",
+        Text::BypassEffects => "Alle Effekte umgehen",
+        Text::BypassEffectsHint => "Trocken spielen, ohne Effekteinstellungen zu verlieren; das Kästchen jedes Effekts umgeht nur diesen",
+        Text::DryMatch => "trocken {} dB zum Angleichen",
+        Text::ActiveSnapshot => "Snapshot: {}",
+        Text::CrushNotes => "Noten zerkleinern",
+        Text::CrushNotesHint => "Quantisiert die Amplitude und hält Samples für einen Lo-Fi-Klang",
+        Text::BitDepth => "Bittiefe",
+        Text::Downsample => "Heruntertakten",
+        Text::DryWet => "Trocken/Nass",
+        Text::OverdriveNotes => "Noten übersteuern",
+        Text::OverdriveNotesHint => "Waveshaping-Verzerrung; der Pegel bleibt gleich, wenn der Drive steigt",
+        Text::Curve => "Kurve",
+        Text::Drive => "Drive",
+        Text::OutputLevel => "Ausgangspegel",
+        Text::ReverbHint => "Raum im Freeverb-Stil; Fahnen klingen nach dem Loslassen aus",
+        Text::RoomSize => "Raumgröße",
+        Text::Damping => "Dämpfung",
+        Text::Enable => "Aktivieren",
+        Text::Cutoff => "Grenzfrequenz",
+        Text::Resonance => "Resonanz",
+        Text::Recording => "Aufnahme läuft",
+        Text::Armed => "Bereit",
+        Text::Record => "Aufnehmen",
+        Text::RecordHint => "Scharf schalten, eine Note spielen, dann die Grenzfrequenz ziehen; erneut klicken zum Stoppen",
+        Text::PlayAutomation => "Automation abspielen",
+        Text::AutomationPoints => "{} Punkte, {} s",
+        Text::AutoWahHint => "Lauteres Spiel öffnet den Filter, leiseres schließt ihn",
+        Text::Low => "Tief",
+        Text::High => "Hoch",
+        Text::Attack => "Attack",
+        Text::SyncToBpm => "Mit BPM synchronisieren",
+        Text::Rate => "Rate",
+        Text::Depth => "Tiefe",
+        Text::TrimHint => "Rechtsklick auf eine Klaviertaste passt ihren Pegel an.",
+        Text::AttackFade => "Einblendzeit",
+        Text::AttackFadeHint => "Blendet jede Note aus der Stille ein, damit Clips, die nicht bei null beginnen, nicht knacken; 0 schaltet es aus",
+        Text::ReleaseTime => "Ausklingzeit",
+        Text::ReleaseTimeHint => "Ausblendlänge, wenn eine gehaltene Note losgelassen wird",
+        Text::Smoothing => "Glättung",
+        Text::SmoothingHint => "Kürzeste Blende, wo immer eine Stimme abgeschnitten wird: Neuanschlag, Stimmenklau, Note-Off und Alle Noten aus",
+        Text::LatencyHint => "Stabil nutzt den Standardpuffer des Geräts und stockt selten. Niedrige Latenz fordert einen Puffer von 256 Frames an, damit die Tasten schneller reagieren, doch ausgelastete Systeme können knacksen oder aussetzen.",
+        Text::Threshold => "Schwelle",
+        Text::MakeupGain => "Aufholverstärkung",
+        Text::SendNotesTo => "Noten senden an",
+        Text::NoPort => "(kein Port)",
+        Text::Rescan => "Neu suchen",
+        Text::SwitchPresetsFrom => "Presets umschalten über",
+        Text::SwitchPresetsHint => "Programmwechsel 1 lädt das erste Preset der Preset-Liste, 2 das zweite und so weiter",
+        Text::ProgramPreset => "Programm {}: {}",
+        Text::ListenUdp => "Auf UDP-Port lauschen",
+        Text::AllowOtherDevices => "Andere Geräte zulassen",
+        Text::AllowOtherDevicesHint => "OSC aus dem ganzen lokalen Netz statt nur von diesem Computer annehmen",
+        Text::ListeningOn => "Lausche auf {}",
+        Text::Stopped => "Gestoppt",
+        Text::Waveform => "Wellenform",
+        Text::DefaultPreset => "Standardvorgabe",
+        Text::BaseFrequency => "Grundfrequenz",
+        Text::Harmonics => "Obertöne",
+        Text::ToneChangesHint => "Änderungen greifen, wenn der Testton verwendet wird.",
+        Text::OverrideRate => "Abtastrate überschreiben",
+        Text::OverrideRateHint => "Behandelt den Clip, als wäre er mit dieser Rate aufgenommen, für Dateien mit falschem Header",
+        Text::WaveformHint => "Ausschnitt: {} ms (scrollen zum Ändern, Umschalt für feine Schritte)",
+        Text::TestToneName => "Testton",
+        Text::WaveformCaption => "{}  ·  Ausschnitt {} ms ab {} ms  ·  Grundton {}  ·  {} Hz {}",
+        Text::PngImage => "PNG-Bild",
+        Text::SavePng => "PNG speichern ...",
+        Text::SavePngHint => "Speichert die Wellenformansicht als Bild, beschriftet mit den Ausschnitteinstellungen",
+        Text::Zoom => "Zoom",
+        Text::ZoomHint => "Wellenform zoomen (Tasten + / -)",
+        Text::LoopLabel => "Schleife: {} ms ({}–{})",
+        Text::ClearLoop => "Schleife entfernen",
+        Text::AddLoop => "Schleife hinzufügen",
+        Text::SnapZero => "An Nulldurchgängen einrasten",
+        Text::SnapZeroHint => "Setzt den Ausschnittstart und die Schleifengriffe auf den nächsten steigenden Nulldurchgang",
+        Text::Unfreeze => "Freigeben",
+        Text::FreezeScope => "Einfrieren",
+        Text::HighCpuEffects => "Hohe CPU-Last: jede Stimme hat ihre eigene Effektkette",
+        Text::HighCpu => "Hohe CPU-Last: auf langsameren Rechnern sind Aussetzer möglich",
+        Text::VoiceCount => "Stimmen: {}/{}",
+        Text::VoiceStolen => "Stimme geklaut",
+        Text::NoHeldVoices => "Keine gehaltenen Stimmen",
+        Text::Releasing => "+{} klingen aus",
+        Text::MaxVoices => "Max. Stimmen: ",
+        Text::MaxVoicesHint => "Wie viele Noten gleichzeitig klingen können, bevor die älteste geklaut wird; ihre Ausklänge können noch einmal so viele ausmachen",
+        Text::TransposeAmount => "Transponiert {} HT",
+        Text::PeakHint => "Spitze {} dBFS (Klick setzt zurück)",
+        Text::StereoMeter => "Stereo-Pegelanzeige",
+        Text::Compact => "Kompakt",
+        Text::CompactHint => "Blendet den Hilfetext aus und verschiebt Nebenregler in ein Menü",
+        Text::SoundBite => "Ausschnitt (ms)",
+        Text::MoreControls => "Weitere Regler",
+        Text::CloseFolder => "Ordner schließen",
+        Text::Previous => "Zurück",
+        Text::Next => "Weiter",
+        Text::BrowserEntryHint => "Halten zum Vorhören, klicken zum Laden",
+        Text::Piano => "Klavier (C3 → C6)",
+        Text::KeyLabels => "Tastenbeschriftung",
+        Text::MovableDoHint => "Bewegliche Do benennt Noten relativ zur Tonart, die für die Tonleiter-Hervorhebung gewählt ist",
+        Text::HighlightScale => "Tonleiter hervorheben",
+        Text::FollowKeyHint => "„Nach Tonart“ schreibt Noten in F-, B-, Es-, As- und Des-Dur und ihren Paralleltonarten mit Bs",
+        Text::UsingTestTone => "Erzeugter Testton aktiv. Lade einen Clip, um ihn zu ersetzen.",
+        Text::Shortcuts => "Tastenkürzel: A W S E D F T G Y H U J K · Strg+Z / Strg+Y Ausschnittlänge rückgängig/wiederholen · Strg+T Testton",
+        Text::OpenFromUrl => "Von URL öffnen",
+        Text::Load => "Laden",
+        Text::TrimNote => "Pegel {}",
+        Text::Reset => "Zurücksetzen",
+    }
+}
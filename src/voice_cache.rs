@@ -0,0 +1,184 @@
+use std::{
+    collections::HashMap,
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use anyhow::{anyhow, Result};
+
+use crate::{clip::SampleClip, dsp, render, NoteParams};
+
+// Worst case is a 5 s stereo 192 kHz bite spread over 37 keys, well past this.
+pub const MAX_CACHE_BYTES: usize = 256 * 1024 * 1024;
+
+// What the cached buffers were rendered from; any change makes them stale. Holding the
+// clip's samples also keeps their address from being reused by a different clip.
+#[derive(Clone)]
+struct CacheSpec {
+    samples: Arc<Vec<f32>>,
+    channels: u16,
+    sample_rate: u32,
+    base_midi: i32,
+    detune_cents: f32,
+    transpose: i32,
+    interp: dsp::InterpQuality,
+}
+
+impl CacheSpec {
+    fn new(clip: &SampleClip, params: &NoteParams) -> Self {
+        Self {
+            samples: Arc::clone(&clip.samples),
+            channels: clip.channels,
+            sample_rate: clip.sample_rate,
+            base_midi: params.base_midi,
+            detune_cents: params.detune_cents,
+            transpose: params.transpose,
+            interp: params.interp,
+        }
+    }
+
+    fn matches(&self, clip: &SampleClip, params: &NoteParams) -> bool {
+        Arc::ptr_eq(&self.samples, &clip.samples)
+            && self.base_midi == params.base_midi
+            && self.detune_cents == params.detune_cents
+            && self.transpose == params.transpose
+            && self.interp == params.interp
+    }
+
+    fn render(&self, midi: i32) -> Vec<f32> {
+        let params = NoteParams {
+            base_midi: self.base_midi,
+            detune_cents: self.detune_cents,
+            transpose: self.transpose,
+            ..NoteParams::default()
+        };
+        dsp::Resampler::new(
+            Arc::clone(&self.samples),
+            self.channels,
+            self.sample_rate,
+            render::pitch_ratio(midi, &params),
+            self.interp,
+        )
+        .collect()
+    }
+
+    fn rendered_len(&self, midi: i32) -> usize {
+        let params = NoteParams {
+            base_midi: self.base_midi,
+            detune_cents: self.detune_cents,
+            transpose: self.transpose,
+            ..NoteParams::default()
+        };
+        let frames = self.samples.len() / usize::from(self.channels.max(1));
+        let ratio = f64::from(render::pitch_ratio(midi, &params).max(0.01));
+        (frames as f64 / ratio).ceil() as usize * usize::from(self.channels.max(1))
+    }
+}
+
+pub struct VoiceCache {
+    spec: CacheSpec,
+    voices: HashMap<i32, Arc<Vec<f32>>>,
+}
+
+impl VoiceCache {
+    pub fn matches(&self, clip: &SampleClip, params: &NoteParams) -> bool {
+        self.spec.matches(clip, params)
+    }
+
+    // The pre-pitched buffer for `midi`, if it was rendered from this clip and tuning.
+    pub fn voice(
+        &self,
+        clip: &SampleClip,
+        midi: i32,
+        params: &NoteParams,
+    ) -> Option<Arc<Vec<f32>>> {
+        if !self.matches(clip, params) {
+            return None;
+        }
+        self.voices.get(&midi).cloned()
+    }
+}
+
+pub struct PendingCache {
+    spec: CacheSpec,
+    pub total: usize,
+    progress: Arc<AtomicUsize>,
+    receiver: mpsc::Receiver<Result<HashMap<i32, Arc<Vec<f32>>>>>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PendingCache {
+    pub fn spawn(clip: &SampleClip, params: &NoteParams, keys: RangeInclusive<i32>) -> Self {
+        let spec = CacheSpec::new(clip, params);
+        let total = keys.clone().count();
+        let progress = Arc::new(AtomicUsize::new(0));
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let worker_spec = spec.clone();
+        let worker_progress = Arc::clone(&progress);
+        let worker_cancelled = Arc::clone(&cancelled);
+        thread::spawn(move || {
+            let bytes: usize = keys
+                .clone()
+                .map(|midi| worker_spec.rendered_len(midi) * std::mem::size_of::<f32>())
+                .sum();
+            if bytes > MAX_CACHE_BYTES {
+                let _ = sender.send(Err(anyhow!(
+                    "the clip needs {} MB of cache, more than the {} MB limit",
+                    bytes / (1024 * 1024),
+                    MAX_CACHE_BYTES / (1024 * 1024)
+                )));
+                return;
+            }
+            let mut voices = HashMap::new();
+            for midi in keys {
+                if worker_cancelled.load(Ordering::Relaxed) {
+                    return;
+                }
+                voices.insert(midi, Arc::new(worker_spec.render(midi)));
+                worker_progress.fetch_add(1, Ordering::Relaxed);
+            }
+            let _ = sender.send(Ok(voices));
+        });
+
+        Self {
+            spec,
+            total,
+            progress,
+            receiver,
+            cancelled,
+        }
+    }
+
+    pub fn matches(&self, clip: &SampleClip, params: &NoteParams) -> bool {
+        self.spec.matches(clip, params)
+    }
+
+    pub fn progress(&self) -> usize {
+        self.progress.load(Ordering::Relaxed)
+    }
+
+    pub fn poll(&self) -> Option<Result<VoiceCache>> {
+        match self.receiver.try_recv() {
+            Ok(result) => Some(result.map(|voices| VoiceCache {
+                spec: self.spec.clone(),
+                voices,
+            })),
+            Err(mpsc::TryRecvError::Empty) => None,
+            Err(mpsc::TryRecvError::Disconnected) => {
+                Some(Err(anyhow!("voice cache worker stopped unexpectedly")))
+            }
+        }
+    }
+}
+
+impl Drop for PendingCache {
+    fn drop(&mut self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}